@@ -0,0 +1,38 @@
+use execution_fuzz::run;
+use rand::Rng;
+use std::{env, fs};
+
+/// Fuzz the command API of the order book against the reference book
+///
+/// Each argument is a corpus file to replay, e.g. inputs produced by a fuzzer or
+/// crashes to reproduce. Arguments starting with `-`, such as fuzzer flags, are
+/// ignored. Without corpus files, random inputs are generated until the process is
+/// interrupted or an invariant fails.
+fn main() {
+    let paths: Vec<String> = (env::args().skip(1))
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    if !paths.is_empty() {
+        for path in paths {
+            let data = fs::read(&path).unwrap_or_else(|err| panic!("{path}: {err}"));
+            run(&data);
+        }
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    for iteration in 0u64.. {
+        let len = rng.gen_range(0..4_096);
+        let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let outcome = std::panic::catch_unwind(|| run(&data));
+        if outcome.is_err() {
+            let path = format!("crash-{iteration}");
+            fs::write(&path, &data).expect("failed to save the crashing input");
+            eprintln!("saved the crashing input to {path}");
+            std::process::exit(1);
+        }
+        if iteration % 10_000 == 0 {
+            eprintln!("{iteration} inputs");
+        }
+    }
+}
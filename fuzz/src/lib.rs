@@ -0,0 +1,220 @@
+use execution::{CancelResult, OrderBook, OrderId, OrderQty, OrderStatus, OwnerId, Price, Side};
+
+pub mod reference;
+
+pub use reference::ReferenceBook;
+
+/// Number of bytes a command is decoded from
+const COMMAND_SIZE: usize = 5;
+
+/// Operation applied to the order book by the fuzz targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Rest an order without matching it
+    Add {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+    },
+
+    /// Match a limit order, resting its remainder
+    Execute {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Limit price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+    },
+
+    /// Cancel one of the orders created so far, which may no longer be resting
+    Cancel {
+        /// Index of the order among the orders created so far
+        order: usize,
+    },
+
+    /// Cancel every resting order of an owner
+    CancelAll {
+        /// Owner whose orders to cancel
+        owner: OwnerId,
+    },
+}
+
+/// Decode arbitrary bytes into a sequence of commands
+///
+/// Every five bytes make a command. Owners, prices and quantities are drawn from small
+/// ranges so that orders often collide on the same levels and cross each other.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to decode, trailing bytes being ignored
+///
+/// # Returns
+///
+/// The decoded commands
+pub fn decode(data: &[u8]) -> Vec<Command> {
+    data.chunks_exact(COMMAND_SIZE)
+        .map(|bytes| {
+            let owner = (bytes[1] % 4) as OwnerId;
+            let side = match bytes[2] & 1 {
+                0 => Side::Bid,
+                _ => Side::Ask,
+            };
+            let price = 100 + (bytes[3] % 16) as Price;
+            let qty = 1 + (bytes[4] % 32) as OrderQty;
+            match bytes[0] % 8 {
+                0..=2 => Command::Add {
+                    owner,
+                    side,
+                    price,
+                    qty,
+                },
+                3..=5 => Command::Execute {
+                    owner,
+                    side,
+                    price,
+                    qty,
+                },
+                6 => Command::Cancel {
+                    order: bytes[1] as usize,
+                },
+                _ => Command::CancelAll { owner },
+            }
+        })
+        .collect()
+}
+
+/// Apply commands to an order book and the reference book, checking invariants after
+/// every step
+///
+/// # Arguments
+///
+/// * `data` - The bytes to decode into commands
+///
+/// # Panics
+///
+/// If the order book diverges from the reference book or breaks an invariant
+pub fn run(data: &[u8]) {
+    let mut book = OrderBook::new();
+    let mut reference = ReferenceBook::new();
+    let mut created: Vec<OrderId> = Vec::new();
+
+    for (step, command) in decode(data).into_iter().enumerate() {
+        match command {
+            Command::Add {
+                owner,
+                side,
+                price,
+                qty,
+            } => {
+                let id = book.add(owner, side, price, qty).unwrap();
+                reference.add(id, owner, side, price, qty);
+                created.push(id);
+            }
+            Command::Execute {
+                owner,
+                side,
+                price,
+                qty,
+            } => {
+                let result = book.execute(owner, side, price, qty);
+                let (fills, remaining) = reference.execute(side, price, qty);
+                assert_eq!(result.orders, fills, "fills of step {step}: {command:?}");
+                assert_eq!(result.remaining, remaining, "step {step}: {command:?}");
+                let status = match (fills.is_empty(), remaining) {
+                    (_, 0) => OrderStatus::Filled,
+                    (true, _) => OrderStatus::Created,
+                    (false, _) => OrderStatus::PartiallyFilled,
+                };
+                assert_eq!(result.status, status, "step {step}: {command:?}");
+                if let Some(id) = result.id {
+                    reference.add(id, owner, side, price, remaining);
+                    created.push(id);
+                }
+            }
+            Command::Cancel { order } => {
+                if created.is_empty() {
+                    continue;
+                }
+                let id = created[order % created.len()];
+                let canceled = book.cancel(id) != CancelResult::NotFound;
+                assert_eq!(canceled, reference.cancel(id), "step {step}: {command:?}");
+            }
+            Command::CancelAll { owner } => {
+                let canceled = book.cancel_all(owner);
+                assert_eq!(
+                    canceled,
+                    reference.cancel_all(owner),
+                    "step {step}: {command:?}"
+                );
+            }
+        }
+        check(&book, &reference, step);
+    }
+}
+
+/// Check the order book against the reference book
+fn check(book: &OrderBook, reference: &ReferenceBook, step: usize) {
+    let snapshot = book.snapshot();
+    assert_eq!(
+        snapshot.bids,
+        reference.levels(Side::Bid),
+        "bids after step {step}"
+    );
+    assert_eq!(
+        snapshot.asks,
+        reference.levels(Side::Ask),
+        "asks after step {step}"
+    );
+    for (side, levels) in [(Side::Bid, &snapshot.bids), (Side::Ask, &snapshot.asks)] {
+        for (price, qty) in levels {
+            assert_eq!(book.get_total_qty(side, *price), *qty, "step {step}");
+        }
+    }
+
+    let bbo = book.bbo();
+    assert_eq!(bbo.bid, snapshot.bids.first().copied(), "step {step}");
+    assert_eq!(bbo.ask, snapshot.asks.first().copied(), "step {step}");
+    assert_eq!(
+        book.depth(3).bids,
+        snapshot.bids[..snapshot.bids.len().min(3)]
+    );
+    assert_eq!(
+        book.depth(3).asks,
+        snapshot.asks[..snapshot.asks.len().min(3)]
+    );
+
+    let stats = book.memory_stats();
+    assert_eq!(stats.resting_orders, reference.len(), "step {step}");
+    assert_eq!(stats.used_levels, snapshot.bids.len() + snapshot.asks.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_random_commands() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            let len = rng.gen_range(0..2_000);
+            let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            run(&data);
+        }
+    }
+}
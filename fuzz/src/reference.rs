@@ -0,0 +1,159 @@
+use execution::{OrderId, OrderQty, OwnerId, Price, Side};
+
+/// Order resting in the reference book
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    /// Identifier given by the order book under test
+    id: OrderId,
+
+    /// Owner of the order
+    owner: OwnerId,
+
+    /// Side of the order
+    side: Side,
+
+    /// Price of the order
+    price: Price,
+
+    /// Remaining quantity of the order
+    qty: OrderQty,
+
+    /// Arrival sequence number, for time priority
+    seq: u64,
+}
+
+/// Naive order book used as the oracle of the fuzz targets
+///
+/// Orders are kept in a flat vector and every operation scans all of them, trading
+/// speed for an implementation simple enough to be obviously correct.
+#[derive(Debug, Default)]
+pub struct ReferenceBook {
+    /// Resting orders, in no particular order
+    orders: Vec<RestingOrder>,
+
+    /// Sequence number of the next order
+    next_seq: u64,
+}
+
+impl ReferenceBook {
+    /// Create a new, empty, reference book
+    pub fn new() -> ReferenceBook {
+        ReferenceBook::default()
+    }
+
+    /// Rest an order without matching it
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier given by the order book under test
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The price of the order
+    /// * `qty` - The quantity of the order
+    pub fn add(&mut self, id: OrderId, owner: OwnerId, side: Side, price: Price, qty: OrderQty) {
+        self.orders.push(RestingOrder {
+            id,
+            owner,
+            side,
+            price,
+            qty,
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Match a limit order against the book in price-time priority
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The fills as (price, quantity) and the quantity left unfilled
+    pub fn execute(
+        &mut self,
+        side: Side,
+        price: Price,
+        mut qty: OrderQty,
+    ) -> (Vec<(Price, OrderQty)>, OrderQty) {
+        let mut fills = Vec::new();
+        while qty > 0 {
+            let best = (self.orders.iter().enumerate())
+                .filter(|(_, o)| o.side != side)
+                .filter(|(_, o)| match side {
+                    Side::Bid => o.price <= price,
+                    Side::Ask => o.price >= price,
+                })
+                .min_by_key(|(_, o)| match side {
+                    Side::Bid => (o.price, o.seq),
+                    Side::Ask => (Price::MAX - o.price, o.seq),
+                })
+                .map(|(idx, _)| idx);
+            let Some(idx) = best else { break };
+            let maker = &mut self.orders[idx];
+            let fill = maker.qty.min(qty);
+            maker.qty -= fill;
+            qty -= fill;
+            fills.push((maker.price, fill));
+            if maker.qty == 0 {
+                self.orders.remove(idx);
+            }
+        }
+        (fills, qty)
+    }
+
+    /// Cancel a resting order
+    ///
+    /// # Returns
+    ///
+    /// Whether the order was resting
+    pub fn cancel(&mut self, id: OrderId) -> bool {
+        let before = self.orders.len();
+        self.orders.retain(|o| o.id != id);
+        self.orders.len() != before
+    }
+
+    /// Cancel every resting order of an owner
+    ///
+    /// # Returns
+    ///
+    /// The number of orders canceled
+    pub fn cancel_all(&mut self, owner: OwnerId) -> usize {
+        let before = self.orders.len();
+        self.orders.retain(|o| o.owner != owner);
+        before - self.orders.len()
+    }
+
+    /// Get the number of resting orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Check whether no order is resting
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Aggregate the resting orders of a side by price
+    ///
+    /// # Returns
+    ///
+    /// The price and total quantity of each level, best first
+    pub fn levels(&self, side: Side) -> Vec<(Price, OrderQty)> {
+        let mut levels: Vec<(Price, OrderQty)> = Vec::new();
+        for order in self.orders.iter().filter(|o| o.side == side) {
+            match levels.iter_mut().find(|(price, _)| *price == order.price) {
+                Some((_, qty)) => *qty += order.qty,
+                None => levels.push((order.price, order.qty)),
+            }
+        }
+        levels.sort_by_key(|(price, _)| *price);
+        if side == Side::Bid {
+            levels.reverse();
+        }
+        levels
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{FillResult, OrderBook, OrderQty, OwnerId, Price, Side};
+
+/// Schedule on which an algo slices its parent order into child orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlgoStrategy {
+    /// Trade equal slices at evenly spaced times
+    Twap {
+        /// Time of the first slice
+        start: u64,
+
+        /// Time by which the parent order should be done, the last slice being sent
+        /// one interval before it
+        end: u64,
+
+        /// Number of slices
+        slices: u64,
+    },
+
+    /// Trade a fixed share of the volume traded by others since the algo started
+    Vwap {
+        /// Share of the market volume to trade, in percent
+        participation: u64,
+    },
+}
+
+/// Aggregated fills of the parent order of an algo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlgoReport {
+    /// Quantity filled by child orders
+    pub filled: OrderQty,
+
+    /// Average price of the fills, zero before the first fill
+    pub avg_price: f64,
+
+    /// Quantity of the parent order left to fill
+    pub remaining: OrderQty,
+
+    /// Number of child orders sent
+    pub children: u64,
+}
+
+/// Execution algo slicing a parent order into child orders sent to an order book
+///
+/// The algo is driven by polling it with the order book, which it reads the time from
+/// through the clock of the order book. Each child order is a limit order at the price
+/// of the parent whose unfilled remainder is canceled at once, the shortfall being
+/// carried over to the next child order.
+#[derive(Debug, Clone)]
+pub struct Algo {
+    /// Owner of the parent order
+    owner: OwnerId,
+
+    /// Side of the parent order
+    side: Side,
+
+    /// Limit price of the parent order
+    price: Price,
+
+    /// Quantity of the parent order
+    qty: OrderQty,
+
+    /// Slicing schedule
+    strategy: AlgoStrategy,
+
+    /// Quantity filled so far
+    filled: OrderQty,
+
+    /// Total price paid for the quantity filled so far
+    notional: u128,
+
+    /// Number of child orders sent
+    children: u64,
+
+    /// Number of TWAP slices that came due
+    slices_due: u64,
+
+    /// Volume of the order book when the algo started, for VWAP
+    start_volume: Option<OrderQty>,
+}
+
+impl Algo {
+    /// Create a new algo
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the parent order
+    /// * `side` - The side of the parent order
+    /// * `price` - The limit price of the parent order
+    /// * `qty` - The quantity of the parent order
+    /// * `strategy` - The slicing schedule
+    pub fn new(
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        strategy: AlgoStrategy,
+    ) -> Algo {
+        Algo {
+            owner,
+            side,
+            price,
+            qty,
+            strategy,
+            filled: 0,
+            notional: 0,
+            children: 0,
+            slices_due: 0,
+            start_volume: None,
+        }
+    }
+
+    /// Get the time of the next TWAP slice
+    ///
+    /// # Returns
+    ///
+    /// The time the algo should next be polled at, `None` for VWAP algos, which should be
+    /// polled after trades, and for TWAP algos with no slices left
+    pub fn next_wakeup(&self) -> Option<u64> {
+        match self.strategy {
+            AlgoStrategy::Twap { start, end, slices } if self.slices_due < slices => {
+                let span = end.saturating_sub(start) as u128;
+                let offset = (span * self.slices_due as u128).div_ceil(slices as u128);
+                Some(start + offset as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Send the child order that came due, if any
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to trade in
+    ///
+    /// # Returns
+    ///
+    /// The result of the child order, `None` if none was due
+    pub fn poll(&mut self, book: &mut OrderBook) -> Option<FillResult> {
+        let target = match self.strategy {
+            AlgoStrategy::Twap { start, end, slices } => {
+                let now = book.clock.now();
+                if now < start || slices == 0 {
+                    return None;
+                }
+                let elapsed = (now - start) as u128 * slices as u128;
+                let due = match end.saturating_sub(start) {
+                    0 => slices,
+                    span => (elapsed / span as u128 + 1).min(slices as u128) as u64,
+                };
+                if due <= self.slices_due {
+                    return None;
+                }
+                self.slices_due = due;
+                (self.qty as u128 * due as u128 / slices as u128) as OrderQty
+            }
+            AlgoStrategy::Vwap { participation } => {
+                let start = *self.start_volume.get_or_insert(book.traded_volume());
+                let market =
+                    (book.traded_volume().saturating_sub(start)).saturating_sub(self.filled);
+                (market as u128 * participation as u128 / 100).min(self.qty as u128) as OrderQty
+            }
+        };
+        let qty = target.saturating_sub(self.filled);
+        if qty == 0 {
+            return None;
+        }
+
+        let result = book.execute(self.owner, self.side, self.price, qty);
+        if let Some(id) = result.id {
+            book.force_cancel(id);
+        }
+        self.children += 1;
+        for (price, qty) in &result.orders {
+            self.filled += qty;
+            self.notional += *price as u128 * *qty as u128;
+        }
+        Some(result)
+    }
+
+    /// Get the aggregated fills of the parent order
+    pub fn report(&self) -> AlgoReport {
+        AlgoReport {
+            filled: self.filled,
+            avg_price: match self.filled {
+                0 => 0.0,
+                filled => self.notional as f64 / filled as f64,
+            },
+            remaining: self.qty - self.filled,
+            children: self.children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+
+    #[test]
+    fn test_twap() {
+        let clock = ManualClock::new(0);
+        let mut book = OrderBook::builder().clock(clock.clone()).build();
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(1, Side::Ask, 101, 20).unwrap();
+        let strategy = AlgoStrategy::Twap {
+            start: 100,
+            end: 500,
+            slices: 4,
+        };
+        let mut algo = Algo::new(2, Side::Bid, 101, 20, strategy);
+        assert!(algo.poll(&mut book).is_none());
+        assert_eq!(algo.next_wakeup(), Some(100));
+
+        clock.set(100);
+        assert_eq!(algo.poll(&mut book).unwrap().orders, vec![(100, 5)]);
+        assert!(algo.poll(&mut book).is_none());
+        assert_eq!(algo.next_wakeup(), Some(200));
+        clock.set(350);
+        assert_eq!(
+            algo.poll(&mut book).unwrap().orders,
+            vec![(100, 5), (101, 5)]
+        );
+        clock.set(1_000);
+        algo.poll(&mut book);
+        assert_eq!(algo.next_wakeup(), None);
+
+        let report = algo.report();
+        assert_eq!(
+            (report.filled, report.remaining, report.children),
+            (20, 0, 3)
+        );
+        assert_eq!(report.avg_price, 100.5);
+
+        let strategy = AlgoStrategy::Twap {
+            start: 1_000,
+            end: 1_010,
+            slices: 3,
+        };
+        let mut algo = Algo::new(2, Side::Bid, 101, 3, strategy);
+        clock.set(1_000);
+        algo.poll(&mut book);
+        clock.set(algo.next_wakeup().unwrap());
+        assert!(algo.poll(&mut book).is_some());
+        let strategy = AlgoStrategy::Twap {
+            start: u64::MAX,
+            end: 0,
+            slices: 3,
+        };
+        assert_eq!(
+            Algo::new(2, Side::Bid, 101, 3, strategy).next_wakeup(),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_vwap() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Ask, 100, 100).unwrap();
+        let strategy = AlgoStrategy::Vwap { participation: 20 };
+        let mut algo = Algo::new(2, Side::Bid, 100, 30, strategy);
+        assert!(algo.poll(&mut book).is_none());
+
+        book.execute(3, Side::Bid, 100, 20);
+        assert_eq!(algo.poll(&mut book).unwrap().orders, vec![(100, 4)]);
+        book.execute(3, Side::Bid, 100, 30);
+        assert_eq!(algo.poll(&mut book).unwrap().orders, vec![(100, 6)]);
+        assert_eq!(algo.report().remaining, 20);
+    }
+}
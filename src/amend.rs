@@ -0,0 +1,197 @@
+use crate::audit::AuditRecord;
+use crate::{stats, OrderBook, OrderId, OrderQty, Price, RejectReason, Side};
+
+/// Priority of a resting order whose quantity is increased by an amend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QtyIncrease {
+    /// Move the order to the back of its queue
+    #[default]
+    Requeue,
+
+    /// Keep the place of the order in its queue
+    KeepPriority,
+}
+
+/// Handling of amends changing the price of a resting order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriceAmend {
+    /// Move the order to the back of the queue of its new price
+    #[default]
+    Requeue,
+
+    /// Reject the amend, orders must be canceled and replaced instead
+    Reject,
+}
+
+/// Priority rules applied to amends, which differ between venues
+///
+/// Decreasing the quantity of an order always keeps its priority.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AmendRules {
+    /// Priority of orders whose quantity increases
+    pub qty_increase: QtyIncrease,
+
+    /// Handling of price changes
+    pub price_change: PriceAmend,
+}
+
+/// Reason an amend was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AmendReject {
+    /// New quantity is zero, the order should be canceled instead
+    ZeroQuantity,
+
+    /// Amend changes the price while the rules forbid it
+    PriceChange,
+
+    /// New price would cross the opposite side of the order book
+    WouldCross,
+
+    /// New price or quantity does not respect the increments of the instrument
+    Instrument(RejectReason),
+}
+
+/// Result of an amend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AmendResult {
+    /// Order was not found
+    NotFound,
+
+    /// Order was amended, with whether it kept its place in the queue
+    Amended(bool),
+
+    /// Amend was rejected, the order is unchanged
+    Rejected(AmendReject),
+}
+
+impl OrderBook {
+    /// Set the priority rules applied to amends
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The amend rules
+    pub fn set_amend_rules(&mut self, rules: AmendRules) {
+        self.amend_rules = rules;
+    }
+
+    /// Amend the price and quantity of a resting order
+    ///
+    /// Amends never match: a new price crossing the opposite side is rejected. Whether
+    /// the order keeps its place in the queue follows the amend rules of the order
+    /// book.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    /// * `price` - The new price of the order
+    /// * `qty` - The new quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The result of the amend
+    pub fn amend(&mut self, id: OrderId, price: Price, qty: OrderQty) -> AmendResult {
+        let Some(&(side, idx)) = self.order_loc.get(&id) else {
+            return AmendResult::NotFound;
+        };
+        if qty == 0 {
+            return AmendResult::Rejected(AmendReject::ZeroQuantity);
+        }
+        if let Err(reason) = self.instrument.check(price, qty) {
+            return AmendResult::Rejected(AmendReject::Instrument(reason));
+        }
+        let book = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        let old_price = book.level_prices[idx];
+        let Some(pos) = book.price_levels[idx].iter().position(|o| o.id == id) else {
+            return AmendResult::NotFound;
+        };
+        let old_qty = book.price_levels[idx][pos].qty;
+
+        let requeue = if price != old_price {
+            if self.amend_rules.price_change == PriceAmend::Reject {
+                return AmendResult::Rejected(AmendReject::PriceChange);
+            }
+            let opposite = match side {
+                Side::Bid => self.asks.prices.last().filter(|&&ask| price >= ask),
+                Side::Ask => self.bids.prices.last().filter(|&&bid| price <= bid),
+            };
+            if opposite.is_some() {
+                return AmendResult::Rejected(AmendReject::WouldCross);
+            }
+            true
+        } else {
+            qty > old_qty && self.amend_rules.qty_increase == QtyIncrease::Requeue
+        };
+
+        let book = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        if requeue {
+            let mut order = book.price_levels[idx]
+                .remove(pos)
+                .expect("amended order left its queue");
+            stats::resize(&book.sizes[idx], old_qty, 0);
+            book.take(idx, old_qty);
+            order.qty = qty;
+            self.insert(order, side, price);
+        } else {
+            book.price_levels[idx][pos].qty = qty;
+            stats::resize(&book.sizes[idx], old_qty, qty);
+            if qty > old_qty {
+                book.give(price, qty - old_qty);
+            } else {
+                book.take(idx, old_qty - qty);
+            }
+        }
+        self.record(AuditRecord::Amended { id, price, qty });
+        self.notify_bbo();
+        self.enforce_reduce_only();
+        AmendResult::Amended(!requeue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amend_rules() {
+        let mut book = OrderBook::new();
+        let first = book.add(1, Side::Bid, 100, 10).unwrap();
+        book.add(2, Side::Bid, 100, 10).unwrap();
+        book.add(3, Side::Ask, 105, 10).unwrap();
+
+        assert_eq!(book.amend(first, 100, 5), AmendResult::Amended(true));
+        assert_eq!(book.amend(first, 100, 8), AmendResult::Amended(false));
+        assert_eq!(book.queue_position(first), Some((1, 10)));
+        assert_eq!(book.get_total_qty(Side::Bid, 100), 18);
+
+        book.set_amend_rules(AmendRules {
+            qty_increase: QtyIncrease::KeepPriority,
+            price_change: PriceAmend::Requeue,
+        });
+        let second = book.add(2, Side::Bid, 101, 10).unwrap();
+        book.add(4, Side::Bid, 101, 10).unwrap();
+        assert_eq!(book.amend(second, 101, 30), AmendResult::Amended(true));
+        assert_eq!(book.queue_position(second), Some((0, 0)));
+        assert_eq!(
+            book.amend(second, 105, 30),
+            AmendResult::Rejected(AmendReject::WouldCross)
+        );
+        assert_eq!(book.amend(second, 102, 30), AmendResult::Amended(false));
+        assert_eq!(book.bbo().bid, Some((102, 30)));
+
+        book.set_amend_rules(AmendRules {
+            price_change: PriceAmend::Reject,
+            ..AmendRules::default()
+        });
+        assert_eq!(
+            book.amend(second, 101, 30),
+            AmendResult::Rejected(AmendReject::PriceChange)
+        );
+        assert_eq!(book.amend(OrderId(0), 101, 30), AmendResult::NotFound);
+    }
+}
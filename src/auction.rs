@@ -0,0 +1,283 @@
+use crate::audit::{self, AuditRecord};
+use crate::bust::{self, Trade};
+use crate::stats;
+use crate::{
+    forget_owner, Execution, HalfBook, LastTrade, OrderBook, OrderQty, Price, Side, TickDirection,
+};
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+/// Trading phase of an order book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TradingPhase {
+    /// Orders are matched as they arrive
+    #[default]
+    Continuous,
+
+    /// Orders rest without matching, possibly crossing, until the opening auction
+    PreOpen,
+}
+
+/// Indicative result of the opening auction, were it run now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indicative {
+    /// Indicative opening price
+    pub price: Price,
+
+    /// Indicative opening volume, the quantity that would trade at the price
+    pub volume: OrderQty,
+}
+
+/// Subscriber to changes of the indicative opening price and volume
+pub trait AuctionListener: fmt::Debug {
+    /// Called whenever the indicative opening price or volume changes during pre-open
+    ///
+    /// # Arguments
+    ///
+    /// * `indicative` - The new indicative result, `None` once the book no longer crosses
+    fn on_indicative(&mut self, indicative: Option<Indicative>);
+}
+
+/// Forward changes over a channel, dropping them once the receiver hangs up
+impl AuctionListener for Sender<Option<Indicative>> {
+    fn on_indicative(&mut self, indicative: Option<Indicative>) {
+        let _ = self.send(indicative);
+    }
+}
+
+/// Total quantity of the levels of a side at or better than a price
+fn volume_at(book: &HalfBook, price: Price) -> OrderQty {
+    let better = |level: Price| match book.side {
+        Side::Bid => level >= price,
+        Side::Ask => level <= price,
+    };
+    (book.levels())
+        .take_while(|(level, _)| better(*level))
+        .map(|(_, qty)| qty)
+        .sum()
+}
+
+impl OrderBook {
+    /// Get the trading phase
+    pub fn phase(&self) -> TradingPhase {
+        self.phase
+    }
+
+    /// Set the subscriber to changes of the indicative opening price and volume
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_auction_listener<L: AuctionListener + 'static>(&mut self, listener: L) {
+        self.auction_listener = Some(Box::new(listener));
+    }
+
+    /// Enter the pre-open phase, in which executed orders rest without matching
+    pub fn pre_open(&mut self) {
+        self.phase = TradingPhase::PreOpen;
+        self.update_indicative();
+    }
+
+    /// Get the indicative opening price and volume, maintained on every change of the
+    /// order book during pre-open
+    ///
+    /// # Returns
+    ///
+    /// The indicative result, `None` outside of pre-open or when the book does not cross
+    pub fn indicative(&self) -> Option<Indicative> {
+        self.indicative
+    }
+
+    /// Run the opening auction and enter the continuous phase
+    ///
+    /// The crossing orders trade at the indicative opening price in price-time priority.
+    /// Of the two orders of each fill, the earlier one is reported as the maker. Stop
+    /// orders triggered by the auction are executed afterwards. The uncrossing price is
+    /// kept as the auction closing price.
+    ///
+    /// # Returns
+    ///
+    /// The executions of the auction
+    pub fn open(&mut self) -> Vec<Execution> {
+        let mut executions = Vec::new();
+        let indicative = self.indicative_now();
+        self.phase = TradingPhase::Continuous;
+        self.indicative = None;
+        if let Some(indicative) = indicative {
+            self.auction_price = Some(indicative.price);
+            let mut remaining = indicative.volume;
+            while remaining > 0 {
+                let execution = self.uncross_front(indicative.price, remaining);
+                remaining -= execution.qty;
+                executions.push(execution);
+            }
+            if let Some(listener) = self.auction_listener.as_mut() {
+                listener.on_indicative(None);
+            }
+        }
+        self.notify_bbo();
+        self.run_stops();
+        self.enforce_reduce_only();
+        executions
+    }
+
+    /// Trade the orders at the front of the best bid and ask against each other
+    fn uncross_front(&mut self, price: Price, remaining: OrderQty) -> Execution {
+        let front = |book: &HalfBook| {
+            let idx = *book.queues.last().expect("uncrossing an empty side");
+            (
+                idx,
+                *book.price_levels[idx].front().expect("empty best level"),
+            )
+        };
+        let (bid_idx, bid) = front(&self.bids);
+        let (ask_idx, ask) = front(&self.asks);
+        let qty = bid.qty.min(ask.qty).min(remaining);
+        let (maker, taker, side) = match bid.seq < ask.seq {
+            true => (bid, ask, Side::Ask),
+            false => (ask, bid, Side::Bid),
+        };
+        let execution = Execution {
+            trade: self.next_trade,
+            maker: maker.id,
+            maker_owner: maker.owner,
+            maker_tag: maker.tag,
+            taker: taker.id,
+            taker_owner: taker.owner,
+            taker_tag: taker.tag,
+            side,
+            price,
+            qty,
+            tick: TickDirection::of(self.last_trade, price),
+        };
+        self.next_trade += 1;
+        self.last_trade = Some(LastTrade::of(&execution));
+        self.traded = Some(match self.traded {
+            Some((low, high)) => (low.min(price), high.max(price)),
+            None => (price, price),
+        });
+        self.volume += qty;
+
+        for (book, idx) in [(&mut self.bids, bid_idx), (&mut self.asks, ask_idx)] {
+            let level = &mut book.price_levels[idx];
+            let order = level.front_mut().expect("empty best level");
+            stats::resize(&book.sizes[idx], order.qty, order.qty - qty);
+            order.qty -= qty;
+            if order.qty == 0 {
+                let order = level.pop_front().expect("empty best level");
+                self.order_loc.remove(&order.id);
+                forget_owner(&mut self.owner_orders, order.owner, order.id);
+            }
+            book.take(idx, qty);
+        }
+
+        if self.tape_capacity > 0 {
+            let trade = Trade {
+                time: self.clock.now(),
+                execution,
+                busted: false,
+                maker_seq: maker.seq,
+                maker_left: maker.qty - qty,
+            };
+            bust::push_trade(&mut self.tape, self.tape_capacity, trade);
+        }
+        audit::append_to(
+            &mut self.audit,
+            &mut self.history,
+            &*self.clock,
+            AuditRecord::Fill(execution),
+        );
+        if let Some(positions) = self.positions.as_mut() {
+            positions.on_execution(&execution);
+        }
+        if let Some(sink) = self.drop_copy.as_mut() {
+            sink.on_execution(&execution);
+        }
+        execution
+    }
+
+    /// Recompute the indicative opening price and volume during pre-open, notifying the
+    /// subscriber if they changed
+    pub(crate) fn update_indicative(&mut self) {
+        if self.phase != TradingPhase::PreOpen {
+            return;
+        }
+        let indicative = self.indicative_now();
+        if indicative != self.indicative {
+            self.indicative = indicative;
+            if let Some(listener) = self.auction_listener.as_mut() {
+                listener.on_indicative(indicative);
+            }
+        }
+    }
+
+    /// Compute the price maximizing the traded volume of the crossing orders
+    ///
+    /// Ties are broken by the smallest imbalance between the bid and ask volumes at the
+    /// price, then by the lowest price.
+    fn indicative_now(&self) -> Option<Indicative> {
+        let bid = *self.bids.prices.last()?;
+        let ask = *self.asks.prices.last()?;
+        if bid < ask {
+            return None;
+        }
+        let mut candidates: Vec<Price> = (self.bids.levels())
+            .take_while(|(price, _)| *price >= ask)
+            .chain(self.asks.levels().take_while(|(price, _)| *price <= bid))
+            .map(|(price, _)| price)
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut best: Option<(Indicative, OrderQty)> = None;
+        for price in candidates {
+            let bids = volume_at(&self.bids, price);
+            let asks = volume_at(&self.asks, price);
+            let volume = bids.min(asks);
+            let imbalance = bids.abs_diff(asks);
+            let better = match best {
+                None => true,
+                Some((b, i)) => volume > b.volume || (volume == b.volume && imbalance < i),
+            };
+            if better {
+                best = Some((Indicative { price, volume }, imbalance));
+            }
+        }
+        best.map(|(indicative, _)| indicative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_opening_auction() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_auction_listener(tx);
+        book.pre_open();
+        let first = book.add(1, Side::Bid, 102, 10).unwrap();
+        book.execute(2, Side::Ask, 100, 4);
+        book.execute(3, Side::Ask, 101, 8);
+        let late = book.execute(4, Side::Bid, 101, 5).id.unwrap();
+        book.cancel(late);
+        let updates: Vec<_> = rx.try_iter().collect();
+        let at = |price, volume| Some(Indicative { price, volume });
+        assert_eq!(
+            updates,
+            vec![at(100, 4), at(101, 10), at(101, 12), at(101, 10)]
+        );
+
+        let executions = book.open();
+        assert_eq!(book.phase(), TradingPhase::Continuous);
+        assert!(executions
+            .iter()
+            .all(|e| e.price == 101 && e.maker == first));
+        assert_eq!(executions.iter().map(|e| e.qty).sum::<OrderQty>(), 10);
+        assert_eq!(book.snapshot().asks, vec![(101, 2)]);
+        assert!(book.snapshot().bids.is_empty());
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![None]);
+    }
+}
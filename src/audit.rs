@@ -0,0 +1,461 @@
+use crate::history::OrderHistory;
+use crate::{
+    Clock, Execution, OrderBook, OrderId, OrderQty, OwnerId, Price, Side, Tag, TickDirection,
+    TradeId,
+};
+
+/// Command accepted by an order book or event resulting from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditRecord {
+    /// An order was added without matching
+    Add {
+        /// Identifier given to the order
+        id: OrderId,
+
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+    },
+
+    /// A limit order was accepted for matching
+    Execute {
+        /// Identifier given to the order
+        id: OrderId,
+
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Limit price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+    },
+
+    /// A two-sided quote was submitted, canceling the previous quote of the owner
+    Quote {
+        /// Owner of the quote
+        owner: OwnerId,
+
+        /// Price of the bid
+        bid_price: Price,
+
+        /// Quantity of the bid
+        bid_qty: OrderQty,
+
+        /// Price of the ask
+        ask_price: Price,
+
+        /// Quantity of the ask
+        ask_qty: OrderQty,
+    },
+
+    /// A resting order and an incoming order traded
+    Fill(Execution),
+
+    /// The remainder of an executed order or a side of a quote rested in the order book
+    Rested {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// Quantity left resting
+        qty: OrderQty,
+    },
+
+    /// A resting order was removed from the order book without trading
+    Canceled {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// Quantity that was left resting
+        qty: OrderQty,
+    },
+
+    /// A trade was busted and its fill reversed
+    Bust {
+        /// Identifier of the trade
+        trade: TradeId,
+    },
+
+    /// The quantity of a resting order was reduced, keeping its priority
+    Reduced {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// Quantity left resting
+        qty: OrderQty,
+    },
+
+    /// The price or quantity of a resting order was amended
+    Amended {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// New price of the order
+        price: Price,
+
+        /// New quantity of the order
+        qty: OrderQty,
+    },
+
+    /// A resting order was transferred to another owner, keeping its priority
+    Transferred {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// New owner of the order
+        owner: OwnerId,
+    },
+}
+
+impl AuditRecord {
+    /// Append the canonical encoding of the record, hashed into the chain
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let side = |side: Side| match side {
+            Side::Bid => 0,
+            Side::Ask => 1,
+        };
+        let tick = |tick: TickDirection| match tick {
+            TickDirection::Up => 0,
+            TickDirection::Down => 1,
+            TickDirection::Zero => 2,
+        };
+        let (kind, fields): (u8, Vec<u64>) = match *self {
+            AuditRecord::Add {
+                id,
+                owner,
+                side: s,
+                price,
+                qty,
+                tag,
+            } => (0, vec![id.0, owner, side(s), price, qty, tag]),
+            AuditRecord::Execute {
+                id,
+                owner,
+                side: s,
+                price,
+                qty,
+                tag,
+            } => (1, vec![id.0, owner, side(s), price, qty, tag]),
+            AuditRecord::Quote {
+                owner,
+                bid_price,
+                bid_qty,
+                ask_price,
+                ask_qty,
+            } => (2, vec![owner, bid_price, bid_qty, ask_price, ask_qty]),
+            AuditRecord::Fill(e) => (
+                3,
+                vec![
+                    e.trade,
+                    e.maker.0,
+                    e.maker_owner,
+                    e.maker_tag,
+                    e.taker.0,
+                    e.taker_owner,
+                    e.taker_tag,
+                    side(e.side),
+                    e.price,
+                    e.qty,
+                    tick(e.tick),
+                ],
+            ),
+            AuditRecord::Rested { id, qty } => (4, vec![id.0, qty]),
+            AuditRecord::Canceled { id, qty } => (5, vec![id.0, qty]),
+            AuditRecord::Bust { trade } => (6, vec![trade]),
+            AuditRecord::Reduced { id, qty } => (7, vec![id.0, qty]),
+            AuditRecord::Amended { id, price, qty } => (8, vec![id.0, price, qty]),
+            AuditRecord::Transferred { id, owner } => (9, vec![id.0, owner]),
+        };
+        buf.push(kind);
+        for field in fields {
+            buf.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+}
+
+/// Entry of an audit trail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Position of the entry in the trail, starting at 0
+    pub seq: u64,
+
+    /// Time the entry was appended
+    pub time: u64,
+
+    /// Recorded command or event
+    pub record: AuditRecord,
+
+    /// Hash of the previous entry, all zeros for the first entry
+    pub prev_hash: [u8; 32],
+
+    /// Hash of this entry
+    pub hash: [u8; 32],
+}
+
+impl AuditEntry {
+    /// Compute the hash an entry must have given its content
+    fn compute_hash(seq: u64, time: u64, record: &AuditRecord, prev_hash: &[u8; 32]) -> [u8; 32] {
+        let mut buf = Vec::with_capacity(128);
+        buf.extend_from_slice(prev_hash);
+        buf.extend_from_slice(&seq.to_le_bytes());
+        buf.extend_from_slice(&time.to_le_bytes());
+        record.encode(&mut buf);
+        sha256(&buf)
+    }
+}
+
+/// Tamper-evident history of an order book
+///
+/// The hash of every entry is the SHA-256 of the hash of the previous entry, the
+/// sequence number and time of the entry as little-endian u64, and the record: a kind
+/// byte (0 add, 1 execute, 2 quote, 3 fill, 4 rested, 5 canceled, 6 bust, 7 reduced,
+/// 8 amended, 9 transferred) followed by its fields as little-endian u64, in declaration
+/// order, with sides encoded as 0 for bids and 1 for asks and tick directions as 0 up, 1
+/// down and 2 zero. Altering, inserting or dropping an entry breaks the chain from that entry on,
+/// which anyone holding the trail can check.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    /// Entries of the trail, in order
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Create a new, empty, audit trail
+    pub fn new() -> AuditLog {
+        AuditLog::default()
+    }
+
+    /// Append a record to the trail
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time of the record
+    /// * `record` - The command or event to record
+    ///
+    /// # Returns
+    ///
+    /// The appended entry
+    pub fn append(&mut self, time: u64, record: AuditRecord) -> &AuditEntry {
+        let seq = self.entries.len() as u64;
+        let prev_hash = self.head();
+        let hash = AuditEntry::compute_hash(seq, time, &record, &prev_hash);
+        self.entries.push(AuditEntry {
+            seq,
+            time,
+            record,
+            prev_hash,
+            hash,
+        });
+        &self.entries[self.entries.len() - 1]
+    }
+
+    /// Get the hash of the last entry, all zeros for an empty trail
+    pub fn head(&self) -> [u8; 32] {
+        self.entries.last().map_or([0; 32], |entry| entry.hash)
+    }
+
+    /// Get the entries of the trail
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Verify the hash chain of a sequence of entries
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries to verify, starting with the first entry of the trail
+    ///
+    /// # Returns
+    ///
+    /// The sequence number of the first entry breaking the chain, if any
+    pub fn verify(entries: &[AuditEntry]) -> Result<(), u64> {
+        let mut prev_hash = [0; 32];
+        for (seq, entry) in entries.iter().enumerate() {
+            let seq = seq as u64;
+            let hash = AuditEntry::compute_hash(seq, entry.time, &entry.record, &prev_hash);
+            if entry.seq != seq || entry.prev_hash != prev_hash || entry.hash != hash {
+                return Err(seq);
+            }
+            prev_hash = hash;
+        }
+        Ok(())
+    }
+}
+
+impl OrderBook {
+    /// Start recording every accepted command and resulting event in an audit trail
+    pub fn enable_audit(&mut self) {
+        self.audit.get_or_insert_with(AuditLog::new);
+    }
+
+    /// Get the audit trail, `None` unless enabled
+    pub fn audit(&self) -> Option<&AuditLog> {
+        self.audit.as_ref()
+    }
+
+    /// Append a record to the audit trail and the order history, if enabled
+    pub(crate) fn record(&mut self, record: AuditRecord) {
+        append_to(&mut self.audit, &mut self.history, &*self.clock, record);
+    }
+}
+
+/// Append a record to an audit trail and an order history, if enabled
+///
+/// # Arguments
+///
+/// * `audit` - The audit trail of the order book
+/// * `history` - The order history of the order book
+/// * `clock` - The clock of the order book
+/// * `record` - The command or event to record
+pub(crate) fn append_to(
+    audit: &mut Option<AuditLog>,
+    history: &mut Option<OrderHistory>,
+    clock: &dyn Clock,
+    record: AuditRecord,
+) {
+    if let Some(history) = history.as_mut() {
+        history.observe(clock.now(), &record);
+    }
+    if let Some(audit) = audit.as_mut() {
+        audit.append(clock.now(), record);
+    }
+}
+
+/// Round constants of SHA-256
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Compute the SHA-256 digest of a message
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, add) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        let hex = |digest: [u8; 32]| {
+            digest
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>()
+        };
+        assert_eq!(
+            hex(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(sha256(&[b'a'; 1000])),
+            "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+        );
+    }
+
+    #[test]
+    fn test_audit_chain() {
+        let mut book = OrderBook::new();
+        book.enable_audit();
+        let maker = book.add(1, Side::Ask, 100, 10).unwrap();
+        book.execute(2, Side::Bid, 100, 15);
+        book.cancel(maker);
+
+        let entries = book.audit().unwrap().entries().to_vec();
+        assert_eq!(entries.len(), 4);
+        assert!(matches!(entries[2].record, AuditRecord::Fill(e) if e.qty == 10));
+        assert!(matches!(
+            entries[3].record,
+            AuditRecord::Rested { qty: 5, .. }
+        ));
+        assert_eq!(AuditLog::verify(&entries), Ok(()));
+
+        let mut tampered = entries.clone();
+        if let AuditRecord::Fill(execution) = &mut tampered[2].record {
+            execution.qty = 9;
+        }
+        assert_eq!(AuditLog::verify(&tampered), Err(2));
+        let mut dropped = entries;
+        dropped.remove(1);
+        assert_eq!(AuditLog::verify(&dropped), Err(1));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{LastTrade, OrderQty, Price};
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+/// Best bid and offer of the order book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bbo {
+    /// Best bid price and the total quantity at it, `None` if there are no bids
+    pub bid: Option<(Price, OrderQty)>,
+
+    /// Best ask price and the total quantity at it, `None` if there are no asks
+    pub ask: Option<(Price, OrderQty)>,
+
+    /// Last trade of the order book, `None` if nothing traded yet, as of the change; a
+    /// change of the last trade alone is not sent to listeners
+    pub last: Option<LastTrade>,
+}
+
+/// Subscriber to changes of the best bid and offer
+pub trait BboListener: fmt::Debug {
+    /// Called whenever the price or size of the best bid or offer changes
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The best bid and offer before the change
+    /// * `new` - The best bid and offer after the change
+    fn on_bbo(&mut self, old: &Bbo, new: &Bbo);
+}
+
+/// Forward changes over a channel as (old, new) pairs, dropping them once the receiver
+/// hangs up
+impl BboListener for Sender<(Bbo, Bbo)> {
+    fn on_bbo(&mut self, old: &Bbo, new: &Bbo) {
+        let _ = self.send((*old, *new));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderBook, Side};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_bbo_changes() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_tape_capacity(8);
+        book.add(1, Side::Bid, 100, 10).unwrap();
+        book.set_bbo_listener(tx);
+
+        let deep = book.add(1, Side::Bid, 99, 10).unwrap();
+        book.add(1, Side::Ask, 105, 10).unwrap();
+        book.add(1, Side::Ask, 106, 10).unwrap();
+        let id = book.add(1, Side::Bid, 100, 5).unwrap();
+        book.cancel(id);
+        book.execute(2, Side::Ask, 100, 10);
+
+        let changes: Vec<_> = rx.try_iter().collect();
+        let last = book.last_trade();
+        assert_eq!(last.map(|t| (t.price, t.qty)), Some((100, 10)));
+        let bid = |price, qty| Bbo {
+            bid: Some((price, qty)),
+            ask: Some((105, 10)),
+            last: None,
+        };
+        let start = Bbo {
+            bid: Some((100, 10)),
+            ask: None,
+            last: None,
+        };
+        assert_eq!(
+            changes,
+            vec![
+                (start, bid(100, 10)),
+                (bid(100, 10), bid(100, 15)),
+                (bid(100, 15), bid(100, 10)),
+                (
+                    bid(100, 10),
+                    Bbo {
+                        last,
+                        ..bid(99, 10)
+                    }
+                ),
+            ]
+        );
+
+        book.execute(2, Side::Ask, 99, 4);
+        let trade = book.last_trade().unwrap().trade;
+        book.cancel(deep);
+        assert_eq!(rx.try_iter().count(), 2);
+        assert!(book.bust_trade(trade).is_ok());
+        assert_eq!(book.last_trade(), last);
+        assert_eq!(rx.try_iter().count(), 0);
+    }
+}
@@ -0,0 +1,203 @@
+use execution::{BookGenerator, ManualClock, OrderBook, OrderId, Side};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::process;
+use std::time::{Duration, Instant};
+
+/// Parameters of a stress run
+#[derive(Debug, Clone)]
+struct Config {
+    /// Number of commands to apply
+    commands: usize,
+
+    /// Mean arrival rate of commands, per simulated second
+    rate: f64,
+
+    /// Share of commands that cancel a resting order
+    cancel_ratio: f64,
+
+    /// Share of orders priced within the hot band around the mid price
+    hot_share: f64,
+
+    /// Half-width of the hot band, in ticks
+    hot_ticks: u64,
+
+    /// Half-width of the whole price range, in ticks
+    range_ticks: u64,
+
+    /// Number of commands between invariant checks, zero for none
+    check_every: usize,
+
+    /// Number of levels of each side generated before the run, zero for an empty book
+    warm_levels: usize,
+
+    /// Number of orders of the best levels generated before the run
+    warm_orders: usize,
+
+    /// Seed of the random generator
+    seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            commands: 1_000_000,
+            rate: 100_000.0,
+            cancel_ratio: 0.4,
+            hot_share: 0.8,
+            hot_ticks: 5,
+            range_ticks: 500,
+            check_every: 10_000,
+            warm_levels: 0,
+            warm_orders: 10,
+            seed: 1,
+        }
+    }
+}
+
+/// Print the usage and exit
+fn usage() -> ! {
+    eprintln!(
+        "usage: stress [--commands N] [--rate PER_SEC] [--cancel-ratio R] [--hot-share R] \
+         [--hot-ticks N] [--range-ticks N] [--check-every N] [--warm-levels N] [--warm-orders N] [--seed N]"
+    );
+    process::exit(2)
+}
+
+/// Parse the command line into a configuration
+fn parse_args() -> Config {
+    let mut config = Config::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        let ok = match flag.as_str() {
+            "--commands" => value.parse().map(|v| config.commands = v).is_ok(),
+            "--rate" => value.parse().map(|v| config.rate = v).is_ok(),
+            "--cancel-ratio" => value.parse().map(|v| config.cancel_ratio = v).is_ok(),
+            "--hot-share" => value.parse().map(|v| config.hot_share = v).is_ok(),
+            "--hot-ticks" => value.parse().map(|v| config.hot_ticks = v).is_ok(),
+            "--range-ticks" => value.parse().map(|v| config.range_ticks = v).is_ok(),
+            "--check-every" => value.parse().map(|v| config.check_every = v).is_ok(),
+            "--warm-levels" => value.parse().map(|v| config.warm_levels = v).is_ok(),
+            "--warm-orders" => value.parse().map(|v| config.warm_orders = v).is_ok(),
+            "--seed" => value.parse().map(|v| config.seed = v).is_ok(),
+            _ => false,
+        };
+        if !ok {
+            usage();
+        }
+    }
+    config
+}
+
+/// Check the invariants of the order book, exiting on the first violation
+fn check(book: &OrderBook, applied: usize) {
+    let bbo = book.bbo();
+    if let (Some((bid, _)), Some((ask, _))) = (bbo.bid, bbo.ask) {
+        if bid >= ask {
+            fail(applied, &format!("crossed book, bid {bid} ask {ask}"));
+        }
+    }
+    for side in [Side::Bid, Side::Ask] {
+        for stats in book.depth_stats(side, usize::MAX) {
+            let empty = stats.orders == 0 || stats.qty == 0;
+            let sizes = stats.min_size > stats.max_size || stats.max_size > stats.qty;
+            if empty || sizes {
+                fail(applied, &format!("inconsistent level {stats:?}"));
+            }
+        }
+    }
+}
+
+/// Report an invariant violation and exit
+fn fail(applied: usize, message: &str) -> ! {
+    eprintln!("invariant violated after {applied} commands: {message}");
+    process::exit(1)
+}
+
+/// Get a percentile of sorted latencies
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    let rank = ((sorted.len() as f64 * pct / 100.0).ceil() as usize).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn main() {
+    let config = parse_args();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let clock = ManualClock::new(0);
+    let mut book = OrderBook::builder().clock(clock.clone()).build();
+    let mid = 1_000_000;
+    let warm = BookGenerator {
+        best_bid: mid - 1,
+        spread: 2,
+        levels: config.warm_levels,
+        orders_per_level: config.warm_orders,
+        ..BookGenerator::default()
+    };
+    let mut resting: Vec<OrderId> = warm.populate(&mut book, &mut rng);
+    let mut latencies = Vec::with_capacity(config.commands);
+    let mut now = 0.0;
+
+    let start = Instant::now();
+    for applied in 0..config.commands {
+        // Exponential inter-arrival times make the arrivals a Poisson process
+        now += -(1.0 - rng.gen::<f64>()).ln() / config.rate;
+        clock.set((now * 1e9) as u64);
+
+        let cancel = !resting.is_empty() && rng.gen_bool(config.cancel_ratio);
+        let side = if rng.gen_bool(0.5) {
+            Side::Bid
+        } else {
+            Side::Ask
+        };
+        let band = match rng.gen_bool(config.hot_share) {
+            true => config.hot_ticks,
+            false => config.range_ticks,
+        };
+        let offset = rng.gen_range(0..=band);
+        let price = match side {
+            Side::Bid => mid - offset,
+            Side::Ask => mid + offset,
+        };
+        let aggressive = rng.gen_bool(0.1);
+        let price = match (aggressive, side) {
+            (true, Side::Bid) => price + band,
+            (true, Side::Ask) => price - band,
+            (false, _) => price,
+        };
+        let qty = rng.gen_range(1..=100);
+        let owner = rng.gen_range(1..=50);
+
+        let began = Instant::now();
+        if cancel {
+            let id = resting.swap_remove(rng.gen_range(0..resting.len()));
+            book.cancel(id);
+        } else if let Some(id) = book.execute(owner, side, price, qty).id {
+            resting.push(id);
+        }
+        latencies.push(began.elapsed());
+
+        if config.check_every > 0 && (applied + 1) % config.check_every == 0 {
+            check(&book, applied + 1);
+        }
+    }
+    let elapsed = start.elapsed();
+    check(&book, config.commands);
+
+    latencies.sort_unstable();
+    let (bids, asks) = (book.bid_view().len(), book.ask_view().len());
+    println!("commands:   {}", config.commands);
+    println!("elapsed:    {elapsed:?}");
+    println!(
+        "throughput: {:.0} commands/s",
+        config.commands as f64 / elapsed.as_secs_f64()
+    );
+    if !latencies.is_empty() {
+        println!("p50:        {:?}", percentile(&latencies, 50.0));
+        println!("p99:        {:?}", percentile(&latencies, 99.0));
+        println!("p99.9:      {:?}", percentile(&latencies, 99.9));
+        println!("max:        {:?}", latencies[latencies.len() - 1]);
+    }
+    println!("volume:     {}", book.traded_volume());
+    println!("levels:     {bids} bids, {asks} asks");
+}
@@ -0,0 +1,402 @@
+use crate::{
+    AmendRules, AuctionListener, BboListener, Clock, ClosingMethod, CreditCheck, ExecutionSink,
+    IdGenerator, Instrument, MatchingHooks, MatchingMode, MinRestingTime, MmpConfig, MmpListener,
+    OrderBook, OwnerId, PositionTracker, QuoteProtection, RateSource, StpPolicy,
+};
+
+/// Collects the configuration of an order book
+///
+/// Options left unset keep the defaults of `OrderBook::new`: no instrument increments,
+/// random order identifiers, the system clock, no self-trade prevention and no
+/// subscribers.
+#[derive(Debug, Default)]
+pub struct OrderBookBuilder {
+    /// Instrument traded in the order book
+    instrument: Option<Instrument>,
+
+    /// Source of the rate converting the quote currency into the common currency
+    rates: Option<Box<dyn RateSource>>,
+
+    /// Source of the identifiers of new orders
+    ids: Option<Box<dyn IdGenerator>>,
+
+    /// Source of the current time
+    clock: Option<Box<dyn Clock>>,
+
+    /// Self-trade prevention policy
+    stp: StpPolicy,
+
+    /// Sink receiving a copy of every execution
+    drop_copy: Option<Box<dyn ExecutionSink>>,
+
+    /// Credit check applied to every executed or added order
+    credit: Option<Box<dyn CreditCheck>>,
+
+    /// Hooks called while matching executed orders, and before resting added ones
+    hooks: Option<Box<dyn MatchingHooks>>,
+
+    /// Subscriber to changes of the best bid and offer
+    bbo_listener: Option<Box<dyn BboListener>>,
+
+    /// Protection applied to quotes against crossing prices
+    quote_protection: Option<QuoteProtection>,
+
+    /// Market-maker protection limits of every protected owner
+    mmp: Vec<(OwnerId, MmpConfig)>,
+
+    /// Subscriber to triggered market-maker protections
+    mmp_listener: Option<Box<dyn MmpListener>>,
+
+    /// Whether to record an audit trail
+    audit: bool,
+
+    /// Number of orders and of steps per order kept in the order history
+    order_history: Option<(usize, usize)>,
+
+    /// Number of most recent trades kept on the tape
+    tape: usize,
+
+    /// Method used to compute the closing price
+    closing_method: ClosingMethod,
+
+    /// Subscriber to changes of the indicative opening price and volume
+    auction_listener: Option<Box<dyn AuctionListener>>,
+
+    /// Minimum time orders must rest before they can be canceled
+    min_rest: Option<MinRestingTime>,
+
+    /// Priority rules applied to amends
+    amend_rules: AmendRules,
+
+    /// Allocation of incoming orders among the orders of a price level
+    matching: MatchingMode,
+
+    /// Whether to track the position of every owner
+    positions: bool,
+
+    /// Number of price levels of each side to allocate room for up front
+    level_capacity: usize,
+}
+
+impl OrderBookBuilder {
+    /// Create a new builder with the default configuration
+    pub fn new() -> OrderBookBuilder {
+        OrderBookBuilder::default()
+    }
+
+    /// Set the instrument traded in the order book
+    ///
+    /// # Arguments
+    ///
+    /// * `instrument` - The instrument, whose increments executed and added orders must
+    ///   respect
+    pub fn instrument(mut self, instrument: Instrument) -> OrderBookBuilder {
+        self.instrument = Some(instrument);
+        self
+    }
+
+    /// Set the source of the rate converting the quote currency of the instrument into
+    /// the common currency of cross-book analytics
+    ///
+    /// # Arguments
+    ///
+    /// * `rates` - The source of conversion rates
+    pub fn rate_source<R: RateSource + 'static>(mut self, rates: R) -> OrderBookBuilder {
+        self.rates = Some(Box::new(rates));
+        self
+    }
+
+    /// Set the source of the identifiers of new orders
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The identifier generator
+    pub fn id_generator<G: IdGenerator + 'static>(mut self, ids: G) -> OrderBookBuilder {
+        self.ids = Some(Box::new(ids));
+        self
+    }
+
+    /// Set the clock
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The source of the current time
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> OrderBookBuilder {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Set the self-trade prevention policy
+    ///
+    /// # Arguments
+    ///
+    /// * `stp` - The policy applied when an order would trade against its own owner
+    pub fn stp(mut self, stp: StpPolicy) -> OrderBookBuilder {
+        self.stp = stp;
+        self
+    }
+
+    /// Set the drop-copy sink
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The sink to report executions to
+    pub fn drop_copy<S: ExecutionSink + 'static>(mut self, sink: S) -> OrderBookBuilder {
+        self.drop_copy = Some(Box::new(sink));
+        self
+    }
+
+    /// Set the credit check, enabling position tracking
+    ///
+    /// # Arguments
+    ///
+    /// * `credit` - The credit model to check orders against
+    pub fn credit_check<C: CreditCheck + 'static>(mut self, credit: C) -> OrderBookBuilder {
+        self.credit = Some(Box::new(credit));
+        self
+    }
+
+    /// Set the matching hooks
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The hooks to call
+    pub fn hooks<H: MatchingHooks + 'static>(mut self, hooks: H) -> OrderBookBuilder {
+        self.hooks = Some(Box::new(hooks));
+        self
+    }
+
+    /// Set the subscriber to changes of the best bid and offer
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn bbo_listener<L: BboListener + 'static>(mut self, listener: L) -> OrderBookBuilder {
+        self.bbo_listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Set the protection applied to quotes against crossing prices
+    ///
+    /// # Arguments
+    ///
+    /// * `protection` - The protection to apply
+    pub fn quote_protection(mut self, protection: QuoteProtection) -> OrderBookBuilder {
+        self.quote_protection = Some(protection);
+        self
+    }
+
+    /// Enable market-maker protection for an owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to protect
+    /// * `config` - The limits of the owner
+    pub fn mmp(mut self, owner: OwnerId, config: MmpConfig) -> OrderBookBuilder {
+        self.mmp.push((owner, config));
+        self
+    }
+
+    /// Set the subscriber to triggered market-maker protections
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn mmp_listener<L: MmpListener + 'static>(mut self, listener: L) -> OrderBookBuilder {
+        self.mmp_listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Record every accepted command and resulting event in an audit trail
+    pub fn audit(mut self) -> OrderBookBuilder {
+        self.audit = true;
+        self
+    }
+
+    /// Keep the lifecycle of the most recent orders, queryable by identifier
+    ///
+    /// # Arguments
+    ///
+    /// * `orders` - The maximum number of orders retained
+    /// * `events` - The maximum number of steps retained per order
+    pub fn order_history(mut self, orders: usize, events: usize) -> OrderBookBuilder {
+        self.order_history = Some((orders, events));
+        self
+    }
+
+    /// Keep the most recent trades on a tape, so they can be busted
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of trades to keep
+    pub fn tape(mut self, capacity: usize) -> OrderBookBuilder {
+        self.tape = capacity;
+        self
+    }
+
+    /// Set the method used to compute the closing price
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The closing price method
+    pub fn closing_method(mut self, method: ClosingMethod) -> OrderBookBuilder {
+        self.closing_method = method;
+        self
+    }
+
+    /// Set the subscriber to changes of the indicative opening price and volume
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn auction_listener<L: AuctionListener + 'static>(
+        mut self,
+        listener: L,
+    ) -> OrderBookBuilder {
+        self.auction_listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Set the minimum time orders must rest before they can be canceled
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The minimum resting time and the handling of earlier cancels
+    pub fn min_resting_time(mut self, rule: MinRestingTime) -> OrderBookBuilder {
+        self.min_rest = Some(rule);
+        self
+    }
+
+    /// Set the priority rules applied to amends
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The amend rules
+    pub fn amend_rules(mut self, rules: AmendRules) -> OrderBookBuilder {
+        self.amend_rules = rules;
+        self
+    }
+
+    /// Set how incoming orders are allocated among the orders of a price level
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The matching mode
+    pub fn matching_mode(mut self, mode: MatchingMode) -> OrderBookBuilder {
+        self.matching = mode;
+        self
+    }
+
+    /// Track the position of every owner, as needed by reduce-only orders
+    pub fn track_positions(mut self) -> OrderBookBuilder {
+        self.positions = true;
+        self
+    }
+
+    /// Allocate room for a number of price levels of each side up front
+    ///
+    /// Order books start empty and grow as levels are added, so books expected to
+    /// hold many levels can avoid reallocating while trading.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The number of price levels of each side
+    pub fn level_capacity(mut self, levels: usize) -> OrderBookBuilder {
+        self.level_capacity = levels;
+        self
+    }
+
+    /// Build the order book
+    ///
+    /// # Returns
+    ///
+    /// An empty order book with the collected configuration
+    pub fn build(self) -> OrderBook {
+        let mut book = OrderBook::new();
+        if let Some(instrument) = self.instrument {
+            book.instrument = instrument;
+        }
+        book.rates = self.rates;
+        if let Some(ids) = self.ids {
+            book.ids = ids;
+        }
+        if let Some(clock) = self.clock {
+            book.clock = clock;
+        }
+        book.stp = self.stp;
+        book.drop_copy = self.drop_copy;
+        if self.credit.is_some() {
+            book.credit = self.credit;
+            book.positions = Some(PositionTracker::new());
+        }
+        book.hooks = self.hooks;
+        book.bbo_listener = self.bbo_listener;
+        book.quote_protection = self.quote_protection;
+        for (owner, config) in self.mmp {
+            book.set_mmp(owner, config);
+        }
+        book.mmp_listener = self.mmp_listener;
+        if let Some((orders, events)) = self.order_history {
+            book.enable_order_history(orders, events);
+        }
+        if self.audit {
+            book.enable_audit();
+        }
+        book.tape_capacity = self.tape;
+        book.closing_method = self.closing_method;
+        book.auction_listener = self.auction_listener;
+        book.min_rest = self.min_rest;
+        book.amend_rules = self.amend_rules;
+        book.matching = self.matching;
+        if self.positions {
+            book.track_positions();
+        }
+        book.bids.reserve(self.level_capacity);
+        book.asks.reserve(self.level_capacity);
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderId, OrderStatus, RejectReason, SequentialIds, Side};
+
+    #[test]
+    fn test_builder() {
+        let mut book = OrderBook::builder()
+            .instrument(Instrument {
+                symbol: "XYZ".to_string(),
+                tick_size: 5,
+                lot_size: 10,
+                ..Instrument::default()
+            })
+            .id_generator(SequentialIds::new(1))
+            .stp(StpPolicy::CancelResting)
+            .build();
+        assert_eq!(book.instrument().symbol, "XYZ");
+
+        let result = book.execute(1, Side::Ask, 101, 10);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::InvalidTick)
+        );
+        let result = book.execute(1, Side::Ask, 100, 15);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::InvalidLot)
+        );
+        assert_eq!(
+            book.add(1, Side::Bid, 99, 10),
+            Err(RejectReason::InvalidTick)
+        );
+
+        let own = book.execute(1, Side::Ask, 100, 10).id.unwrap();
+        let other = book.execute(2, Side::Ask, 100, 10).id.unwrap();
+        assert_eq!((own, other), (OrderId(3), OrderId(4)));
+        let result = book.execute(1, Side::Bid, 100, 10);
+        assert_eq!(result.status, OrderStatus::Filled);
+        assert_eq!(book.order(own), None);
+        assert_eq!(book.order(other), None);
+    }
+}
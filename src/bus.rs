@@ -0,0 +1,247 @@
+use crate::{
+    Bbo, BboListener, DepthListener, Execution, ExecutionSink, LevelUpdate, OrderBook, OrderId,
+    OwnerId,
+};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Sending end of the channel of a subscriber
+type Sender<T> = mpsc::Sender<T>;
+
+/// Receiving end of the channel of a subscriber
+type Subscription<T> = mpsc::Receiver<T>;
+
+/// Open the channel of a new subscriber
+fn channel<T>() -> (Sender<T>, Subscription<T>) {
+    mpsc::channel()
+}
+
+/// Subscribers to one type of event
+#[derive(Debug)]
+struct Topic<T> {
+    /// Channels to the subscribers
+    subscribers: Vec<Sender<T>>,
+}
+
+impl<T> Default for Topic<T> {
+    fn default() -> Topic<T> {
+        Topic {
+            subscribers: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> Topic<T> {
+    /// Add a subscriber
+    fn subscribe(&mut self) -> Subscription<T> {
+        let (tx, rx) = channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    /// Send an event to every subscriber, dropping those that hung up
+    fn publish(&mut self, event: &T) {
+        self.subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// Topics of the event bus
+#[derive(Debug, Default)]
+struct Topics {
+    /// Executions
+    trades: Topic<Execution>,
+
+    /// Executions that were busted
+    busts: Topic<Execution>,
+
+    /// Changes of the depth of the order book
+    depth: Topic<LevelUpdate>,
+
+    /// Changes of the best bid and offer
+    bbo: Topic<Bbo>,
+}
+
+/// Event bus fanning out the events of an order book to typed topics
+///
+/// Consumers such as the tape, metrics, publishers or a position tracker subscribe to
+/// the topics they need and receive events over their own channel, so the order book
+/// only ever reports to the bus. Subscribers that hang up are dropped. Clones share the
+/// same topics, so the bus can be attached to an order book while keeping a handle.
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    /// Shared topics
+    topics: Arc<Mutex<Topics>>,
+}
+
+impl EventBus {
+    /// Create a new event bus without subscribers
+    pub fn new() -> EventBus {
+        EventBus::default()
+    }
+
+    /// Lock the topics, even if a publisher panicked while holding them
+    fn topics(&self) -> MutexGuard<'_, Topics> {
+        self.topics
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Report the executions, changes of the depth and changes of the best bid and offer
+    /// of an order book to the bus
+    ///
+    /// A drop-copy sink, depth subscriber or BBO subscriber already set on the order
+    /// book keeps receiving its events, after the bus.
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to attach to
+    pub fn attach(&self, book: &mut OrderBook) {
+        let (drop_copy, depth, bbo) = (
+            book.drop_copy.take(),
+            book.depth_listener.take(),
+            book.bbo_listener.take(),
+        );
+        book.set_drop_copy(self.chain(drop_copy));
+        book.set_depth_listener(self.chain(depth));
+        book.set_bbo_listener(self.chain(bbo));
+    }
+
+    /// Chain the bus in front of a subscriber
+    fn chain<L: ?Sized>(&self, previous: Option<Box<L>>) -> Chained<L> {
+        Chained {
+            bus: self.clone(),
+            previous,
+        }
+    }
+
+    /// Subscribe to executions
+    pub fn subscribe_trades(&self) -> Subscription<Execution> {
+        self.topics().trades.subscribe()
+    }
+
+    /// Subscribe to busted executions
+    pub fn subscribe_busts(&self) -> Subscription<Execution> {
+        self.topics().busts.subscribe()
+    }
+
+    /// Subscribe to changes of the depth, as levels with their new total quantity
+    pub fn subscribe_depth(&self) -> Subscription<LevelUpdate> {
+        self.topics().depth.subscribe()
+    }
+
+    /// Subscribe to changes of the best bid and offer
+    pub fn subscribe_bbo(&self) -> Subscription<Bbo> {
+        self.topics().bbo.subscribe()
+    }
+}
+
+/// Event bus attached to an order book, forwarding the events to the subscriber it
+/// replaced on the order book
+#[derive(Debug)]
+struct Chained<L: ?Sized> {
+    /// Bus the events are published to first
+    bus: EventBus,
+
+    /// Subscriber previously set on the order book
+    previous: Option<Box<L>>,
+}
+
+impl ExecutionSink for Chained<dyn ExecutionSink> {
+    fn on_execution(&mut self, execution: &Execution) {
+        self.bus.on_execution(execution);
+        if let Some(previous) = self.previous.as_mut() {
+            previous.on_execution(execution);
+        }
+    }
+
+    fn on_bust(&mut self, execution: &Execution) {
+        self.bus.on_bust(execution);
+        if let Some(previous) = self.previous.as_mut() {
+            previous.on_bust(execution);
+        }
+    }
+
+    fn on_transfer(&mut self, id: OrderId, from: OwnerId, to: OwnerId) {
+        if let Some(previous) = self.previous.as_mut() {
+            previous.on_transfer(id, from, to);
+        }
+    }
+}
+
+impl DepthListener for Chained<dyn DepthListener> {
+    fn on_level(&mut self, update: &LevelUpdate) {
+        self.bus.on_level(update);
+        if let Some(previous) = self.previous.as_mut() {
+            previous.on_level(update);
+        }
+    }
+}
+
+impl BboListener for Chained<dyn BboListener> {
+    fn on_bbo(&mut self, old: &Bbo, new: &Bbo) {
+        self.bus.on_bbo(old, new);
+        if let Some(previous) = self.previous.as_mut() {
+            previous.on_bbo(old, new);
+        }
+    }
+}
+
+impl ExecutionSink for EventBus {
+    fn on_execution(&mut self, execution: &Execution) {
+        self.topics().trades.publish(execution);
+    }
+
+    fn on_bust(&mut self, execution: &Execution) {
+        self.topics().busts.publish(execution);
+    }
+}
+
+impl DepthListener for EventBus {
+    fn on_level(&mut self, update: &LevelUpdate) {
+        self.topics().depth.publish(update);
+    }
+}
+
+impl BboListener for EventBus {
+    fn on_bbo(&mut self, _old: &Bbo, new: &Bbo) {
+        self.topics().bbo.publish(new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_event_bus() {
+        let bus = EventBus::new();
+        let mut book = OrderBook::new();
+        let (copy_tx, copies) = mpsc::channel::<Execution>();
+        book.set_drop_copy(copy_tx);
+        bus.attach(&mut book);
+        let trades = bus.subscribe_trades();
+        let depth = bus.subscribe_depth();
+        let bbo = bus.subscribe_bbo();
+        drop(bus.subscribe_trades());
+
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 102, 10).unwrap();
+        book.execute(2, Side::Bid, 101, 10);
+        book.quote(3, 99, 5, 0, 0);
+        book.quote(3, 99, 5, 0, 0);
+
+        let trades: Vec<_> = trades.try_iter().collect();
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].price, trades[0].qty), (101, 10));
+        assert_eq!(copies.try_iter().collect::<Vec<_>>(), trades);
+        let updates: Vec<_> = depth.try_iter().map(|u| (u.price, u.qty)).collect();
+        assert_eq!(updates, vec![(101, 10), (102, 10), (101, 0), (99, 5)]);
+        let asks: Vec<_> = bbo.try_iter().map(|b| b.ask).collect();
+        assert_eq!(
+            asks,
+            vec![Some((101, 10)), Some((102, 10)), Some((102, 10))]
+        );
+        assert_eq!(bus.topics().trades.subscribers.len(), 1);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::audit::{self, AuditRecord};
+use crate::{min_rest, stats};
+use crate::{Execution, LastTrade, Order, OrderBook, OrderQty, Side, TradeId};
+use std::collections::VecDeque;
+
+/// Trade kept on the tape of an order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trade {
+    /// Time of the trade, from the clock of the order book
+    pub time: u64,
+
+    /// Execution reported for the trade
+    pub execution: Execution,
+
+    /// Whether the trade was busted
+    pub busted: bool,
+
+    /// Arrival sequence number of the resting order, to restore its priority
+    pub(crate) maker_seq: u64,
+
+    /// Quantity of the resting order left after the trade
+    pub(crate) maker_left: OrderQty,
+}
+
+/// Reason a trade could not be busted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BustError {
+    /// Trade is not on the tape, either unknown or already dropped from it
+    NotFound,
+
+    /// Trade was already busted
+    AlreadyBusted,
+}
+
+/// Append a trade to a tape holding at most a number of trades, dropping the oldest
+pub(crate) fn push_trade(tape: &mut VecDeque<Trade>, capacity: usize, trade: Trade) {
+    if tape.len() == capacity {
+        tape.pop_front();
+    }
+    tape.push_back(trade);
+}
+
+impl OrderBook {
+    /// Set the number of most recent trades kept on the tape, which can be busted
+    ///
+    /// The tape is disabled by default. Shrinking it drops the oldest trades.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of trades to keep, zero to disable the tape
+    pub fn set_tape_capacity(&mut self, capacity: usize) {
+        self.tape_capacity = capacity;
+        while self.tape.len() > capacity {
+            self.tape.pop_front();
+        }
+    }
+
+    /// Get the trades on the tape
+    ///
+    /// # Returns
+    ///
+    /// An iterator over the most recent trades, oldest first
+    pub fn tape(&self) -> impl Iterator<Item = &Trade> {
+        self.tape.iter()
+    }
+
+    /// Bust a trade on the tape, reversing its execution
+    ///
+    /// The traded quantity is given back to the resting order. Should the trade, along
+    /// with later trades on the tape, have filled the resting order, the order is put
+    /// back at its price with its original time priority, under the owner it last traded
+    /// under should it have been transferred since; a resting order canceled since the
+    /// trade is not restored. Positions are reversed by an offsetting fill at the
+    /// price of the trade and the drop-copy sink is sent a correction.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade` - The identifier of the trade to bust
+    ///
+    /// # Returns
+    ///
+    /// The execution of the busted trade, or why it could not be busted
+    pub fn bust_trade(&mut self, trade: TradeId) -> Result<Execution, BustError> {
+        let pos = (self.tape.iter())
+            .position(|t| t.execution.trade == trade)
+            .ok_or(BustError::NotFound)?;
+        if self.tape[pos].busted {
+            return Err(BustError::AlreadyBusted);
+        }
+        self.tape[pos].busted = true;
+        self.volume -= self.tape[pos].execution.qty;
+        let Trade {
+            execution,
+            maker_seq,
+            maker_left,
+            ..
+        } = self.tape[pos];
+
+        let side = match execution.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let book = match side {
+            Side::Ask => &mut self.asks,
+            Side::Bid => &mut self.bids,
+        };
+        if let Some(&(_, idx)) = self.order_loc.get(&execution.maker) {
+            let price = book.level_prices[idx];
+            let queue = &mut book.price_levels[idx];
+            if let Some(order) = queue.iter_mut().find(|o| o.id == execution.maker) {
+                stats::resize(&book.sizes[idx], order.qty, order.qty + execution.qty);
+                order.qty += execution.qty;
+            }
+            book.give(price, execution.qty);
+        } else {
+            let filled_later: OrderQty = (self.tape.iter().skip(pos + 1))
+                .filter(|t| !t.busted && t.execution.maker == execution.maker)
+                .map(|t| t.execution.qty)
+                .sum();
+            if filled_later == maker_left {
+                let owner = (self.tape.iter().rev())
+                    .find(|t| t.execution.maker == execution.maker)
+                    .map_or(execution.maker_owner, |t| t.execution.maker_owner);
+                let order = Order {
+                    id: execution.maker,
+                    owner,
+                    qty: execution.qty,
+                    tag: execution.maker_tag,
+                    seq: maker_seq,
+                    entered: min_rest::UNTIMED,
+                };
+                let idx = book.restore(execution.price, order);
+                self.order_loc.insert(execution.maker, (side, idx));
+                self.owner_orders
+                    .entry(owner)
+                    .or_default()
+                    .insert(execution.maker);
+            }
+        }
+
+        if let Some(positions) = self.positions.as_mut() {
+            positions.on_execution(&Execution { side, ..execution });
+        }
+        if let Some(sink) = self.drop_copy.as_mut() {
+            sink.on_bust(&execution);
+        }
+        if self.last_trade.map(|e| e.trade) == Some(trade) {
+            let last = self.tape.iter().rev().find(|t| !t.busted);
+            self.last_trade = last.map(|t| LastTrade::of(&t.execution));
+        }
+        audit::append_to(
+            &mut self.audit,
+            &mut self.history,
+            &*self.clock,
+            AuditRecord::Bust { trade },
+        );
+        self.notify_bbo();
+        self.enforce_reduce_only();
+        Ok(execution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bust_trade() {
+        let mut book = OrderBook::new();
+        book.set_tape_capacity(8);
+        let a = book.add(1, Side::Ask, 100, 10).unwrap();
+        let b = book.add(2, Side::Ask, 100, 10).unwrap();
+        book.execute(3, Side::Bid, 100, 14);
+        let trades: Vec<_> = book.tape().map(|t| t.execution).collect();
+        assert_eq!(trades.len(), 2);
+        assert_eq!((trades[0].trade, trades[0].maker), (1, a));
+        assert_eq!(book.order(a), None);
+
+        assert_eq!(book.bust_trade(trades[1].trade), Ok(trades[1]));
+        assert_eq!(book.order(b).map(|o| o.qty), Some(10));
+        assert_eq!(book.bust_trade(trades[0].trade), Ok(trades[0]));
+        assert_eq!(
+            book.bust_trade(trades[0].trade),
+            Err(BustError::AlreadyBusted)
+        );
+        assert_eq!(book.bust_trade(9), Err(BustError::NotFound));
+        assert_eq!(book.get_total_qty(Side::Ask, 100), 20);
+
+        // The restored order keeps its priority ahead of the partially filled one
+        let result = book.execute(4, Side::Bid, 100, 10);
+        assert_eq!(result.status, crate::OrderStatus::Filled);
+        assert_eq!(book.order(a), None);
+        assert_eq!(book.order(b).map(|o| o.qty), Some(10));
+    }
+
+    #[test]
+    fn test_bust_transferred() {
+        let mut book = OrderBook::new();
+        book.set_tape_capacity(8);
+        let a = book.add(1, Side::Ask, 100, 10).unwrap();
+        book.execute(3, Side::Bid, 100, 4);
+        book.transfer(a, 5);
+        book.execute(3, Side::Bid, 100, 6);
+        let first = book.tape().next().unwrap().execution;
+        assert_eq!(first.maker_owner, 1);
+
+        assert_eq!(book.bust_trade(first.trade), Ok(first));
+        assert_eq!(book.order(a).map(|o| (o.owner, o.qty)), Some((5, 4)));
+        assert_eq!(book.cancel_all(1), 0);
+        assert_eq!(book.cancel_all(5), 1);
+    }
+}
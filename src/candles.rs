@@ -0,0 +1,196 @@
+use crate::{Execution, OrderQty, Price};
+
+/// Open, high, low, close and volume of the executions within one interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candle {
+    /// Start of the interval, a multiple of the interval length
+    pub start: u64,
+
+    /// Price of the first execution
+    pub open: Price,
+
+    /// Highest execution price
+    pub high: Price,
+
+    /// Lowest execution price
+    pub low: Price,
+
+    /// Price of the last execution
+    pub close: Price,
+
+    /// Total executed quantity
+    pub volume: OrderQty,
+
+    /// Number of executions
+    pub trades: u64,
+}
+
+/// Aggregates executions into time-bucketed OHLCV candles
+///
+/// Timestamps are in whatever unit the caller uses, the interval must be in the same
+/// unit. Intervals without any execution produce no candle.
+#[derive(Debug, Clone)]
+pub struct CandleBuilder {
+    /// Length of an interval
+    interval: u64,
+
+    /// Candles built so far, ordered by start
+    candles: Vec<Candle>,
+}
+
+impl CandleBuilder {
+    /// Create a new candle builder
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The length of the interval of every candle, must be positive
+    pub fn new(interval: u64) -> CandleBuilder {
+        assert!(interval > 0, "candle interval must be positive");
+        CandleBuilder {
+            interval,
+            candles: Vec::new(),
+        }
+    }
+
+    /// Account for an execution
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The time of the execution
+    /// * `execution` - The execution
+    pub fn on_execution(&mut self, timestamp: u64, execution: &Execution) {
+        self.on_trade(timestamp, execution.price, execution.qty);
+    }
+
+    /// Account for a trade
+    ///
+    /// A trade older than the latest candle updates the high, low, volume and trade count
+    /// of its candle, but not its close
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The time of the trade
+    /// * `price` - The price of the trade
+    /// * `qty` - The quantity of the trade
+    pub fn on_trade(&mut self, timestamp: u64, price: Price, qty: OrderQty) {
+        let start = timestamp - timestamp % self.interval;
+        let latest = self.candles.last().map(|c| c.start);
+        if latest.is_none_or(|latest| start > latest) {
+            self.candles.push(Candle {
+                start,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: qty,
+                trades: 1,
+            });
+            return;
+        }
+        match self.candles.binary_search_by_key(&start, |c| c.start) {
+            Ok(idx) => {
+                let candle = &mut self.candles[idx];
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.volume += qty;
+                candle.trades += 1;
+                if Some(start) == latest {
+                    candle.close = price;
+                }
+            }
+            Err(idx) => self.candles.insert(
+                idx,
+                Candle {
+                    start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: qty,
+                    trades: 1,
+                },
+            ),
+        }
+    }
+
+    /// Get the length of an interval
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Get every candle built so far, ordered by start
+    pub fn candles(&self) -> &[Candle] {
+        &self.candles
+    }
+
+    /// Get the latest candle, which may still be updated
+    pub fn last(&self) -> Option<&Candle> {
+        self.candles.last()
+    }
+
+    /// Get the candles whose interval starts within a time range
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the range, inclusive
+    /// * `to` - The end of the range, exclusive
+    ///
+    /// # Returns
+    ///
+    /// The candles starting within the range, ordered by start
+    pub fn range(&self, from: u64, to: u64) -> &[Candle] {
+        let lo = self.candles.partition_point(|c| c.start < from);
+        let hi = self.candles.partition_point(|c| c.start < to);
+        &self.candles[lo..hi.max(lo)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candles() {
+        let mut builder = CandleBuilder::new(60);
+        builder.on_trade(5, 100, 10);
+        builder.on_trade(30, 104, 5);
+        builder.on_trade(59, 99, 1);
+        builder.on_trade(200, 101, 2);
+        builder.on_trade(70, 98, 3);
+
+        assert_eq!(
+            builder.candles(),
+            &[
+                Candle {
+                    start: 0,
+                    open: 100,
+                    high: 104,
+                    low: 99,
+                    close: 99,
+                    volume: 16,
+                    trades: 3,
+                },
+                Candle {
+                    start: 60,
+                    open: 98,
+                    high: 98,
+                    low: 98,
+                    close: 98,
+                    volume: 3,
+                    trades: 1,
+                },
+                Candle {
+                    start: 180,
+                    open: 101,
+                    high: 101,
+                    low: 101,
+                    close: 101,
+                    volume: 2,
+                    trades: 1,
+                },
+            ]
+        );
+        assert_eq!(builder.range(60, 181).len(), 2);
+        assert_eq!(builder.range(61, 180).len(), 0);
+    }
+}
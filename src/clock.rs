@@ -0,0 +1,62 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time of an order book, in nanoseconds
+pub trait Clock: fmt::Debug {
+    /// Get the current time
+    fn now(&self) -> u64;
+}
+
+/// Clock reading the system time, in nanoseconds since the Unix epoch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Clock that only moves when told to, for simulations and tests
+///
+/// Clones share the same time, so a handle can be kept to drive the clock of an
+/// order book it was given to
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    /// Current time
+    now: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Create a new manual clock
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The initial time
+    pub fn new(start: u64) -> ManualClock {
+        ManualClock {
+            now: Arc::new(AtomicU64::new(start)),
+        }
+    }
+
+    /// Set the current time
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::Relaxed);
+    }
+
+    /// Move the current time forward
+    pub fn advance(&self, delta: u64) {
+        self.now.fetch_add(delta, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::Relaxed)
+    }
+}
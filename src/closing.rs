@@ -0,0 +1,92 @@
+use crate::{OrderBook, Price};
+
+/// Method used to compute the closing price of an order book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClosingMethod {
+    /// Price of the last trade that was not busted
+    #[default]
+    LastTrade,
+
+    /// Price the last auction of the order book uncrossed at
+    Auction,
+
+    /// Volume-weighted average price of the trades on the tape over a final window
+    Vwap {
+        /// Length of the window, in nanoseconds of the clock of the order book
+        window: u64,
+    },
+}
+
+impl OrderBook {
+    /// Set the method used to compute the closing price
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The closing price method
+    pub fn set_closing_method(&mut self, method: ClosingMethod) {
+        self.closing_method = method;
+    }
+
+    /// Compute the closing price, typically at the end of a session for marking positions
+    ///
+    /// The VWAP method only sees the trades kept on the tape, so the tape must be large
+    /// enough to hold the trades of the window.
+    ///
+    /// # Returns
+    ///
+    /// The closing price, `None` if there is no trade or auction price to compute it from
+    pub fn closing_price(&self) -> Option<Price> {
+        match self.closing_method {
+            ClosingMethod::LastTrade => self.last_trade.map(|e| e.price),
+            ClosingMethod::Auction => self.auction_price,
+            ClosingMethod::Vwap { window } => {
+                let start = self.clock.now().saturating_sub(window);
+                let (notional, volume) = (self.tape.iter())
+                    .filter(|t| !t.busted && t.time >= start)
+                    .fold((0u128, 0u128), |(notional, volume), t| {
+                        let qty = t.execution.qty as u128;
+                        (notional + t.execution.price as u128 * qty, volume + qty)
+                    });
+                match volume {
+                    0 => None,
+                    _ => Some((notional / volume) as Price),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ManualClock, Side};
+
+    #[test]
+    fn test_closing_price() {
+        let clock = ManualClock::new(0);
+        let mut book = OrderBook::builder().clock(clock.clone()).tape(16).build();
+        assert_eq!(book.closing_price(), None);
+
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(1, Side::Ask, 110, 10).unwrap();
+        book.execute(2, Side::Bid, 100, 10);
+        clock.set(1_000);
+        book.execute(2, Side::Bid, 110, 5);
+        clock.set(2_000);
+        book.execute(2, Side::Bid, 110, 5);
+        assert_eq!(book.closing_price(), Some(110));
+
+        book.set_closing_method(ClosingMethod::Vwap { window: 10_000 });
+        assert_eq!(book.closing_price(), Some(105));
+        book.set_closing_method(ClosingMethod::Vwap { window: 1_500 });
+        assert_eq!(book.closing_price(), Some(110));
+
+        book.set_closing_method(ClosingMethod::Auction);
+        assert_eq!(book.closing_price(), None);
+        book.pre_open();
+        book.execute(3, Side::Bid, 108, 5);
+        book.execute(4, Side::Ask, 108, 5);
+        book.open();
+        assert_eq!(book.closing_price(), Some(108));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{OrderQty, OwnerId, Position, Price, Side};
+use std::fmt;
+
+/// Incoming order submitted to a credit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditRequest {
+    /// Owner of the order
+    pub owner: OwnerId,
+
+    /// Side of the order
+    pub side: Side,
+
+    /// Limit price of the order
+    pub price: Price,
+
+    /// Quantity of the order
+    pub qty: OrderQty,
+
+    /// Notional value of the order, price times quantity
+    pub notional: u128,
+}
+
+/// Credit or margin model deciding whether an incoming order may be accepted
+pub trait CreditCheck: fmt::Debug {
+    /// Check an incoming order
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The incoming order
+    /// * `position` - The current position of the owner of the order
+    ///
+    /// # Returns
+    ///
+    /// `Ok` to accept the order, or the reason to reject it
+    fn check(&mut self, request: &CreditRequest, position: &Position) -> Result<(), String>;
+}
+
+/// Credit model applying the same position and notional limits to every owner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreditLimit {
+    /// Maximum absolute net position an owner may reach if the order fully fills
+    pub max_position: u64,
+
+    /// Maximum notional value of a single order
+    pub max_notional: u128,
+}
+
+impl CreditCheck for CreditLimit {
+    fn check(&mut self, request: &CreditRequest, position: &Position) -> Result<(), String> {
+        if request.notional > self.max_notional {
+            return Err(format!(
+                "order notional {} exceeds limit {}",
+                request.notional, self.max_notional
+            ));
+        }
+        let projected = match request.side {
+            Side::Bid => position.qty as i128 + request.qty as i128,
+            Side::Ask => position.qty as i128 - request.qty as i128,
+        };
+        if projected.unsigned_abs() > self.max_position as u128 {
+            return Err(format!(
+                "projected position {} exceeds limit {}",
+                projected, self.max_position
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderBook, OrderStatus, RejectReason};
+
+    #[test]
+    fn test_credit_limit() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Ask, 100, 50).unwrap();
+        book.set_credit_check(CreditLimit {
+            max_position: 10,
+            max_notional: 2_000,
+        });
+        assert_eq!(
+            book.add(3, Side::Ask, 100, 30),
+            Err(RejectReason::Credit(
+                "order notional 3000 exceeds limit 2000".to_string()
+            ))
+        );
+
+        let result = book.execute(2, Side::Bid, 100, 30);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::Credit(
+                "order notional 3000 exceeds limit 2000".to_string()
+            ))
+        );
+        assert_eq!(result.remaining, 30);
+        assert_eq!(book.get_total_qty(Side::Ask, 100), 50);
+
+        assert_eq!(
+            book.execute(2, Side::Bid, 100, 8).status,
+            OrderStatus::Filled
+        );
+        assert_eq!(book.positions().unwrap().position(2).qty, 8);
+        let result = book.execute(2, Side::Bid, 100, 3);
+        assert!(matches!(result.status, OrderStatus::Rejected(_)));
+        assert_eq!(
+            book.execute(2, Side::Ask, 101, 15).status,
+            OrderStatus::Created
+        );
+    }
+}
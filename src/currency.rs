@@ -0,0 +1,149 @@
+use crate::{OrderBook, OrderQty, Price, Side};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Source of the rates converting quote currencies into a common currency
+pub trait RateSource: fmt::Debug {
+    /// Get the current conversion rate of a currency
+    ///
+    /// # Arguments
+    ///
+    /// * `currency` - The quote currency of an instrument
+    ///
+    /// # Returns
+    ///
+    /// The value of one unit of the currency in the common currency, `None` if unknown
+    fn rate(&self, currency: &str) -> Option<f64>;
+}
+
+/// Fixed rates, by currency
+impl RateSource for HashMap<String, f64> {
+    fn rate(&self, currency: &str) -> Option<f64> {
+        self.get(currency).copied()
+    }
+}
+
+/// Rates shared with, and updated by, another owner such as a rates feed
+impl<S: RateSource> RateSource for Arc<RwLock<S>> {
+    fn rate(&self, currency: &str) -> Option<f64> {
+        self.read().ok()?.rate(currency)
+    }
+}
+
+impl OrderBook {
+    /// Set the source of the rate converting the quote currency of the instrument into
+    /// the common currency of cross-book analytics
+    ///
+    /// # Arguments
+    ///
+    /// * `rates` - The source of conversion rates
+    pub fn set_rate_source<R: RateSource + 'static>(&mut self, rates: R) {
+        self.rates = Some(Box::new(rates));
+    }
+
+    /// Get the current rate converting the quote currency of the instrument into the
+    /// common currency
+    ///
+    /// # Returns
+    ///
+    /// The conversion rate, `None` without a rate source or if the source does not
+    /// know the currency
+    pub fn conversion_rate(&self) -> Option<f64> {
+        self.rates.as_ref()?.rate(&self.instrument.currency)
+    }
+
+    /// Get the value in the common currency of a quantity at a price
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price, in the quote currency of the instrument
+    /// * `qty` - The quantity
+    ///
+    /// # Returns
+    ///
+    /// The notional value, `None` if the conversion rate is unknown
+    pub fn normalized_notional(&self, price: Price, qty: OrderQty) -> Option<f64> {
+        Some(price as f64 * qty as f64 * self.conversion_rate()?)
+    }
+}
+
+/// Merge the depth of a side of several order books, with prices converted into the
+/// common currency
+///
+/// Order books quoting the same instrument in different currencies can then be compared
+/// level by level, e.g. to find the consolidated depth available to a router.
+///
+/// # Arguments
+///
+/// * `books` - The order books to consolidate
+/// * `side` - The side of the order books
+/// * `levels` - The maximum number of levels taken from each order book
+///
+/// # Returns
+///
+/// The converted price and total quantity of the levels, from the best price outwards,
+/// `None` if the conversion rate of an order book is unknown
+pub fn consolidated_depth(
+    books: &[&OrderBook],
+    side: Side,
+    levels: usize,
+) -> Option<Vec<(f64, OrderQty)>> {
+    let mut depth = Vec::new();
+    for book in books {
+        let rate = book.conversion_rate()?;
+        let converted = (book.cumulative_depth(side).take(levels))
+            .map(|(price, qty, _)| (price as f64 * rate, qty));
+        depth.extend(converted);
+    }
+    depth.sort_by(|a, b| match side {
+        Side::Bid => b.0.total_cmp(&a.0),
+        Side::Ask => a.0.total_cmp(&b.0),
+    });
+    depth.dedup_by(|level, kept| {
+        let same = level.0 == kept.0;
+        if same {
+            kept.1 += level.1;
+        }
+        same
+    });
+    Some(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Instrument;
+
+    #[test]
+    fn test_consolidated_depth() {
+        let rates = Arc::new(RwLock::new(HashMap::from([
+            ("USD".to_string(), 1.0),
+            ("EUR".to_string(), 1.5),
+        ])));
+        let book = |currency: &str| {
+            let instrument = Instrument {
+                currency: currency.to_string(),
+                ..Instrument::default()
+            };
+            let mut book = OrderBook::builder()
+                .instrument(instrument)
+                .rate_source(rates.clone())
+                .build();
+            book.add(1, Side::Ask, 300, 10).unwrap();
+            book.add(1, Side::Ask, 310, 10).unwrap();
+            book
+        };
+        let (usd, eur) = (book("USD"), book("EUR"));
+        assert_eq!(eur.normalized_notional(200, 3), Some(900.0));
+
+        let depth = consolidated_depth(&[&usd, &eur], Side::Ask, 1).unwrap();
+        assert_eq!(depth, vec![(300.0, 10), (450.0, 10)]);
+        rates.write().unwrap().insert("EUR".to_string(), 1.0);
+        let depth = consolidated_depth(&[&usd, &eur], Side::Ask, 2).unwrap();
+        assert_eq!(depth, vec![(300.0, 20), (310.0, 20)]);
+
+        assert_eq!(consolidated_depth(&[&book("JPY")], Side::Ask, 1), None);
+        assert_eq!(OrderBook::new().conversion_rate(), None);
+    }
+}
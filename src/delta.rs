@@ -0,0 +1,259 @@
+use crate::{LevelUpdate, OrderBook, Snapshot};
+use std::collections::VecDeque;
+
+/// Changes of the depth of an order book, identified by a sequence number
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Delta {
+    /// Sequence number of the delta, consecutive from one
+    pub seq: u64,
+
+    /// Level updates of the delta, in order
+    pub updates: Vec<LevelUpdate>,
+}
+
+/// Data sent to a subscriber recovering from a gap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovery {
+    /// Depth to restart from with the sequence number it reflects, `None` if the
+    /// deltas alone cover the gap
+    pub snapshot: Option<(u64, Snapshot)>,
+
+    /// Deltas to apply next, in order
+    pub deltas: Vec<Delta>,
+}
+
+/// Publisher of sequenced depth deltas of an order book
+///
+/// The publisher retains a bounded ring of its most recent deltas along with the depth
+/// preceding the oldest of them. A subscriber that detects a gap asks to replay from
+/// the first sequence number it missed: while the ring still holds it, the missing
+/// deltas are enough, otherwise the retained depth is sent along with every delta of
+/// the ring, so recovery never needs a fresh snapshot of the order book.
+#[derive(Debug, Clone)]
+pub struct DeltaPublisher {
+    /// Maximum number of deltas retained
+    capacity: usize,
+
+    /// Most recent deltas, oldest first
+    ring: VecDeque<Delta>,
+
+    /// Depth preceding the oldest retained delta
+    base: Snapshot,
+
+    /// Sequence number reflected in the base depth
+    base_seq: u64,
+
+    /// Depth last published, to diff against
+    last_depth: Snapshot,
+
+    /// Sequence number of the last delta
+    seq: u64,
+}
+
+impl DeltaPublisher {
+    /// Create a new publisher starting from an empty order book
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of recent deltas retained for recovery, at least one
+    pub fn new(capacity: usize) -> DeltaPublisher {
+        DeltaPublisher {
+            capacity: capacity.max(1),
+            ring: VecDeque::new(),
+            base: Snapshot::default(),
+            base_seq: 0,
+            last_depth: Snapshot::default(),
+            seq: 0,
+        }
+    }
+
+    /// Get the sequence number of the last delta, zero before the first one
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Publish the changes of the depth of an order book since the last call
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to publish the depth of
+    ///
+    /// # Returns
+    ///
+    /// The new delta to send to the subscribers, `None` if the depth did not change
+    pub fn publish(&mut self, book: &OrderBook) -> Option<Delta> {
+        let snapshot = book.snapshot();
+        let updates = self.last_depth.diff(&snapshot);
+        if updates.is_empty() {
+            return None;
+        }
+        self.last_depth = snapshot;
+        self.seq += 1;
+        let delta = Delta {
+            seq: self.seq,
+            updates,
+        };
+        if self.ring.len() == self.capacity {
+            let oldest = self.ring.pop_front().expect("full ring");
+            for update in &oldest.updates {
+                self.base.apply(update);
+            }
+            self.base_seq = oldest.seq;
+        }
+        self.ring.push_back(delta.clone());
+        Some(delta)
+    }
+
+    /// Serve a subscriber that missed deltas
+    ///
+    /// # Arguments
+    ///
+    /// * `from_seq` - The first sequence number the subscriber missed
+    ///
+    /// # Returns
+    ///
+    /// The data bringing the subscriber up to date
+    pub fn recover(&self, from_seq: u64) -> Recovery {
+        if from_seq > self.base_seq {
+            let skip = (from_seq - self.base_seq - 1) as usize;
+            return Recovery {
+                snapshot: None,
+                deltas: self.ring.iter().skip(skip).cloned().collect(),
+            };
+        }
+        Recovery {
+            snapshot: Some((self.base_seq, self.base.clone())),
+            deltas: self.ring.iter().cloned().collect(),
+        }
+    }
+}
+
+/// What a delta did to the depth maintained by a subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaEvent {
+    /// Delta was applied
+    Applied,
+
+    /// Delta was already applied and ignored
+    Stale,
+
+    /// Delta is past the next expected one, recovery should be requested from the
+    /// given sequence number
+    Gap(u64),
+}
+
+/// Depth of an order book maintained from sequenced deltas
+#[derive(Debug, Clone, Default)]
+pub struct DeltaSubscriber {
+    /// Maintained depth
+    book: Snapshot,
+
+    /// Sequence number of the last delta applied
+    seq: u64,
+}
+
+impl DeltaSubscriber {
+    /// Create a new subscriber starting from an empty order book
+    pub fn new() -> DeltaSubscriber {
+        DeltaSubscriber::default()
+    }
+
+    /// Get the maintained depth
+    pub fn book(&self) -> &Snapshot {
+        &self.book
+    }
+
+    /// Get the sequence number of the last delta applied
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Apply the next delta received
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The delta to apply
+    ///
+    /// # Returns
+    ///
+    /// What the delta did, a gap leaves the depth unchanged
+    pub fn on_delta(&mut self, delta: &Delta) -> DeltaEvent {
+        if delta.seq <= self.seq {
+            return DeltaEvent::Stale;
+        }
+        if delta.seq > self.seq + 1 {
+            return DeltaEvent::Gap(self.seq + 1);
+        }
+        for update in &delta.updates {
+            self.book.apply(update);
+        }
+        self.seq = delta.seq;
+        DeltaEvent::Applied
+    }
+
+    /// Apply the data served by the publisher to recover from a gap
+    ///
+    /// # Arguments
+    ///
+    /// * `recovery` - The recovery data
+    ///
+    /// # Returns
+    ///
+    /// The first sequence number still missing if the recovery was not contiguous,
+    /// which happens if the publisher moved on, `None` once caught up
+    pub fn recover(&mut self, recovery: &Recovery) -> Option<u64> {
+        if let Some((seq, snapshot)) = &recovery.snapshot {
+            self.book = snapshot.clone();
+            self.seq = *seq;
+        }
+        for delta in &recovery.deltas {
+            if let DeltaEvent::Gap(missing) = self.on_delta(delta) {
+                return Some(missing);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_gap_recovery() {
+        let mut book = OrderBook::new();
+        let mut publisher = DeltaPublisher::new(3);
+        let mut subscriber = DeltaSubscriber::new();
+        assert_eq!(publisher.publish(&book), None);
+
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        let first = publisher.publish(&book).unwrap();
+        assert_eq!(subscriber.on_delta(&first), DeltaEvent::Applied);
+        assert_eq!(subscriber.on_delta(&first), DeltaEvent::Stale);
+
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        publisher.publish(&book);
+        book.add(1, Side::Bid, 98, 10).unwrap();
+        let third = publisher.publish(&book).unwrap();
+        assert_eq!(subscriber.on_delta(&third), DeltaEvent::Gap(2));
+        let recovery = publisher.recover(2);
+        assert_eq!(recovery.snapshot, None);
+        assert_eq!(recovery.deltas.len(), 2);
+        assert_eq!(subscriber.recover(&recovery), None);
+        assert_eq!(subscriber.book(), &book.snapshot());
+
+        for price in 102..106 {
+            book.add(1, Side::Ask, price, 5).unwrap();
+            publisher.publish(&book);
+        }
+        book.execute(2, Side::Bid, 102, 15);
+        let last = publisher.publish(&book).unwrap();
+        assert_eq!(subscriber.on_delta(&last), DeltaEvent::Gap(4));
+        let recovery = publisher.recover(4);
+        assert_eq!(recovery.snapshot.as_ref().map(|s| s.0), Some(5));
+        assert_eq!(subscriber.recover(&recovery), None);
+        assert_eq!(subscriber.seq(), publisher.seq());
+        assert_eq!(subscriber.book(), &book.snapshot());
+    }
+}
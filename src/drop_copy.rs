@@ -0,0 +1,156 @@
+use crate::{Execution, OrderId, OwnerId, Side};
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::mpsc::Sender;
+
+/// Consumer of the execution reports of an order book
+pub trait ExecutionSink: fmt::Debug {
+    /// Handle a single execution report
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The execution that just happened
+    fn on_execution(&mut self, execution: &Execution);
+
+    /// Handle the correction of an execution reported earlier that was busted
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The execution as originally reported
+    fn on_bust(&mut self, _execution: &Execution) {}
+
+    /// Handle the transfer of a resting order to another owner
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    /// * `from` - The previous owner of the order
+    /// * `to` - The new owner of the order
+    fn on_transfer(&mut self, _id: OrderId, _from: OwnerId, _to: OwnerId) {}
+}
+
+/// Forward executions over a channel, dropping them once the receiver hangs up
+impl ExecutionSink for Sender<Execution> {
+    fn on_execution(&mut self, execution: &Execution) {
+        let _ = self.send(*execution);
+    }
+}
+
+/// Writes executions as comma separated lines to any writer, e.g. a `TcpStream`
+///
+/// Each line holds the maker, maker owner, maker tag, taker, taker owner, taker tag, taker
+/// side (`B` or `S`), price and quantity. Busted executions are written again as
+/// corrections, prefixed by `X,`. Writing stops at the first I/O error, which is
+/// kept until taken.
+#[derive(Debug)]
+pub struct WriteSink<W: Write + fmt::Debug> {
+    /// Underlying writer
+    inner: W,
+
+    /// First error encountered while writing
+    error: Option<io::Error>,
+}
+
+impl<W: Write + fmt::Debug> WriteSink<W> {
+    /// Create a new write sink
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The writer to write executions to
+    pub fn new(inner: W) -> WriteSink<W> {
+        WriteSink { inner, error: None }
+    }
+
+    /// Take the error that stopped the sink, if any, resuming writes
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Consume the sink, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write an execution as a line, after a prefix
+    fn write(&mut self, prefix: &str, execution: &Execution) {
+        if self.error.is_some() {
+            return;
+        }
+        let side = match execution.side {
+            Side::Bid => 'B',
+            Side::Ask => 'S',
+        };
+        if let Err(err) = writeln!(
+            self.inner,
+            "{}{},{},{},{},{},{},{},{},{}",
+            prefix,
+            execution.maker.0,
+            execution.maker_owner,
+            execution.maker_tag,
+            execution.taker.0,
+            execution.taker_owner,
+            execution.taker_tag,
+            side,
+            execution.price,
+            execution.qty
+        ) {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl<W: Write + fmt::Debug> ExecutionSink for WriteSink<W> {
+    fn on_execution(&mut self, execution: &Execution) {
+        self.write("", execution);
+    }
+
+    fn on_bust(&mut self, execution: &Execution) {
+        self.write("X,", execution);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderBook;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_drop_copy_channel() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_drop_copy(tx);
+        let a = book.add(1, Side::Ask, 101, 10).unwrap();
+        let b = book.add(2, Side::Ask, 102, 10).unwrap();
+        let result = book.execute(3, Side::Bid, 102, 15);
+        book.execute(4, Side::Ask, 90, 1);
+
+        let reports: Vec<_> = rx.try_iter().collect();
+        assert_eq!(result.orders.len(), 2);
+        assert_eq!(reports.len(), 2);
+        assert_eq!((reports[0].maker, reports[0].maker_owner), (a, 1));
+        assert_eq!((reports[1].maker, reports[1].maker_owner), (b, 2));
+        assert!(reports.iter().all(|e| e.taker_owner == 3));
+        assert_eq!(reports[1].qty, 5);
+    }
+
+    #[test]
+    fn test_write_sink() {
+        let mut sink = WriteSink::new(Vec::new());
+        sink.on_execution(&Execution {
+            trade: 1,
+            maker: crate::OrderId(1),
+            maker_owner: 2,
+            maker_tag: 0,
+            taker: crate::OrderId(3),
+            taker_owner: 4,
+            taker_tag: 9,
+            side: Side::Ask,
+            price: 100,
+            qty: 7,
+            tick: crate::TickDirection::Zero,
+        });
+        assert!(sink.take_error().is_none());
+        assert_eq!(sink.into_inner(), b"1,2,0,3,4,9,S,100,7\n");
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{
+    Command, CommandResult, Exchange, ExchangeError, InstrumentState, Journal, JournalEntry,
+    Snapshot,
+};
+use std::collections::HashMap;
+use std::panic;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+
+/// Event published by an engine to its subscribers
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// Command was applied to the exchange
+    Applied {
+        /// Sequence number of the command, starting at one
+        seq: u64,
+
+        /// Symbol of the instrument the command was for
+        symbol: String,
+
+        /// Result of the command, or the reason the exchange rejected it
+        result: Result<CommandResult, ExchangeError>,
+    },
+
+    /// Engine shut down, no event follows
+    Terminated {
+        /// Sequence number of the last command applied, zero if none was
+        seq: u64,
+    },
+}
+
+/// Final state of an instrument handed off by an engine that shut down
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookHandoff {
+    /// Lifecycle state of the instrument
+    pub state: InstrumentState,
+
+    /// Full depth of the order book, or its archived depth if delisted
+    pub snapshot: Snapshot,
+
+    /// Journal of the commands applied to the order book, ending with a checkpoint of
+    /// the final state unless the instrument was delisted
+    pub journal: Vec<JournalEntry>,
+}
+
+/// Final state of an engine that shut down
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handoff {
+    /// Sequence number of the last command applied, as sent to subscribers
+    pub seq: u64,
+
+    /// Final state of every instrument ever listed, by symbol
+    pub books: HashMap<String, BookHandoff>,
+}
+
+/// Request to the thread of an engine
+#[derive(Debug)]
+enum Request {
+    /// Apply a command to the order book of an instrument
+    Apply {
+        /// Symbol of the instrument
+        symbol: String,
+
+        /// The command
+        command: Command,
+    },
+
+    /// Publish the following events to a new subscriber
+    Subscribe(Sender<EngineEvent>),
+
+    /// Stop, once the commands queued before are applied
+    Shutdown,
+}
+
+/// State of the thread of an engine
+#[derive(Debug)]
+struct Worker {
+    /// The exchange
+    exchange: Exchange,
+
+    /// Journal of each instrument that was sent commands
+    journals: HashMap<String, Journal>,
+
+    /// Number of commands between journal checkpoints
+    interval: u64,
+
+    /// Subscribers to the events of the engine
+    subscribers: Vec<Sender<EngineEvent>>,
+
+    /// Sequence number of the last command applied
+    seq: u64,
+}
+
+impl Worker {
+    /// Publish an event to every subscriber, dropping those that hung up
+    fn publish(&mut self, event: EngineEvent) {
+        (self.subscribers).retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Handle a request
+    ///
+    /// # Returns
+    ///
+    /// Whether the request was to shut down
+    fn handle(&mut self, request: Request) -> bool {
+        let (symbol, command) = match request {
+            Request::Apply { symbol, command } => (symbol, command),
+            Request::Subscribe(subscriber) => {
+                self.subscribers.push(subscriber);
+                return false;
+            }
+            Request::Shutdown => return true,
+        };
+        self.seq += 1;
+        let result = self.exchange.apply(&symbol, &command);
+        if let (Ok(_), Some(book)) = (&result, self.exchange.book(&symbol)) {
+            (self.journals.entry(symbol.clone()))
+                .or_insert_with(|| Journal::new(self.interval))
+                .record(book, command);
+        }
+        self.publish(EngineEvent::Applied {
+            seq: self.seq,
+            symbol,
+            result,
+        });
+        false
+    }
+
+    /// Flush the journals and collect the final state of every instrument
+    fn finish(mut self) -> Handoff {
+        let symbols: Vec<_> = self.exchange.symbols().map(str::to_string).collect();
+        let mut books = HashMap::new();
+        for symbol in symbols {
+            let mut journal = self.journals.remove(&symbol).unwrap_or_default();
+            let snapshot = match self.exchange.book(&symbol) {
+                Some(book) => {
+                    journal.checkpoint(book);
+                    book.snapshot()
+                }
+                None => (self.exchange.archive(&symbol))
+                    .map(|archive| archive.snapshot.clone())
+                    .unwrap_or_default(),
+            };
+            let handoff = BookHandoff {
+                state: self
+                    .exchange
+                    .state(&symbol)
+                    .unwrap_or(InstrumentState::Delisted),
+                snapshot,
+                journal: journal.entries().to_vec(),
+            };
+            books.insert(symbol, handoff);
+        }
+        self.publish(EngineEvent::Terminated { seq: self.seq });
+        Handoff {
+            seq: self.seq,
+            books,
+        }
+    }
+}
+
+/// Sender of commands to an engine, cheap to clone and send to other threads
+#[derive(Debug, Clone)]
+pub struct EngineHandle {
+    /// Queue of requests to the thread of the engine
+    requests: Sender<Request>,
+
+    /// Whether the engine still takes commands, write-locked to close it so no command
+    /// is queued once the engine shuts down
+    open: Arc<RwLock<bool>>,
+}
+
+impl EngineHandle {
+    /// Queue a command for the order book of an instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    /// * `command` - The command to apply
+    ///
+    /// # Returns
+    ///
+    /// Whether the command was queued, and will be applied, false once the engine
+    /// started shutting down
+    pub fn submit(&self, symbol: &str, command: Command) -> bool {
+        let open = self
+            .open
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !*open {
+            return false;
+        }
+        let symbol = symbol.to_string();
+        self.requests
+            .send(Request::Apply { symbol, command })
+            .is_ok()
+    }
+
+    /// Refuse further commands, waiting for the commands being queued
+    fn close(&self) {
+        *self
+            .open
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = false;
+    }
+}
+
+/// Exchange running on its own thread, applying the commands queued by any number of
+/// threads in order
+///
+/// Order books are not `Send`, so the exchange is built on the thread of the engine
+/// and its final state is handed back as depth snapshots and journals, which rebuild
+/// each order book when replayed into one configured like the original.
+#[derive(Debug)]
+pub struct Engine {
+    /// Sender of commands to the engine
+    handle: EngineHandle,
+
+    /// Thread of the engine
+    worker: JoinHandle<Handoff>,
+}
+
+impl Engine {
+    /// Start an engine
+    ///
+    /// # Arguments
+    ///
+    /// * `setup` - The function building the exchange, called on the thread of the
+    ///   engine
+    /// * `interval` - The number of commands between journal checkpoints, zero for
+    ///   none but the final one
+    pub fn spawn<F>(setup: F, interval: u64) -> Engine
+    where
+        F: FnOnce() -> Exchange + Send + 'static,
+    {
+        let (requests, queue) = channel();
+        let worker = thread::spawn(move || {
+            let mut worker = Worker {
+                exchange: setup(),
+                journals: HashMap::new(),
+                interval,
+                subscribers: Vec::new(),
+                seq: 0,
+            };
+            for request in &queue {
+                if worker.handle(request) {
+                    break;
+                }
+            }
+            drop(queue);
+            worker.finish()
+        });
+        let open = Arc::new(RwLock::new(true));
+        Engine {
+            handle: EngineHandle { requests, open },
+            worker,
+        }
+    }
+
+    /// Get a sender of commands to the engine, for other threads
+    pub fn handle(&self) -> EngineHandle {
+        self.handle.clone()
+    }
+
+    /// Queue a command for the order book of an instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    /// * `command` - The command to apply
+    ///
+    /// # Returns
+    ///
+    /// Whether the command was queued, false if the thread of the engine died
+    pub fn submit(&self, symbol: &str, command: Command) -> bool {
+        self.handle.submit(symbol, command)
+    }
+
+    /// Subscribe to the events of the engine
+    ///
+    /// # Returns
+    ///
+    /// The receiver of the events following the commands queued so far
+    pub fn subscribe(&self) -> Receiver<EngineEvent> {
+        let (events, receiver) = channel();
+        let _ = self.handle.requests.send(Request::Subscribe(events));
+        receiver
+    }
+
+    /// Shut the engine down and hand off its final state
+    ///
+    /// Intake is closed first: commands being queued by other handles are let through
+    /// and applied, later ones are refused. The journals are then flushed with a
+    /// checkpoint of the final state and subscribers are sent the terminal sequence
+    /// number.
+    ///
+    /// # Returns
+    ///
+    /// The final state of the exchange
+    pub fn shutdown(self) -> Handoff {
+        self.handle.close();
+        let _ = self.handle.requests.send(Request::Shutdown);
+        drop(self.handle);
+        (self.worker.join()).unwrap_or_else(|panic| panic::resume_unwind(panic))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{replay, OrderBook, SequentialIds, Side};
+
+    #[test]
+    fn test_graceful_shutdown() {
+        let builder = || OrderBook::builder().id_generator(SequentialIds::new(1));
+        let engine = Engine::spawn(
+            move || {
+                let mut exchange = Exchange::new();
+                exchange.list("ABC", builder()).unwrap();
+                exchange
+            },
+            2,
+        );
+        let events = engine.subscribe();
+        let handle = engine.handle();
+        let add = |side, price| Command::Add {
+            owner: 1,
+            side,
+            price,
+            qty: 10,
+            tag: 0,
+        };
+        thread::spawn(move || {
+            for price in 100..103 {
+                assert!(handle.submit("ABC", add(Side::Ask, price)));
+            }
+        })
+        .join()
+        .unwrap();
+        engine.submit("XYZ", add(Side::Bid, 99));
+        engine.submit("ABC", add(Side::Bid, 99));
+        let late = engine.handle();
+        let handoff = engine.shutdown();
+        assert!(!late.submit("ABC", add(Side::Bid, 98)));
+
+        let events: Vec<_> = events.iter().collect();
+        assert_eq!(events.len(), 6);
+        assert!(matches!(
+            &events[3],
+            EngineEvent::Applied {
+                seq: 4,
+                result: Err(ExchangeError::UnknownSymbol),
+                ..
+            }
+        ));
+        assert_eq!(events[5], EngineEvent::Terminated { seq: 5 });
+        assert_eq!(handoff.seq, 5);
+
+        let abc = &handoff.books["ABC"];
+        assert_eq!(abc.state, InstrumentState::Active);
+        assert_eq!(abc.snapshot.asks.len(), 3);
+        assert!(matches!(
+            abc.journal.last(),
+            Some(JournalEntry::Checkpoint { commands: 4, .. })
+        ));
+        let mut book = builder().build();
+        assert_eq!(replay(&mut book, &abc.journal), Ok(()));
+        assert_eq!(book.snapshot(), abc.snapshot);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    Command, CommandResult, OrderBook, OrderBookBuilder, OrderView, Side, Snapshot, Trade,
+};
+use std::collections::HashMap;
+
+/// Lifecycle state of an instrument listed on an exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstrumentState {
+    /// Orders are accepted
+    Active,
+
+    /// Only cancels are accepted, resting orders are kept
+    Suspended,
+
+    /// Instrument was removed, every command is rejected
+    Delisted,
+}
+
+/// Reason a request to an exchange was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeError {
+    /// No instrument was ever listed under the symbol
+    UnknownSymbol,
+
+    /// An instrument is already listed under the symbol
+    AlreadyListed,
+
+    /// Instrument is suspended and only accepts cancels
+    Suspended,
+
+    /// Instrument was delisted
+    Delisted,
+}
+
+/// Final state of a delisted instrument
+#[derive(Debug, Clone, PartialEq)]
+pub struct Archive {
+    /// Depth of the order book when the instrument was delisted
+    pub snapshot: Snapshot,
+
+    /// Trades on the tape of the order book, oldest first
+    pub tape: Vec<Trade>,
+
+    /// Orders that expired when the instrument was delisted
+    pub expired: Vec<OrderView>,
+}
+
+/// Instrument listed on an exchange
+#[derive(Debug)]
+enum Listing {
+    /// Instrument that is trading or suspended
+    Listed {
+        /// Order book of the instrument
+        book: Box<OrderBook>,
+
+        /// Whether the instrument is suspended
+        suspended: bool,
+    },
+
+    /// Instrument that was delisted
+    Delisted(Archive),
+}
+
+/// Set of order books keyed by symbol, whose instruments are listed and delisted at
+/// runtime
+#[derive(Debug, Default)]
+pub struct Exchange {
+    /// Instruments ever listed, by symbol
+    listings: HashMap<String, Listing>,
+}
+
+impl Exchange {
+    /// Create a new exchange without instruments
+    pub fn new() -> Exchange {
+        Exchange::default()
+    }
+
+    /// List a new instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument, which can not be reused once delisted
+    /// * `builder` - The configuration of the order book of the instrument
+    pub fn list(&mut self, symbol: &str, builder: OrderBookBuilder) -> Result<(), ExchangeError> {
+        if self.listings.contains_key(symbol) {
+            return Err(ExchangeError::AlreadyListed);
+        }
+        let listing = Listing::Listed {
+            book: Box::new(builder.build()),
+            suspended: false,
+        };
+        self.listings.insert(symbol.to_string(), listing);
+        Ok(())
+    }
+
+    /// Iterate over the symbols of the instruments ever listed, in no particular order
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.listings.keys().map(String::as_str)
+    }
+
+    /// Get the lifecycle state of an instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    ///
+    /// # Returns
+    ///
+    /// The state of the instrument, `None` if it was never listed
+    pub fn state(&self, symbol: &str) -> Option<InstrumentState> {
+        Some(match self.listings.get(symbol)? {
+            Listing::Listed {
+                suspended: false, ..
+            } => InstrumentState::Active,
+            Listing::Listed { .. } => InstrumentState::Suspended,
+            Listing::Delisted(_) => InstrumentState::Delisted,
+        })
+    }
+
+    /// Get the order book of a listed instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    ///
+    /// # Returns
+    ///
+    /// The order book, `None` if the instrument is not listed
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        match self.listings.get(symbol)? {
+            Listing::Listed { book, .. } => Some(book.as_ref()),
+            Listing::Delisted(_) => None,
+        }
+    }
+
+    /// Get the archive of a delisted instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    ///
+    /// # Returns
+    ///
+    /// The archive, `None` if the instrument was not delisted
+    pub fn archive(&self, symbol: &str) -> Option<&Archive> {
+        match self.listings.get(symbol)? {
+            Listing::Delisted(archive) => Some(archive),
+            Listing::Listed { .. } => None,
+        }
+    }
+
+    /// Get a listed instrument, whether suspended or not
+    fn listed(&mut self, symbol: &str) -> Result<(&mut OrderBook, &mut bool), ExchangeError> {
+        match self.listings.get_mut(symbol) {
+            None => Err(ExchangeError::UnknownSymbol),
+            Some(Listing::Delisted(_)) => Err(ExchangeError::Delisted),
+            Some(Listing::Listed { book, suspended }) => Ok((book.as_mut(), suspended)),
+        }
+    }
+
+    /// Suspend an instrument, halting new orders until it resumes
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    pub fn suspend(&mut self, symbol: &str) -> Result<(), ExchangeError> {
+        *self.listed(symbol)?.1 = true;
+        Ok(())
+    }
+
+    /// Resume trading of a suspended instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    pub fn resume(&mut self, symbol: &str) -> Result<(), ExchangeError> {
+        *self.listed(symbol)?.1 = false;
+        Ok(())
+    }
+
+    /// Delist an instrument
+    ///
+    /// Every resting and pending stop order is canceled and expires, the final depth and
+    /// tape of the order book are archived and further commands for the symbol are
+    /// rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    ///
+    /// # Returns
+    ///
+    /// The expired orders in priority order, bids first, followed by the pending stop
+    /// orders at their limit price
+    pub fn delist(&mut self, symbol: &str) -> Result<Vec<OrderView>, ExchangeError> {
+        let (book, _) = self.listed(symbol)?;
+        let snapshot = book.snapshot();
+        let tape = book.tape().copied().collect();
+        let resting = book.orders(Side::Bid).chain(book.orders(Side::Ask));
+        let mut expired: Vec<_> = resting.collect();
+        let stops: Vec<_> = book.stop_orders().collect();
+        for stop in &stops {
+            book.cancel_stop(stop.id);
+        }
+        for order in &expired {
+            book.force_cancel(order.id);
+        }
+        expired.extend(stops);
+        let archive = Archive {
+            snapshot,
+            tape,
+            expired: expired.clone(),
+        };
+        self.listings
+            .insert(symbol.to_string(), Listing::Delisted(archive));
+        Ok(expired)
+    }
+
+    /// Apply a command to the order book of an instrument
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol of the instrument
+    /// * `command` - The command to apply
+    ///
+    /// # Returns
+    ///
+    /// The result of the command, or the reason the instrument rejected it
+    pub fn apply(
+        &mut self,
+        symbol: &str,
+        command: &Command,
+    ) -> Result<CommandResult, ExchangeError> {
+        let (book, suspended) = self.listed(symbol)?;
+        let cancel = matches!(
+            command,
+            Command::Cancel { .. } | Command::CancelAll { .. } | Command::CancelStop { .. }
+        );
+        if *suspended && !cancel {
+            return Err(ExchangeError::Suspended);
+        }
+        Ok(command.apply(book))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_instrument_lifecycle() {
+        let mut exchange = Exchange::new();
+        exchange.list("ABC", OrderBook::builder().tape(10)).unwrap();
+        assert_eq!(
+            exchange.list("ABC", OrderBook::builder()),
+            Err(ExchangeError::AlreadyListed)
+        );
+        let add = |side, price| Command::Add {
+            owner: 1,
+            side,
+            price,
+            qty: 10,
+            tag: 0,
+        };
+        exchange.apply("ABC", &add(Side::Ask, 101)).unwrap();
+        exchange.apply("ABC", &add(Side::Bid, 99)).unwrap();
+        let buy = Command::Execute {
+            owner: 2,
+            side: Side::Bid,
+            price: 101,
+            qty: 4,
+            tag: 0,
+        };
+        exchange.apply("ABC", &buy).unwrap();
+        let stop = exchange
+            .listed("ABC")
+            .unwrap()
+            .0
+            .add_stop(3, Side::Ask, 95, 94, 5);
+
+        exchange.suspend("ABC").unwrap();
+        assert_eq!(exchange.state("ABC"), Some(InstrumentState::Suspended));
+        assert_eq!(exchange.apply("ABC", &buy), Err(ExchangeError::Suspended));
+        exchange.resume("ABC").unwrap();
+
+        let expired = exchange.delist("ABC").unwrap();
+        assert_eq!(expired.len(), 3);
+        assert_eq!((expired[1].side, expired[1].qty), (Side::Ask, 6));
+        assert_eq!((expired[2].id, expired[2].price), (stop, 94));
+        let archive = exchange.archive("ABC").unwrap();
+        assert_eq!(archive.snapshot.asks, vec![(101, 6)]);
+        assert_eq!(archive.tape.len(), 1);
+        assert_eq!(exchange.apply("ABC", &buy), Err(ExchangeError::Delisted));
+        assert_eq!(exchange.suspend("ABC"), Err(ExchangeError::Delisted));
+        assert!(exchange.book("ABC").is_none());
+        assert_eq!(
+            exchange.apply("XYZ", &buy),
+            Err(ExchangeError::UnknownSymbol)
+        );
+    }
+}
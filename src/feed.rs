@@ -0,0 +1,292 @@
+use crate::{LevelUpdate, OrderQty, Price, Side, Snapshot};
+use std::io;
+
+/// Level updates decoded from a market data message, covering a range of sequence numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedDiff {
+    /// First sequence number covered by the message
+    pub first_seq: u64,
+
+    /// Last sequence number covered by the message
+    pub last_seq: u64,
+
+    /// Level updates of the message, in order
+    pub updates: Vec<LevelUpdate>,
+
+    /// Whether every update consumes its own sequence number, in which case the updates
+    /// already reflected in a snapshot are skipped instead of applied again
+    pub sequenced_updates: bool,
+}
+
+/// Translation of the market data format of a venue
+pub trait FeedAdapter {
+    /// Message received from the venue
+    type Message: ?Sized;
+
+    /// Fetch a full snapshot of the order book
+    ///
+    /// # Returns
+    ///
+    /// The last sequence number reflected in the snapshot and the snapshot
+    fn fetch_snapshot(&mut self) -> io::Result<(u64, Snapshot)>;
+
+    /// Decode a message into level updates
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to decode
+    ///
+    /// # Returns
+    ///
+    /// The decoded updates, or an error if the message is malformed
+    fn decode(&mut self, message: &Self::Message) -> io::Result<FeedDiff>;
+}
+
+/// What a message did to the maintained order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedEvent {
+    /// Message was applied
+    Applied,
+
+    /// Message was older than the order book and ignored
+    Stale,
+
+    /// A gap was detected, the order book was resynchronized from a snapshot before
+    /// the message was processed
+    Resynced,
+}
+
+/// Maintains an order book from the market data of a venue
+///
+/// Diffs are applied in sequence. A diff starting past the next expected sequence number
+/// reveals a gap, on which the order book is rebuilt from a fresh snapshot; the first
+/// message also triggers a snapshot fetch.
+#[derive(Debug)]
+pub struct FeedSync<A: FeedAdapter> {
+    /// Adapter of the venue
+    adapter: A,
+
+    /// Maintained order book
+    book: Snapshot,
+
+    /// Last sequence number applied, `None` until synchronized
+    last_seq: Option<u64>,
+
+    /// Number of snapshots fetched
+    resyncs: u64,
+}
+
+impl<A: FeedAdapter> FeedSync<A> {
+    /// Create a new, unsynchronized, feed maintenance loop
+    ///
+    /// # Arguments
+    ///
+    /// * `adapter` - The adapter of the venue
+    pub fn new(adapter: A) -> FeedSync<A> {
+        FeedSync {
+            adapter,
+            book: Snapshot::default(),
+            last_seq: None,
+            resyncs: 0,
+        }
+    }
+
+    /// Get the maintained order book
+    pub fn book(&self) -> &Snapshot {
+        &self.book
+    }
+
+    /// Get the last sequence number applied, `None` until synchronized
+    pub fn last_seq(&self) -> Option<u64> {
+        self.last_seq
+    }
+
+    /// Get the number of snapshots fetched so far
+    pub fn resyncs(&self) -> u64 {
+        self.resyncs
+    }
+
+    /// Get the adapter of the venue
+    pub fn adapter(&mut self) -> &mut A {
+        &mut self.adapter
+    }
+
+    /// Rebuild the order book from a fresh snapshot
+    pub fn resync(&mut self) -> io::Result<()> {
+        self.last_seq = None;
+        let (seq, snapshot) = self.adapter.fetch_snapshot()?;
+        self.book = snapshot;
+        self.last_seq = Some(seq);
+        self.resyncs += 1;
+        Ok(())
+    }
+
+    /// Process a message received from the venue
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to process
+    ///
+    /// # Returns
+    ///
+    /// What the message did, or an error if the message could not be decoded, the
+    /// snapshot could not be fetched or the snapshot is older than the message, in
+    /// which case synchronization is retried on the next message
+    pub fn on_message(&mut self, message: &A::Message) -> io::Result<FeedEvent> {
+        let diff = self.adapter.decode(message)?;
+        let gap = |last: Option<u64>| last.is_none_or(|last| diff.first_seq > last + 1);
+        let mut event = FeedEvent::Applied;
+        if gap(self.last_seq) {
+            self.resync()?;
+            event = FeedEvent::Resynced;
+            if gap(self.last_seq) {
+                self.last_seq = None;
+                return Err(io::Error::other("snapshot older than the feed"));
+            }
+        }
+        let last = self.last_seq.unwrap_or_default();
+        if diff.last_seq <= last {
+            return Ok(match event {
+                FeedEvent::Resynced => FeedEvent::Resynced,
+                _ => FeedEvent::Stale,
+            });
+        }
+        let skip = match diff.sequenced_updates {
+            true => (last + 1 - diff.first_seq) as usize,
+            false => 0,
+        };
+        diff.updates
+            .iter()
+            .skip(skip)
+            .for_each(|u| self.book.apply(u));
+        self.last_seq = Some(diff.last_seq);
+        Ok(event)
+    }
+}
+
+/// Depth diff of venues numbering whole messages with an update id range, such as the
+/// diff depth streams of most crypto exchanges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepthDiff {
+    /// First update id of the message
+    pub first_update_id: u64,
+
+    /// Last update id of the message
+    pub final_update_id: u64,
+
+    /// Changed bid levels as (price, new total quantity), zero removing the level
+    pub bids: Vec<(Price, OrderQty)>,
+
+    /// Changed ask levels as (price, new total quantity), zero removing the level
+    pub asks: Vec<(Price, OrderQty)>,
+}
+
+/// Adapter of update id ranged depth diffs, fetching snapshots with a closure
+#[derive(Debug)]
+pub struct DepthDiffAdapter<F: FnMut() -> io::Result<(u64, Snapshot)>> {
+    /// Source of snapshots, returning the last update id reflected and the snapshot
+    fetch: F,
+}
+
+impl<F: FnMut() -> io::Result<(u64, Snapshot)>> DepthDiffAdapter<F> {
+    /// Create a new depth diff adapter
+    ///
+    /// # Arguments
+    ///
+    /// * `fetch` - The source of snapshots, e.g. a REST request
+    pub fn new(fetch: F) -> DepthDiffAdapter<F> {
+        DepthDiffAdapter { fetch }
+    }
+}
+
+impl<F: FnMut() -> io::Result<(u64, Snapshot)>> FeedAdapter for DepthDiffAdapter<F> {
+    type Message = DepthDiff;
+
+    fn fetch_snapshot(&mut self) -> io::Result<(u64, Snapshot)> {
+        (self.fetch)()
+    }
+
+    fn decode(&mut self, message: &DepthDiff) -> io::Result<FeedDiff> {
+        if message.final_update_id < message.first_update_id {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "update id range is reversed",
+            ));
+        }
+        let level = |side| {
+            move |(price, qty): &(Price, OrderQty)| LevelUpdate {
+                side,
+                price: *price,
+                qty: *qty,
+            }
+        };
+        Ok(FeedDiff {
+            first_seq: message.first_update_id,
+            last_seq: message.final_update_id,
+            updates: (message.bids.iter().map(level(Side::Bid)))
+                .chain(message.asks.iter().map(level(Side::Ask)))
+                .collect(),
+            sequenced_updates: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_diff_sync() {
+        let mut snapshots = vec![
+            (
+                20,
+                Snapshot {
+                    bids: vec![(99, 1)],
+                    asks: vec![(101, 1)],
+                },
+            ),
+            (
+                10,
+                Snapshot {
+                    bids: vec![(100, 5)],
+                    asks: vec![(101, 5)],
+                },
+            ),
+        ];
+        let adapter = DepthDiffAdapter::new(move || Ok(snapshots.pop().unwrap()));
+        let mut feed = FeedSync::new(adapter);
+        let diff = |first, last, bids: &[(Price, OrderQty)]| DepthDiff {
+            first_update_id: first,
+            final_update_id: last,
+            bids: bids.to_vec(),
+            asks: vec![],
+        };
+
+        assert_eq!(
+            feed.on_message(&diff(5, 8, &[(100, 1)])).unwrap(),
+            FeedEvent::Resynced
+        );
+        assert_eq!(feed.book().bids, vec![(100, 5)]);
+        assert_eq!(
+            feed.on_message(&diff(9, 12, &[(100, 7)])).unwrap(),
+            FeedEvent::Applied
+        );
+        assert_eq!(
+            feed.on_message(&diff(11, 12, &[(100, 1)])).unwrap(),
+            FeedEvent::Stale
+        );
+        assert_eq!(
+            feed.on_message(&diff(13, 13, &[(100, 0), (98, 2)]))
+                .unwrap(),
+            FeedEvent::Applied
+        );
+        assert_eq!(feed.book().bids, vec![(98, 2)]);
+
+        assert_eq!(
+            feed.on_message(&diff(15, 21, &[(97, 3)])).unwrap(),
+            FeedEvent::Resynced
+        );
+        assert_eq!(feed.book().bids, vec![(99, 1), (97, 3)]);
+        assert_eq!((feed.last_seq(), feed.resyncs()), (Some(21), 2));
+    }
+}
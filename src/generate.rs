@@ -0,0 +1,209 @@
+use crate::{OrderBook, OrderBookBuilder, OrderId, OrderQty, Price, Side, SYNTHETIC_OWNER};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Number of orders of each level relative to the best level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthProfile {
+    /// Every level holds as many orders as the best level
+    Flat,
+
+    /// The number of orders changes linearly away from the best level, by a share of
+    /// the best level per level, negative to thin out
+    Linear(f64),
+
+    /// The number of orders decays exponentially away from the best level, by a rate
+    /// per level
+    Exponential(f64),
+}
+
+impl DepthProfile {
+    /// Get the multiplier of the number of orders of a level
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The rank of the level, zero for the best level
+    pub fn weight(&self, level: usize) -> f64 {
+        match *self {
+            DepthProfile::Flat => 1.0,
+            DepthProfile::Linear(growth) => (1.0 + growth * level as f64).max(0.0),
+            DepthProfile::Exponential(rate) => (-rate * level as f64).exp(),
+        }
+    }
+}
+
+/// Distribution of the quantity of generated orders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    /// Every order has the same quantity
+    Constant(OrderQty),
+
+    /// Quantities are drawn uniformly from a range
+    Uniform {
+        /// Smallest quantity
+        min: OrderQty,
+
+        /// Largest quantity
+        max: OrderQty,
+    },
+
+    /// Quantities are a floor plus an exponentially distributed size, many small
+    /// orders and a few large ones as seen on most venues
+    Exponential {
+        /// Smallest quantity
+        min: OrderQty,
+
+        /// Mean of the size on top of the floor
+        mean: f64,
+    },
+}
+
+impl SizeDistribution {
+    /// Draw a quantity, at least one
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The source of randomness
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> OrderQty {
+        let qty = match *self {
+            SizeDistribution::Constant(qty) => qty,
+            SizeDistribution::Uniform { min, max } => rng.gen_range(min..=max.max(min)),
+            SizeDistribution::Exponential { min, mean } => {
+                let size = -(1.0 - rng.gen::<f64>()).ln() * mean;
+                min.saturating_add(size as OrderQty)
+            }
+        };
+        qty.max(1)
+    }
+}
+
+/// Generator of synthetic order book states, for benchmarks and tests to run against
+/// realistically deep books
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookGenerator {
+    /// Best bid price
+    pub best_bid: Price,
+
+    /// Distance of the best ask from the best bid, in ticks
+    pub spread: Price,
+
+    /// Distance between consecutive levels of a side, in ticks
+    pub level_spacing: Price,
+
+    /// Tick size of the prices
+    pub tick: Price,
+
+    /// Number of levels of each side
+    pub levels: usize,
+
+    /// Number of orders of the best level of each side
+    pub orders_per_level: usize,
+
+    /// Number of orders of the other levels relative to the best level
+    pub profile: DepthProfile,
+
+    /// Distribution of the quantity of the orders
+    pub sizes: SizeDistribution,
+}
+
+impl Default for BookGenerator {
+    fn default() -> BookGenerator {
+        BookGenerator {
+            best_bid: 1_000_000,
+            spread: 1,
+            level_spacing: 1,
+            tick: 1,
+            levels: 100,
+            orders_per_level: 10,
+            profile: DepthProfile::Linear(0.05),
+            sizes: SizeDistribution::Exponential { min: 1, mean: 50.0 },
+        }
+    }
+}
+
+impl BookGenerator {
+    /// Rest the generated orders in an order book, without matching them
+    ///
+    /// Every level holds at least one order. Orders belong to `SYNTHETIC_OWNER`, and the
+    /// ones the order book rejects are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to fill, expected to be empty
+    /// * `rng` - The source of randomness
+    ///
+    /// # Returns
+    ///
+    /// The identifiers of the generated orders, bids first, each side from the best
+    /// level outwards
+    pub fn populate<R: Rng>(&self, book: &mut OrderBook, rng: &mut R) -> Vec<OrderId> {
+        let tick = self.tick.max(1);
+        let step = self.level_spacing.max(1) * tick;
+        let best_ask = self.best_bid + self.spread.max(1) * tick;
+        let mut ids = Vec::new();
+        for side in [Side::Bid, Side::Ask] {
+            for level in 0..self.levels {
+                let offset = level as Price * step;
+                let price = match side {
+                    Side::Bid if offset > self.best_bid => break,
+                    Side::Bid => self.best_bid - offset,
+                    Side::Ask => best_ask + offset,
+                };
+                let weight = self.profile.weight(level);
+                let orders = ((self.orders_per_level as f64 * weight).round() as usize).max(1);
+                for _ in 0..orders {
+                    let qty = self.sizes.sample(rng);
+                    ids.extend(book.add(SYNTHETIC_OWNER, side, price, qty).ok());
+                }
+            }
+        }
+        ids
+    }
+
+    /// Build an order book and rest the generated orders in it
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The configuration of the order book
+    /// * `seed` - The seed of the random draws, making the state reproducible
+    ///
+    /// # Returns
+    ///
+    /// The populated order book
+    pub fn generate(&self, builder: OrderBookBuilder, seed: u64) -> OrderBook {
+        let mut book = builder.build();
+        self.populate(&mut book, &mut StdRng::seed_from_u64(seed));
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_book() {
+        let generator = BookGenerator {
+            best_bid: 1_000,
+            spread: 2,
+            level_spacing: 2,
+            tick: 5,
+            levels: 4,
+            orders_per_level: 10,
+            profile: DepthProfile::Linear(-0.5),
+            sizes: SizeDistribution::Uniform { min: 1, max: 9 },
+        };
+        let book = generator.generate(OrderBook::builder(), 7);
+        let bids = book.depth_stats(Side::Bid, usize::MAX);
+        let asks = book.depth_stats(Side::Ask, usize::MAX);
+        let orders: Vec<_> = bids.iter().map(|level| level.orders).collect();
+        assert_eq!(orders, vec![10, 5, 1, 1]);
+        assert_eq!(bids[1].price, 990);
+        assert_eq!(asks[0].price, 1_010);
+        assert!(asks.iter().all(|level| level.max_size <= 9));
+        assert_eq!(
+            book.snapshot(),
+            generator.generate(OrderBook::builder(), 7).snapshot()
+        );
+    }
+}
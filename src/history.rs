@@ -0,0 +1,336 @@
+use crate::audit::AuditRecord;
+use crate::{OrderBook, OrderId, OrderQty, OwnerId, Price, Side, TradeId};
+use std::collections::{HashMap, VecDeque};
+
+/// Step of the lifecycle of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderEvent {
+    /// Order was accepted by the order book
+    Accepted {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+    },
+
+    /// What was left of the order started resting
+    Rested {
+        /// Quantity left resting
+        qty: OrderQty,
+    },
+
+    /// Order traded
+    Filled {
+        /// Identifier of the trade
+        trade: TradeId,
+
+        /// Price of the trade
+        price: Price,
+
+        /// Quantity traded
+        qty: OrderQty,
+
+        /// Quantity of the order left, zero once fully filled
+        remaining: OrderQty,
+    },
+
+    /// Price or quantity of the resting order was amended
+    Amended {
+        /// Price before the amend
+        old_price: Price,
+
+        /// Quantity before the amend
+        old_qty: OrderQty,
+
+        /// Price after the amend
+        price: Price,
+
+        /// Quantity after the amend
+        qty: OrderQty,
+    },
+
+    /// Quantity of the resting order was reduced
+    Reduced {
+        /// Quantity before the reduction
+        old_qty: OrderQty,
+
+        /// Quantity left resting
+        qty: OrderQty,
+    },
+
+    /// Resting order was transferred to another owner
+    Transferred {
+        /// Previous owner of the order
+        from: OwnerId,
+
+        /// New owner of the order
+        to: OwnerId,
+    },
+
+    /// Resting order was canceled
+    Canceled {
+        /// Quantity that was canceled
+        qty: OrderQty,
+    },
+}
+
+/// Step of the lifecycle of an order with its time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Time of the step, from the clock of the order book
+    pub time: u64,
+
+    /// The step
+    pub event: OrderEvent,
+}
+
+/// Lifecycle of a single order
+#[derive(Debug, Clone)]
+struct Lifecycle {
+    /// Current owner of the order
+    owner: OwnerId,
+
+    /// Current price of the order
+    price: Price,
+
+    /// Quantity of the order left
+    qty: OrderQty,
+
+    /// Steps of the lifecycle, the acceptance first
+    events: VecDeque<HistoryEntry>,
+}
+
+/// Bounded history of the lifecycle of the most recent orders
+#[derive(Debug, Clone)]
+pub(crate) struct OrderHistory {
+    /// Maximum number of orders retained
+    orders: usize,
+
+    /// Maximum number of steps retained per order
+    events: usize,
+
+    /// Lifecycle of each retained order
+    lifecycles: HashMap<OrderId, Lifecycle>,
+
+    /// Retained orders, in order of acceptance
+    arrivals: VecDeque<OrderId>,
+}
+
+impl OrderHistory {
+    /// Create a new, empty, history
+    pub(crate) fn new(orders: usize, events: usize) -> OrderHistory {
+        OrderHistory {
+            orders: orders.max(1),
+            events: events.max(2),
+            lifecycles: HashMap::new(),
+            arrivals: VecDeque::new(),
+        }
+    }
+
+    /// Start the lifecycle of an order, forgetting the oldest order if full
+    fn accept(&mut self, time: u64, id: OrderId, event: OrderEvent) {
+        let OrderEvent::Accepted {
+            owner, price, qty, ..
+        } = event
+        else {
+            return;
+        };
+        if self.arrivals.len() == self.orders {
+            if let Some(oldest) = self.arrivals.pop_front() {
+                self.lifecycles.remove(&oldest);
+            }
+        }
+        let lifecycle = Lifecycle {
+            owner,
+            price,
+            qty,
+            events: VecDeque::from([HistoryEntry { time, event }]),
+        };
+        self.lifecycles.insert(id, lifecycle);
+        self.arrivals.push_back(id);
+    }
+
+    /// Append a step to the lifecycle of an order, dropping the oldest step after the
+    /// acceptance if full
+    fn push(&mut self, time: u64, id: OrderId, update: impl FnOnce(&mut Lifecycle) -> OrderEvent) {
+        let Some(lifecycle) = self.lifecycles.get_mut(&id) else {
+            return;
+        };
+        let event = update(lifecycle);
+        if lifecycle.events.len() == self.events {
+            lifecycle.events.remove(1);
+        }
+        lifecycle.events.push_back(HistoryEntry { time, event });
+    }
+
+    /// Account for a record of the audit trail
+    pub(crate) fn observe(&mut self, time: u64, record: &AuditRecord) {
+        match *record {
+            AuditRecord::Add {
+                id,
+                owner,
+                side,
+                price,
+                qty,
+                ..
+            } => {
+                let accepted = OrderEvent::Accepted {
+                    owner,
+                    side,
+                    price,
+                    qty,
+                };
+                self.accept(time, id, accepted);
+                self.push(time, id, |_| OrderEvent::Rested { qty });
+            }
+            AuditRecord::Execute {
+                id,
+                owner,
+                side,
+                price,
+                qty,
+                ..
+            } => {
+                let accepted = OrderEvent::Accepted {
+                    owner,
+                    side,
+                    price,
+                    qty,
+                };
+                self.accept(time, id, accepted);
+            }
+            AuditRecord::Rested { id, qty } => {
+                self.push(time, id, |lifecycle| {
+                    lifecycle.qty = qty;
+                    OrderEvent::Rested { qty }
+                });
+            }
+            AuditRecord::Fill(execution) => {
+                for id in [execution.maker, execution.taker] {
+                    self.push(time, id, |lifecycle| {
+                        lifecycle.qty = lifecycle.qty.saturating_sub(execution.qty);
+                        OrderEvent::Filled {
+                            trade: execution.trade,
+                            price: execution.price,
+                            qty: execution.qty,
+                            remaining: lifecycle.qty,
+                        }
+                    });
+                }
+            }
+            AuditRecord::Canceled { id, qty } => {
+                self.push(time, id, |lifecycle| {
+                    lifecycle.qty = 0;
+                    OrderEvent::Canceled { qty }
+                });
+            }
+            AuditRecord::Reduced { id, qty } => {
+                self.push(time, id, |lifecycle| {
+                    let old_qty = std::mem::replace(&mut lifecycle.qty, qty);
+                    OrderEvent::Reduced { old_qty, qty }
+                });
+            }
+            AuditRecord::Amended { id, price, qty } => {
+                self.push(time, id, |lifecycle| {
+                    let old_price = std::mem::replace(&mut lifecycle.price, price);
+                    let old_qty = std::mem::replace(&mut lifecycle.qty, qty);
+                    OrderEvent::Amended {
+                        old_price,
+                        old_qty,
+                        price,
+                        qty,
+                    }
+                });
+            }
+            AuditRecord::Transferred { id, owner } => {
+                self.push(time, id, |lifecycle| {
+                    let from = std::mem::replace(&mut lifecycle.owner, owner);
+                    OrderEvent::Transferred { from, to: owner }
+                });
+            }
+            AuditRecord::Quote { .. } | AuditRecord::Bust { .. } => {}
+        }
+    }
+}
+
+impl OrderBook {
+    /// Start keeping the lifecycle of the most recent orders
+    ///
+    /// Acceptance, resting, fills, amends, reductions, transfers and cancels are kept
+    /// per order, without replaying the journal. The history is bounded: the oldest
+    /// orders are forgotten beyond the order limit and, beyond the step limit of an
+    /// order, its oldest steps after the acceptance are dropped. The orders of quotes
+    /// are not kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `orders` - The maximum number of orders retained
+    /// * `events` - The maximum number of steps retained per order, at least two
+    pub fn enable_order_history(&mut self, orders: usize, events: usize) {
+        self.history = Some(OrderHistory::new(orders, events));
+    }
+
+    /// Get the lifecycle of an order
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    ///
+    /// # Returns
+    ///
+    /// An iterator over the steps of the order, oldest first, empty if the order is
+    /// not retained or the history is disabled
+    pub fn order_history(&self, id: OrderId) -> impl Iterator<Item = &HistoryEntry> {
+        (self.history.as_ref())
+            .and_then(|history| history.lifecycles.get(&id))
+            .into_iter()
+            .flat_map(|lifecycle| lifecycle.events.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_history() {
+        let mut book = OrderBook::builder().order_history(2, 4).build();
+        let maker = book.add(1, Side::Ask, 101, 10).unwrap();
+        book.amend(maker, 101, 8);
+        let result = book.execute(2, Side::Bid, 101, 3);
+        let events: Vec<_> = book.order_history(maker).map(|e| e.event).collect();
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[2],
+            OrderEvent::Amended {
+                old_price: 101,
+                old_qty: 10,
+                price: 101,
+                qty: 8,
+            }
+        );
+        assert!(matches!(
+            events[3],
+            OrderEvent::Filled {
+                qty: 3,
+                remaining: 5,
+                ..
+            }
+        ));
+        book.cancel(maker);
+        let events: Vec<_> = book.order_history(maker).map(|e| e.event).collect();
+        assert!(matches!(events[0], OrderEvent::Accepted { owner: 1, .. }));
+        assert_eq!(events[3], OrderEvent::Canceled { qty: 5 });
+
+        assert_eq!(result.id, None);
+        book.add(3, Side::Bid, 90, 1).unwrap();
+        assert_eq!(book.order_history(maker).count(), 0);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{Execution, FillResult, OrderQty, OwnerId, Price, Side};
+use std::fmt;
+
+/// Decision taken by a matching hook about a fill that is about to happen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillAction {
+    /// Apply the fill
+    Fill,
+
+    /// Cancel the resting order without filling it and continue matching
+    CancelResting,
+
+    /// Cancel the rest of the incoming order without filling it, nothing is added to the
+    /// order book
+    CancelIncoming,
+}
+
+/// Callbacks into the matching of incoming orders
+///
+/// Every method has a no-op default, implement only the ones needed
+pub trait MatchingHooks: fmt::Debug {
+    /// Called before an incoming order is matched, or an added order is rested
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// `Ok` to match the order, or the reason to reject it
+    fn pre_match(
+        &mut self,
+        _owner: OwnerId,
+        _side: Side,
+        _price: Price,
+        _qty: OrderQty,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Called before every fill of an incoming order against a resting order
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The execution that is about to happen
+    ///
+    /// # Returns
+    ///
+    /// What to do with the fill
+    fn on_fill(&mut self, _execution: &Execution) -> FillAction {
+        FillAction::Fill
+    }
+
+    /// Called once an incoming order is done matching
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the execution
+    fn post_trade(&mut self, _result: &FillResult) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancelResult, OrderBook, OrderStatus, RejectReason};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Cancels resting orders matching against their own owner and counts fills
+    #[derive(Debug, Default)]
+    struct SelfTradeCancel {
+        fills: Rc<Cell<usize>>,
+    }
+
+    impl MatchingHooks for SelfTradeCancel {
+        fn pre_match(
+            &mut self,
+            _owner: OwnerId,
+            _side: Side,
+            _price: Price,
+            qty: OrderQty,
+        ) -> Result<(), String> {
+            match qty {
+                0 => Err("empty order".to_string()),
+                _ => Ok(()),
+            }
+        }
+
+        fn on_fill(&mut self, execution: &Execution) -> FillAction {
+            match execution.maker_owner == execution.taker_owner {
+                true => FillAction::CancelResting,
+                false => FillAction::Fill,
+            }
+        }
+
+        fn post_trade(&mut self, result: &FillResult) {
+            self.fills.set(self.fills.get() + result.orders.len());
+        }
+    }
+
+    #[test]
+    fn test_hooks() {
+        let mut book = OrderBook::new();
+        let hooks = SelfTradeCancel::default();
+        let fills = hooks.fills.clone();
+        book.set_hooks(hooks);
+        let own = book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(2, Side::Ask, 100, 10).unwrap();
+
+        let result = book.execute(1, Side::Bid, 100, 5);
+        assert_eq!(result.status, OrderStatus::Filled);
+        assert_eq!(result.orders, vec![(100, 5)]);
+        assert_eq!(book.cancel(own), CancelResult::NotFound);
+        assert_eq!(fills.get(), 1);
+
+        let result = book.execute(1, Side::Bid, 100, 0);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::Hook("empty order".to_string()))
+        );
+    }
+}
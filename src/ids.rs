@@ -0,0 +1,44 @@
+use rand::Rng;
+use std::fmt;
+
+/// Source of the identifiers of new orders
+pub trait IdGenerator: fmt::Debug {
+    /// Get the identifier of the next order, unique within the order book
+    fn next_id(&mut self) -> u64;
+}
+
+/// Generator drawing random identifiers, the default of an order book
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn next_id(&mut self) -> u64 {
+        rand::thread_rng().gen()
+    }
+}
+
+/// Generator counting up from a starting identifier, for reproducible runs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SequentialIds {
+    /// Identifier of the next order
+    next: u64,
+}
+
+impl SequentialIds {
+    /// Create a new sequential generator
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The identifier of the first order
+    pub fn new(start: u64) -> SequentialIds {
+        SequentialIds { next: start }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{OrderQty, Price, RejectReason};
+
+/// Static configuration of the instrument traded in an order book
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instrument {
+    /// Symbol of the instrument
+    pub symbol: String,
+
+    /// Price increment, prices must be multiples of it
+    pub tick_size: Price,
+
+    /// Quantity increment, quantities must be multiples of it
+    pub lot_size: OrderQty,
+
+    /// Currency prices are quoted in, empty if unspecified
+    pub currency: String,
+}
+
+impl Default for Instrument {
+    fn default() -> Self {
+        Instrument {
+            symbol: String::new(),
+            tick_size: 1,
+            lot_size: 1,
+            currency: String::new(),
+        }
+    }
+}
+
+impl Instrument {
+    /// Check the price and quantity of an order against the increments
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The reason to reject the order, if any
+    pub fn check(&self, price: Price, qty: OrderQty) -> Result<(), RejectReason> {
+        if self.tick_size > 0 && !price.is_multiple_of(self.tick_size) {
+            return Err(RejectReason::InvalidTick);
+        }
+        if self.lot_size > 0 && !qty.is_multiple_of(self.lot_size) {
+            return Err(RejectReason::InvalidLot);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,593 @@
+use crate::audit::sha256;
+use crate::{
+    AmendResult, BustError, CancelResult, Execution, FillResult, HalfBook, OrderBook, OrderId,
+    OrderQty, OwnerId, PostOnly, Price, QuoteResult, RejectReason, Side, Tag, TradeId,
+};
+
+/// Command applied to an order book, as journaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Rest an order without matching it
+    Add {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+    },
+
+    /// Execute a limit order
+    Execute {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Limit price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+    },
+
+    /// Cancel a resting order
+    Cancel {
+        /// Identifier of the order
+        id: OrderId,
+    },
+
+    /// Cancel every resting order of an owner
+    CancelAll {
+        /// Owner whose orders to cancel
+        owner: OwnerId,
+    },
+
+    /// Amend the price and quantity of a resting order
+    Amend {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// New price of the order
+        price: Price,
+
+        /// New quantity of the order
+        qty: OrderQty,
+    },
+
+    /// Reduce the quantity of a resting order
+    Reduce {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// Quantity to take off the order
+        qty: OrderQty,
+    },
+
+    /// Transfer a resting order to another owner
+    Transfer {
+        /// Identifier of the order
+        id: OrderId,
+
+        /// New owner of the order
+        owner: OwnerId,
+    },
+
+    /// Replace the two-sided quote of an owner
+    Quote {
+        /// Owner of the quote
+        owner: OwnerId,
+
+        /// Price of the bid
+        bid_price: Price,
+
+        /// Quantity of the bid
+        bid_qty: OrderQty,
+
+        /// Price of the ask
+        ask_price: Price,
+
+        /// Quantity of the ask
+        ask_qty: OrderQty,
+    },
+
+    /// Add a post-only order
+    AddPostOnly {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Limit price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+
+        /// What to do if the order would cross
+        on_cross: PostOnly,
+    },
+
+    /// Execute a reduce-only limit order
+    ExecuteReduceOnly {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Limit price of the order
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+
+        /// User payload of the order
+        tag: Tag,
+    },
+
+    /// Add a stop order
+    AddStop {
+        /// Owner of the order
+        owner: OwnerId,
+
+        /// Side of the order
+        side: Side,
+
+        /// Trigger price of the order
+        trigger: Price,
+
+        /// Limit price of the order once triggered
+        price: Price,
+
+        /// Quantity of the order
+        qty: OrderQty,
+    },
+
+    /// Cancel a pending stop order
+    CancelStop {
+        /// Identifier of the stop order
+        id: OrderId,
+    },
+
+    /// Bust a trade on the tape
+    Bust {
+        /// Identifier of the trade
+        trade: TradeId,
+    },
+
+    /// Enter the pre-open phase
+    PreOpen,
+
+    /// Run the opening auction and enter the continuous phase
+    Open,
+}
+
+/// Result of a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandResult {
+    /// Identifier of the added order
+    Added(OrderId),
+
+    /// Reason the added order was rejected
+    Rejected(RejectReason),
+
+    /// Result of the execution
+    Executed(FillResult),
+
+    /// Result of the cancel
+    Canceled(CancelResult),
+
+    /// Number of orders canceled
+    CanceledAll(usize),
+
+    /// Result of the amend
+    Amended(AmendResult),
+
+    /// Quantity left resting after the reduce, `None` if the order was not resting
+    Reduced(Option<OrderQty>),
+
+    /// Previous owner of the transferred order, `None` if the order was not resting
+    Transferred(Option<OwnerId>),
+
+    /// Result of the quote
+    Quoted(QuoteResult),
+
+    /// Identifier of the added stop order
+    StopAdded(OrderId),
+
+    /// Whether the stop order was pending
+    StopCanceled(bool),
+
+    /// Execution of the busted trade, or why it could not be busted
+    Busted(Result<Execution, BustError>),
+
+    /// The order book entered the pre-open phase
+    PreOpened,
+
+    /// Executions of the opening auction
+    Opened(Vec<Execution>),
+}
+
+impl Command {
+    /// Apply the command to an order book
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to apply the command to
+    ///
+    /// # Returns
+    ///
+    /// The result of the command
+    pub fn apply(&self, book: &mut OrderBook) -> CommandResult {
+        match *self {
+            Command::Add {
+                owner,
+                side,
+                price,
+                qty,
+                tag,
+            } => match book.add_tagged(owner, side, price, qty, tag) {
+                Ok(id) => CommandResult::Added(id),
+                Err(reason) => CommandResult::Rejected(reason),
+            },
+            Command::Execute {
+                owner,
+                side,
+                price,
+                qty,
+                tag,
+            } => CommandResult::Executed(book.execute_tagged(owner, side, price, qty, tag)),
+            Command::Cancel { id } => CommandResult::Canceled(book.cancel(id)),
+            Command::CancelAll { owner } => CommandResult::CanceledAll(book.cancel_all(owner)),
+            Command::Amend { id, price, qty } => CommandResult::Amended(book.amend(id, price, qty)),
+            Command::Reduce { id, qty } => CommandResult::Reduced(book.reduce(id, qty)),
+            Command::Transfer { id, owner } => CommandResult::Transferred(book.transfer(id, owner)),
+            Command::Quote {
+                owner,
+                bid_price,
+                bid_qty,
+                ask_price,
+                ask_qty,
+            } => CommandResult::Quoted(book.quote(owner, bid_price, bid_qty, ask_price, ask_qty)),
+            Command::AddPostOnly {
+                owner,
+                side,
+                price,
+                qty,
+                tag,
+                on_cross,
+            } => {
+                CommandResult::Executed(book.add_post_only(owner, side, price, qty, tag, on_cross))
+            }
+            Command::ExecuteReduceOnly {
+                owner,
+                side,
+                price,
+                qty,
+                tag,
+            } => CommandResult::Executed(book.execute_reduce_only(owner, side, price, qty, tag)),
+            Command::AddStop {
+                owner,
+                side,
+                trigger,
+                price,
+                qty,
+            } => CommandResult::StopAdded(book.add_stop(owner, side, trigger, price, qty)),
+            Command::CancelStop { id } => CommandResult::StopCanceled(book.cancel_stop(id)),
+            Command::Bust { trade } => CommandResult::Busted(book.bust_trade(trade)),
+            Command::PreOpen => {
+                book.pre_open();
+                CommandResult::PreOpened
+            }
+            Command::Open => CommandResult::Opened(book.open()),
+        }
+    }
+}
+
+/// Entry of a journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalEntry {
+    /// Command applied to the order book
+    Command(Command),
+
+    /// Hash of the state of the order book after a number of commands
+    Checkpoint {
+        /// Number of commands applied before the checkpoint
+        commands: u64,
+
+        /// State hash of the order book
+        hash: [u8; 32],
+    },
+}
+
+/// Checkpoint of a journal that did not match the replayed order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// Number of commands applied at the previous checkpoint, which matched
+    pub from: u64,
+
+    /// Number of commands applied at the mismatched checkpoint
+    pub to: u64,
+}
+
+/// Journal of the commands applied to an order book, with periodic state-hash
+/// checkpoints
+///
+/// Replaying the journal into an order book with the same configuration, in particular
+/// the same identifier generator, must rebuild the same state. A checkpoint every few
+/// commands localizes corruption or nondeterminism to the commands since the previous
+/// checkpoint. Every change to the order book must go through a journaled command for
+/// the replay to hold: changes of its configuration made directly on the order book,
+/// or time passing on its clock, are not journaled.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    /// Entries of the journal, in order
+    entries: Vec<JournalEntry>,
+
+    /// Number of commands between checkpoints, zero for none
+    interval: u64,
+
+    /// Number of commands journaled
+    commands: u64,
+}
+
+impl Journal {
+    /// Create a new, empty, journal
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The number of commands between checkpoints, zero for none
+    pub fn new(interval: u64) -> Journal {
+        Journal {
+            interval,
+            ..Journal::default()
+        }
+    }
+
+    /// Apply a command to an order book and journal it
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to apply the command to
+    /// * `command` - The command to apply
+    ///
+    /// # Returns
+    ///
+    /// The result of the command
+    pub fn apply(&mut self, book: &mut OrderBook, command: Command) -> CommandResult {
+        let result = command.apply(book);
+        self.record(book, command);
+        result
+    }
+
+    /// Journal a command that was already applied to an order book
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book the command was applied to
+    /// * `command` - The applied command
+    pub fn record(&mut self, book: &OrderBook, command: Command) {
+        self.entries.push(JournalEntry::Command(command));
+        self.commands += 1;
+        if self.interval > 0 && self.commands.is_multiple_of(self.interval) {
+            self.checkpoint(book);
+        }
+    }
+
+    /// Append a checkpoint of the current state, unless the journal already ends with
+    /// one, e.g. to flush the journal before handing it off
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The journaled order book
+    pub fn checkpoint(&mut self, book: &OrderBook) {
+        if let Some(JournalEntry::Checkpoint { .. }) = self.entries.last() {
+            return;
+        }
+        self.entries.push(JournalEntry::Checkpoint {
+            commands: self.commands,
+            hash: book.state_hash(),
+        });
+    }
+
+    /// Get the entries of the journal
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+}
+
+/// Replay journal entries into an order book, verifying every checkpoint
+///
+/// # Arguments
+///
+/// * `book` - The order book to replay into, configured as the journaled one was
+/// * `entries` - The journal entries to replay
+///
+/// # Returns
+///
+/// Nothing if every checkpoint matched, otherwise the first window of commands after
+/// which the state diverged
+pub fn replay(book: &mut OrderBook, entries: &[JournalEntry]) -> Result<(), ReplayMismatch> {
+    let mut commands = 0;
+    let mut verified = 0;
+    for entry in entries {
+        match entry {
+            JournalEntry::Command(command) => {
+                command.apply(book);
+                commands += 1;
+            }
+            JournalEntry::Checkpoint { hash, .. } => {
+                if book.state_hash() != *hash {
+                    return Err(ReplayMismatch {
+                        from: verified,
+                        to: commands,
+                    });
+                }
+                verified = commands;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Append the canonical encoding of the resting orders of a side, best level first
+fn encode_side(book: &HalfBook, buf: &mut Vec<u8>) {
+    for pos in (0..book.prices.len()).rev() {
+        buf.extend_from_slice(&book.prices[pos].to_le_bytes());
+        buf.extend_from_slice(&book.qtys[pos].to_le_bytes());
+        for order in &book.price_levels[book.queues[pos]] {
+            for field in [order.id.0, order.owner, order.qty, order.tag] {
+                buf.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+    }
+}
+
+impl OrderBook {
+    /// Compute a hash of the state of the order book
+    ///
+    /// The hash is the SHA-256 of the resting orders of the bids, a u64::MAX separator,
+    /// the resting orders of the asks and the identifier of the next trade, all as
+    /// little-endian u64. Each side lists its levels from the best one, each level its
+    /// price and total quantity followed by the identifier, owner, quantity and tag of
+    /// its orders in queue order.
+    ///
+    /// # Returns
+    ///
+    /// The state hash
+    pub fn state_hash(&self) -> [u8; 32] {
+        let mut buf = Vec::new();
+        encode_side(&self.bids, &mut buf);
+        buf.extend_from_slice(&u64::MAX.to_le_bytes());
+        encode_side(&self.asks, &mut buf);
+        buf.extend_from_slice(&self.next_trade.to_le_bytes());
+        sha256(&buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SequentialIds;
+
+    #[test]
+    fn test_replay_checkpoints() {
+        let build = || {
+            OrderBook::builder()
+                .id_generator(SequentialIds::new(1))
+                .tape(16)
+                .build()
+        };
+        let mut book = build();
+        let mut journal = Journal::new(2);
+        for (i, price) in [100, 101, 102, 99].into_iter().enumerate() {
+            let command = Command::Add {
+                owner: 1,
+                side: Side::Ask,
+                price,
+                qty: 10,
+                tag: i as Tag,
+            };
+            journal.apply(&mut book, command);
+        }
+        let command = Command::Execute {
+            owner: 2,
+            side: Side::Bid,
+            price: 101,
+            qty: 15,
+            tag: 0,
+        };
+        journal.apply(&mut book, command);
+        journal.apply(&mut book, Command::CancelAll { owner: 1 });
+        assert_eq!(journal.entries().len(), 9);
+        assert_eq!(replay(&mut build(), journal.entries()), Ok(()));
+
+        let mut entries = journal.entries().to_vec();
+        entries[4] = JournalEntry::Command(Command::Cancel { id: OrderId(1) });
+        assert_eq!(
+            replay(&mut build(), &entries),
+            Err(ReplayMismatch { from: 2, to: 4 })
+        );
+
+        let mut book = build();
+        let mut journal = Journal::new(0);
+        let commands = [
+            Command::PreOpen,
+            Command::Quote {
+                owner: 1,
+                bid_price: 99,
+                bid_qty: 10,
+                ask_price: 101,
+                ask_qty: 10,
+            },
+            Command::Open,
+            Command::AddStop {
+                owner: 3,
+                side: Side::Bid,
+                trigger: 101,
+                price: 101,
+                qty: 5,
+            },
+            Command::Amend {
+                id: OrderId(1),
+                price: 98,
+                qty: 10,
+            },
+            Command::Reduce {
+                id: OrderId(2),
+                qty: 5,
+            },
+            Command::Transfer {
+                id: OrderId(2),
+                owner: 4,
+            },
+            Command::Execute {
+                owner: 2,
+                side: Side::Bid,
+                price: 101,
+                qty: 5,
+                tag: 0,
+            },
+            Command::AddPostOnly {
+                owner: 5,
+                side: Side::Ask,
+                price: 101,
+                qty: 5,
+                tag: 0,
+                on_cross: PostOnly::Reprice,
+            },
+            Command::Bust { trade: 1 },
+            Command::CancelStop { id: OrderId(3) },
+        ];
+        let results: Vec<_> = (commands.iter())
+            .map(|&command| journal.apply(&mut book, command))
+            .collect();
+        assert_eq!(results[2], CommandResult::Opened(Vec::new()));
+        assert_eq!(
+            results[4],
+            CommandResult::Amended(AmendResult::Amended(false))
+        );
+        assert!(matches!(results[9], CommandResult::Busted(Ok(_))));
+        assert_eq!(results[10], CommandResult::StopCanceled(false));
+        journal.checkpoint(&book);
+        assert_eq!(replay(&mut build(), journal.entries()), Ok(()));
+        assert_ne!(book.state_hash(), build().state_hash());
+    }
+}
@@ -1,14 +1,128 @@
-use rand::Rng;
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use history::OrderHistory;
+use mmp::MmpState;
+use stats::SizeRange;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+pub mod algos;
+pub mod amend;
+pub mod auction;
+pub mod audit;
+pub mod bbo;
+pub mod builder;
+pub mod bus;
+pub mod bust;
+pub mod candles;
+pub mod clock;
+pub mod closing;
+pub mod credit;
+pub mod currency;
+pub mod delta;
+pub mod drop_copy;
+pub mod engine;
+pub mod exchange;
+pub mod feed;
+pub mod generate;
+pub mod history;
+pub mod hooks;
+pub mod ids;
+pub mod instrument;
+pub mod journal;
+pub mod liquidity;
+pub mod mbo;
+pub mod memory;
+pub mod min_rest;
+pub mod mmp;
+#[cfg(all(unix, feature = "multicast"))]
+pub mod multicast;
+pub mod nbbo;
+pub mod position;
+pub mod post_only;
+pub mod pro_rata;
+pub mod quote;
+pub mod rcu;
+pub mod reduce_only;
+pub mod shape;
+#[cfg(unix)]
+pub mod shm;
+pub mod sim;
+pub mod snapshot;
+pub mod spread;
+pub mod stats;
+pub mod stops;
+pub mod stp;
+pub mod stream;
+pub mod surveillance;
+pub mod tick;
+pub mod transfer;
+pub mod view;
+pub mod watchdog;
+
+pub use algos::{Algo, AlgoReport, AlgoStrategy};
+pub use amend::{AmendReject, AmendResult, AmendRules, PriceAmend, QtyIncrease};
+pub use auction::{AuctionListener, Indicative, TradingPhase};
+pub use audit::{AuditEntry, AuditLog, AuditRecord};
+pub use bbo::{Bbo, BboListener};
+pub use builder::OrderBookBuilder;
+pub use bus::EventBus;
+pub use bust::{BustError, Trade};
+pub use candles::{Candle, CandleBuilder};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use closing::ClosingMethod;
+pub use credit::{CreditCheck, CreditRequest};
+pub use currency::{consolidated_depth, RateSource};
+pub use delta::{Delta, DeltaEvent, DeltaPublisher, DeltaSubscriber, Recovery};
+pub use drop_copy::ExecutionSink;
+pub use engine::{BookHandoff, Engine, EngineEvent, EngineHandle, Handoff};
+pub use exchange::{Archive, Exchange, ExchangeError, InstrumentState};
+pub use feed::{FeedAdapter, FeedDiff, FeedEvent, FeedSync};
+pub use generate::{BookGenerator, DepthProfile, SizeDistribution};
+pub use history::{HistoryEntry, OrderEvent};
+pub use hooks::{FillAction, MatchingHooks};
+pub use ids::{IdGenerator, RandomIds, SequentialIds};
+pub use instrument::Instrument;
+pub use journal::{replay, Command, CommandResult, Journal, JournalEntry, ReplayMismatch};
+pub use liquidity::{
+    AlertId, LiquidityAlert, LiquidityCondition, LiquidityListener, LiquidityPredicate,
+};
+pub use mbo::{MboAction, MboConverter, MboRecord};
+pub use memory::MemoryStats;
+pub use min_rest::{EarlyCancel, MinRestingTime};
+pub use mmp::{MmpConfig, MmpListener, MmpTrigger};
+pub use nbbo::{Nbbo, NbboListener, NbboQuote, NbboSide};
+pub use position::{Position, PositionTracker};
+pub use post_only::PostOnly;
+pub use pro_rata::{MatchingMode, ProRataRemainder, ProRataRules};
+pub use quote::{CrossAction, QuoteCross, QuoteProtection, QuoteResult, QuoteStatus};
+pub use rcu::{depth_views, DepthPublisher, DepthReader, DepthView};
+pub use shape::{BookShape, SideShape};
+pub use sim::{LatencyModel, MarketUpdate, SimEvent, Simulator};
+pub use snapshot::{DepthListener, LevelUpdate, Snapshot, SYNTHETIC_OWNER};
+pub use spread::{execute_spread, Leg, SpreadOrder, SpreadReject, SpreadResult, SpreadStatus};
+pub use stats::LevelStats;
+pub use stops::StopListener;
+pub use stp::StpPolicy;
+pub use surveillance::{Alert, Surveillance, SurveillanceConfig};
+pub use tick::{LastTrade, TickDirection};
+pub use view::{AskBookView, BidBookView, LevelView};
+pub use watchdog::{CountingAllocator, SlowCommand, Watchdog};
 
 pub type Price = u64;
 
 pub type OrderQty = u64;
 
+pub type OwnerId = u64;
+
+/// Opaque user payload attached to an order
+pub type Tag = u64;
+
+/// Identifier of a trade, unique within an order book
+pub type TradeId = u64;
+
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct OrderId(u64);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Side {
     /// Buy side
     Bid,
@@ -17,29 +131,120 @@ pub enum Side {
     Ask,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct Order {
     /// Unique identifier for the order
     id: OrderId,
 
+    /// Owner of the order
+    owner: OwnerId,
+
     /// Quantity of the order
     qty: OrderQty,
+
+    /// User payload of the order
+    tag: Tag,
+
+    /// Arrival sequence number, assigned when the order is inserted
+    seq: u64,
+
+    /// Time the order started resting, `UNTIMED` unless a minimum resting time applied
+    entered: u64,
 }
 
+/// Half of an order book, holding the orders of one side
+///
+/// The non-empty levels are kept as parallel arrays of prices, aggregate quantities and
+/// queue indices sorted from the worst price to the best, so that matching and depth
+/// queries scan contiguous memory from the end and the levels near the top of the book
+/// are cheap to insert and remove. The order queues are stored separately and reused
+/// when a price level empties and refills.
 #[derive(Debug)]
 struct HalfBook {
+    /// Side of the orders of this half of the order book
+    side: Side,
+
+    /// Prices of the non-empty levels, from the worst price to the best
+    prices: Vec<Price>,
+
+    /// Total quantity resting at each level in prices
+    qtys: Vec<OrderQty>,
+
+    /// Index in price_levels of the queue of each level in prices
+    queues: Vec<usize>,
+
     /// Map of price to index in price_levels
-    price_map: BTreeMap<Price, usize>,
+    price_map: HashMap<Price, usize>,
 
     /// Vector of price levels, each level is a queue of orders
     price_levels: Vec<VecDeque<Order>>,
+
+    /// Price of each level in price_levels
+    level_prices: Vec<Price>,
+
+    /// Range of the order quantities of each level in price_levels
+    sizes: Vec<Cell<SizeRange>>,
+
+    /// Price and previous total quantity of the levels changed since the depth
+    /// subscriber was last notified, `None` unless there is one
+    changed: Option<Vec<(Price, OrderQty)>>,
 }
 
 impl HalfBook {
-    fn new() -> HalfBook {
+    fn new(side: Side) -> HalfBook {
         HalfBook {
-            price_map: BTreeMap::new(),
-            price_levels: Vec::with_capacity(50_000),
+            side,
+            prices: Vec::new(),
+            qtys: Vec::new(),
+            queues: Vec::new(),
+            price_map: HashMap::new(),
+            price_levels: Vec::new(),
+            level_prices: Vec::new(),
+            sizes: Vec::new(),
+            changed: None,
+        }
+    }
+
+    /// Reserve room for a number of price levels beyond those already held
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The number of additional price levels
+    fn reserve(&mut self, levels: usize) {
+        self.prices.reserve(levels);
+        self.qtys.reserve(levels);
+        self.queues.reserve(levels);
+        self.price_map.reserve(levels);
+        self.price_levels.reserve(levels);
+        self.level_prices.reserve(levels);
+        self.sizes.reserve(levels);
+    }
+
+    /// Remember the total quantity of a level about to change, for the depth subscriber
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the level
+    /// * `qty` - The total quantity of the level before the change
+    fn touch(&mut self, price: Price, qty: OrderQty) {
+        if let Some(changed) = self.changed.as_mut() {
+            changed.push((price, qty));
+        }
+    }
+
+    /// Find a price among the non-empty levels
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price to find
+    ///
+    /// # Returns
+    ///
+    /// The position of the level in prices, or the position to insert it at
+    fn position(&self, price: Price) -> Result<usize, usize> {
+        match self.side {
+            Side::Bid => self.prices.binary_search(&price),
+            Side::Ask => self.prices.binary_search_by(|level| price.cmp(level)),
         }
     }
 
@@ -53,20 +258,298 @@ impl HalfBook {
     ///
     /// The total quantity at the given price level
     fn get_total_qty(&self, price: Price) -> OrderQty {
-        self.price_levels[self.price_map[&price]]
+        self.position(price).map_or(0, |pos| self.qtys[pos])
+    }
+
+    /// Iterate over the non-empty price levels
+    ///
+    /// # Returns
+    ///
+    /// The price and total quantity of each level, best first
+    fn levels(&self) -> impl Iterator<Item = (Price, OrderQty)> + '_ {
+        self.prices
             .iter()
-            .map(|o| o.qty)
-            .sum()
+            .copied()
+            .zip(self.qtys.iter().copied())
+            .rev()
+    }
+
+    /// Append an order to the queue of its price level
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the order
+    /// * `order` - The order to append
+    ///
+    /// # Returns
+    ///
+    /// The index of the queue of the level in price_levels
+    fn push(&mut self, price: Price, order: Order) -> usize {
+        let idx = self.give(price, order.qty);
+        self.add_size(idx, order.qty);
+        self.price_levels[idx].push_back(order);
+        idx
+    }
+
+    /// Put an order back into the queue of its price level at its arrival priority
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the order
+    /// * `order` - The order to put back
+    ///
+    /// # Returns
+    ///
+    /// The index of the queue of the level in price_levels
+    fn restore(&mut self, price: Price, order: Order) -> usize {
+        let idx = self.give(price, order.qty);
+        self.add_size(idx, order.qty);
+        let queue = &mut self.price_levels[idx];
+        let pos = queue.partition_point(|o| o.seq < order.seq);
+        queue.insert(pos, order);
+        idx
+    }
+
+    /// Account for quantity added to a price level, adding the level if it was empty
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the level
+    /// * `qty` - The quantity added
+    ///
+    /// # Returns
+    ///
+    /// The index of the queue of the level in price_levels
+    fn give(&mut self, price: Price, qty: OrderQty) -> usize {
+        let idx = *self.price_map.entry(price).or_insert_with(|| {
+            self.price_levels.push(VecDeque::new());
+            self.level_prices.push(price);
+            self.sizes.push(Cell::default());
+            self.price_levels.len() - 1
+        });
+        match self.position(price) {
+            Ok(pos) => {
+                self.touch(price, self.qtys[pos]);
+                self.qtys[pos] += qty;
+            }
+            Err(pos) => {
+                self.touch(price, 0);
+                self.prices.insert(pos, price);
+                self.qtys.insert(pos, qty);
+                self.queues.insert(pos, idx);
+            }
+        }
+        idx
+    }
+
+    /// Account for an order about to join the queue of a price level in its size range
+    fn add_size(&mut self, idx: usize, qty: OrderQty) {
+        match self.price_levels[idx].is_empty() {
+            true => stats::reset(&self.sizes[idx], qty),
+            false => stats::resize(&self.sizes[idx], 0, qty),
+        }
+    }
+
+    /// Account for quantity taken out of the queue of a price level, dropping the level
+    /// once its queue is empty
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The index of the queue in price_levels
+    /// * `qty` - The quantity taken out
+    fn take(&mut self, idx: usize, qty: OrderQty) {
+        if let Ok(pos) = self.position(self.level_prices[idx]) {
+            self.touch(self.level_prices[idx], self.qtys[pos]);
+            self.qtys[pos] -= qty;
+            if self.price_levels[idx].is_empty() {
+                self.prices.remove(pos);
+                self.qtys.remove(pos);
+                self.queues.remove(pos);
+            }
+        }
+    }
+
+    /// Drop the best levels whose queues were emptied by matching
+    fn pop_empty(&mut self) {
+        while let Some(idx) = self.queues.last() {
+            if !self.price_levels[*idx].is_empty() {
+                break;
+            }
+            self.prices.pop();
+            self.qtys.pop();
+            self.queues.pop();
+        }
+    }
+}
+
+/// Limit order entering the order book, before matching
+#[derive(Debug, Clone, Copy)]
+struct Incoming {
+    /// Identifier of the order, kept if it rests
+    id: OrderId,
+
+    /// Owner of the order
+    owner: OwnerId,
+
+    /// Side of the order
+    side: Side,
+
+    /// Limit price of the order
+    price: Price,
+
+    /// Quantity of the order
+    qty: OrderQty,
+
+    /// User payload of the order
+    tag: Tag,
+}
+
+/// Check whether an incoming order crosses a resting price level
+///
+/// # Arguments
+///
+/// * `side` - The side of the incoming order
+/// * `price` - The limit price of the incoming order
+/// * `level` - The price of a level of the opposite side
+fn crosses(side: Side, price: Price, level: Price) -> bool {
+    match side {
+        Side::Bid => level <= price,
+        Side::Ask => level >= price,
+    }
+}
+
+/// Remove an order of a price level while matching, without trading it
+///
+/// # Returns
+///
+/// The removed order
+fn pull_at(
+    level: &mut VecDeque<Order>,
+    at: usize,
+    level_qty: &mut OrderQty,
+    sizes: &Cell<SizeRange>,
+    order_loc: &mut HashMap<OrderId, (Side, usize)>,
+    owner_orders: &mut HashMap<OwnerId, HashSet<OrderId>>,
+) -> Order {
+    let order = level.remove(at).expect("pulled a missing order");
+    *level_qty -= order.qty;
+    stats::resize(sizes, order.qty, 0);
+    order_loc.remove(&order.id);
+    forget_owner(owner_orders, order.owner, order.id);
+    order
+}
+
+/// Remove a resting order from the orders of its owner
+fn forget_owner(
+    owner_orders: &mut HashMap<OwnerId, HashSet<OrderId>>,
+    owner: OwnerId,
+    id: OrderId,
+) {
+    if let Some(ids) = owner_orders.get_mut(&owner) {
+        ids.remove(&id);
+        if ids.is_empty() {
+            owner_orders.remove(&owner);
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CancelResult {
     /// Order was not found
     NotFound,
 
-    /// Order was successfully canceled
-    Canceled,
+    /// Order was successfully canceled, with the tag of the order
+    Canceled(Tag),
+
+    /// Order has not rested for the minimum resting time, with the time from which it
+    /// can be canceled
+    TooEarly(u64),
+
+    /// Cancel was deferred until the minimum resting time elapses, at the given time
+    Deferred(u64),
+}
+
+/// Read-only view of a resting order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderView {
+    /// Unique identifier for the order
+    pub id: OrderId,
+
+    /// Owner of the order
+    pub owner: OwnerId,
+
+    /// Side of the order
+    pub side: Side,
+
+    /// Price of the order
+    pub price: Price,
+
+    /// Remaining quantity of the order
+    pub qty: OrderQty,
+
+    /// User payload of the order
+    pub tag: Tag,
+}
+
+/// Report of a single fill between a resting and an incoming order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Execution {
+    /// Identifier of the trade
+    pub trade: TradeId,
+
+    /// Resting order that provided liquidity
+    pub maker: OrderId,
+
+    /// Owner of the resting order
+    pub maker_owner: OwnerId,
+
+    /// Tag of the resting order
+    pub maker_tag: Tag,
+
+    /// Incoming order that took liquidity
+    pub taker: OrderId,
+
+    /// Owner of the incoming order
+    pub taker_owner: OwnerId,
+
+    /// Tag of the incoming order
+    pub taker_tag: Tag,
+
+    /// Side of the incoming order
+    pub side: Side,
+
+    /// Price of the fill, the price of the resting order
+    pub price: Price,
+
+    /// Quantity of the fill
+    pub qty: OrderQty,
+
+    /// Direction of the price relative to the trade before it
+    pub tick: TickDirection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Order was refused by the credit check, with the reason given by the credit model
+    Credit(String),
+
+    /// Order was refused by the matching hooks, with the reason given by the hooks
+    Hook(String),
+
+    /// Owner of the order has a triggered market-maker protection
+    MmpTriggered,
+
+    /// Price of the order is not a multiple of the tick size of the instrument
+    InvalidTick,
+
+    /// Quantity of the order is not a multiple of the lot size of the instrument
+    InvalidLot,
+
+    /// Post-only order would have taken liquidity
+    WouldCross,
+
+    /// Reduce-only order whose owner has no position to reduce
+    ReduceOnly,
 }
 
 #[derive(Debug)]
@@ -85,17 +568,321 @@ pub struct OrderBook {
 
     /// Map of order id to side and price level
     order_loc: HashMap<OrderId, (Side, usize)>,
+
+    /// Map of owner to its resting orders
+    owner_orders: HashMap<OwnerId, HashSet<OrderId>>,
+
+    /// Instrument traded in the order book
+    instrument: Instrument,
+
+    /// Source of the rate converting the quote currency into the common currency
+    rates: Option<Box<dyn RateSource>>,
+
+    /// Source of the identifiers of new orders
+    ids: Box<dyn IdGenerator>,
+
+    /// Source of the current time
+    clock: Box<dyn Clock>,
+
+    /// Self-trade prevention policy
+    stp: StpPolicy,
+
+    /// Sink receiving a copy of every execution
+    drop_copy: Option<Box<dyn ExecutionSink>>,
+
+    /// Credit check applied to every executed order
+    credit: Option<Box<dyn CreditCheck>>,
+
+    /// Positions of every owner, tracked while a credit check is set
+    positions: Option<PositionTracker>,
+
+    /// Hooks called while matching executed orders
+    hooks: Option<Box<dyn MatchingHooks>>,
+
+    /// Subscriber to changes of the best bid and offer
+    bbo_listener: Option<Box<dyn BboListener>>,
+
+    /// Subscriber to changes of the depth
+    depth_listener: Option<Box<dyn DepthListener>>,
+
+    /// Registered liquidity alerts
+    liquidity_alerts: Vec<liquidity::Registered>,
+
+    /// Subscriber to liquidity alerts
+    liquidity_listener: Option<Box<dyn LiquidityListener>>,
+
+    /// Identifier of the last registered liquidity alert
+    next_alert: AlertId,
+
+    /// Best bid and offer last reported to the subscriber
+    last_bbo: Bbo,
+
+    /// Map of owner to the bid and ask orders of its current quote
+    quotes: HashMap<OwnerId, QuoteResult>,
+
+    /// Protection applied to quotes against crossing prices
+    quote_protection: Option<QuoteProtection>,
+
+    /// Market-maker protection counters of every protected owner
+    mmp: HashMap<OwnerId, MmpState>,
+
+    /// Subscriber to triggered market-maker protections
+    mmp_listener: Option<Box<dyn MmpListener>>,
+
+    /// Tamper-evident history of the order book, kept once enabled
+    audit: Option<AuditLog>,
+
+    /// Lifecycle of the most recent orders, kept while enabled
+    history: Option<OrderHistory>,
+
+    /// Sequence number of the next order inserted
+    next_seq: u64,
+
+    /// Identifier of the next trade
+    next_trade: TradeId,
+
+    /// Most recent trades, which can be busted
+    tape: VecDeque<Trade>,
+
+    /// Number of trades kept on the tape
+    tape_capacity: usize,
+
+    /// Last trade that was not busted
+    last_trade: Option<LastTrade>,
+
+    /// Total quantity traded, excluding busted trades
+    volume: OrderQty,
+
+    /// Method used to compute the closing price
+    closing_method: ClosingMethod,
+
+    /// Price the last auction uncrossed at
+    auction_price: Option<Price>,
+
+    /// Trading phase
+    phase: TradingPhase,
+
+    /// Indicative opening price and volume, maintained during pre-open
+    indicative: Option<Indicative>,
+
+    /// Subscriber to changes of the indicative opening price and volume
+    auction_listener: Option<Box<dyn AuctionListener>>,
+
+    /// Pending stop orders of each side, by trigger key
+    stops: [BTreeMap<(Price, u64), Incoming>; 2],
+
+    /// Map of pending stop order to its side and trigger key
+    stop_loc: HashMap<OrderId, (Side, (Price, u64))>,
+
+    /// Maximum number of stop orders triggered by a single command
+    stop_cascade_limit: usize,
+
+    /// Lowest and highest trade prices since stop orders were last triggered
+    traded: Option<(Price, Price)>,
+
+    /// Subscriber to triggered stop orders
+    stop_listener: Option<Box<dyn StopListener>>,
+
+    /// Minimum time orders must rest before they can be canceled
+    min_rest: Option<MinRestingTime>,
+
+    /// Deferred cancels, by the time they are allowed from
+    deferred_cancels: BTreeSet<(u64, OrderId)>,
+
+    /// Priority rules applied to amends
+    amend_rules: AmendRules,
+
+    /// Allocation of incoming orders among the orders of a price level
+    matching: MatchingMode,
+
+    /// Pro-rata allocations of the level being matched, kept to reuse its memory
+    allocations: Vec<OrderQty>,
+
+    /// Resting reduce-only orders
+    reduce_only: HashSet<OrderId>,
+}
+
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OrderBook {
+    /// Create a new order book with the default configuration
+    ///
+    /// Use `OrderBook::builder` to configure the order book instead
     pub fn new() -> OrderBook {
         OrderBook {
             best_bid: 0,
             best_ask: 0,
-            bids: HalfBook::new(),
-            asks: HalfBook::new(),
+            bids: HalfBook::new(Side::Bid),
+            asks: HalfBook::new(Side::Ask),
             order_loc: HashMap::new(),
+            owner_orders: HashMap::new(),
+            instrument: Instrument::default(),
+            rates: None,
+            ids: Box::new(RandomIds),
+            clock: Box::new(SystemClock),
+            stp: StpPolicy::default(),
+            drop_copy: None,
+            credit: None,
+            positions: None,
+            hooks: None,
+            bbo_listener: None,
+            depth_listener: None,
+            liquidity_alerts: Vec::new(),
+            liquidity_listener: None,
+            next_alert: 0,
+            last_bbo: Bbo::default(),
+            quotes: HashMap::new(),
+            quote_protection: None,
+            mmp: HashMap::new(),
+            mmp_listener: None,
+            audit: None,
+            history: None,
+            next_seq: 0,
+            next_trade: 1,
+            tape: VecDeque::new(),
+            tape_capacity: 0,
+            last_trade: None,
+            volume: 0,
+            closing_method: ClosingMethod::LastTrade,
+            auction_price: None,
+            phase: TradingPhase::Continuous,
+            indicative: None,
+            auction_listener: None,
+            stops: [BTreeMap::new(), BTreeMap::new()],
+            stop_loc: HashMap::new(),
+            stop_cascade_limit: 1024,
+            traded: None,
+            stop_listener: None,
+            min_rest: None,
+            deferred_cancels: BTreeSet::new(),
+            amend_rules: AmendRules::default(),
+            matching: MatchingMode::Fifo,
+            allocations: Vec::new(),
+            reduce_only: HashSet::new(),
+        }
+    }
+
+    /// Start configuring a new order book
+    pub fn builder() -> OrderBookBuilder {
+        OrderBookBuilder::new()
+    }
+
+    /// Get the instrument traded in the order book
+    pub fn instrument(&self) -> &Instrument {
+        &self.instrument
+    }
+
+    /// Get the identifier of a new order
+    fn next_id(&mut self) -> OrderId {
+        OrderId(self.ids.next_id())
+    }
+
+    /// Set the clock
+    ///
+    /// The order book uses the system clock by default
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The source of the current time
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Set the drop-copy sink
+    ///
+    /// Every execution, whichever owners are involved, is reported to the sink in the
+    /// order it happens, in addition to the fill result returned to the incoming order
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The sink to report executions to
+    pub fn set_drop_copy<S: ExecutionSink + 'static>(&mut self, sink: S) {
+        self.drop_copy = Some(Box::new(sink));
+    }
+
+    /// Set the credit check
+    ///
+    /// Every order submitted through `execute` or `add` is checked against the credit
+    /// model, given the current position of its owner, before it is matched or rested.
+    /// Setting a credit check enables position tracking.
+    ///
+    /// # Arguments
+    ///
+    /// * `credit` - The credit model to check orders against
+    pub fn set_credit_check<C: CreditCheck + 'static>(&mut self, credit: C) {
+        self.credit = Some(Box::new(credit));
+        self.positions.get_or_insert_with(PositionTracker::new);
+    }
+
+    /// Set the matching hooks
+    ///
+    /// The hooks are called before, during and after the matching of every order
+    /// submitted through `execute`. Orders submitted through `add` only go through
+    /// `pre_match`, before they rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The hooks to call
+    pub fn set_hooks<H: MatchingHooks + 'static>(&mut self, hooks: H) {
+        self.hooks = Some(Box::new(hooks));
+    }
+
+    /// Set the subscriber to changes of the best bid and offer
+    ///
+    /// The subscriber is only called when the price or total quantity of the best bid
+    /// or offer actually changes, not on changes deeper in the order book
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_bbo_listener<L: BboListener + 'static>(&mut self, listener: L) {
+        self.bbo_listener = Some(Box::new(listener));
+        self.last_bbo = self.bbo();
+    }
+
+    /// Get the best bid and offer
+    ///
+    /// # Returns
+    ///
+    /// The best price and total quantity of each side
+    pub fn bbo(&self) -> Bbo {
+        Bbo {
+            bid: self.bids.levels().next(),
+            ask: self.asks.levels().next(),
+            last: self.last_trade,
+        }
+    }
+
+    /// Notify the depth subscriber of the changed levels, and the best bid and offer
+    /// subscriber if the best bid or offer changed, a change of the last trade alone is
+    /// not notified
+    fn notify_bbo(&mut self) {
+        self.notify_depth();
+        self.update_indicative();
+        self.evaluate_alerts();
+        if self.bbo_listener.is_none() {
+            return;
+        }
+        let bbo = self.bbo();
+        if (bbo.bid, bbo.ask) != (self.last_bbo.bid, self.last_bbo.ask) {
+            if let Some(listener) = self.bbo_listener.as_mut() {
+                listener.on_bbo(&self.last_bbo, &bbo);
+            }
         }
+        self.last_bbo = bbo;
+    }
+
+    /// Get the positions of every owner
+    ///
+    /// # Returns
+    ///
+    /// The position tracker, `None` if position tracking is not enabled
+    pub fn positions(&self) -> Option<&PositionTracker> {
+        self.positions.as_ref()
     }
 
     /// Get the total quantity at a given price level
@@ -119,56 +906,805 @@ impl OrderBook {
     ///
     /// # Arguments
     ///
+    /// * `owner` - The owner of the order
     /// * `side` - The side of the order
     /// * `price` - The price of the order
     /// * `qty` - The quantity of the order
     ///
     /// # Returns
     ///
-    /// The unique identifier for the order
-    pub fn add(&mut self, side: Side, price: Price, qty: OrderQty) -> OrderId {
-        let id = OrderId(rand::thread_rng().gen());
-        let book = match side {
-            Side::Ask => &mut self.asks,
-            Side::Bid => &mut self.bids,
-        };
-        match book.price_map.get(&price) {
-            Some(idx) => {
-                self.order_loc.insert(id, (side, *idx));
-                book.price_levels[*idx].push_back(Order { id, qty });
-            }
-            None => {
-                self.order_loc.insert(id, (side, book.price_levels.len()));
-                book.price_map.insert(price, book.price_levels.len());
-                book.price_levels
-                    .push(VecDeque::from(vec![Order { id, qty }]));
-            }
-        };
-        id
+    /// The unique identifier for the order, or the reason the order was rejected
+    pub fn add(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+    ) -> Result<OrderId, RejectReason> {
+        self.add_tagged(owner, side, price, qty, 0)
     }
 
-    /// Cancel an order
+    /// Add an order carrying a user payload to the order book
+    ///
+    /// The tag is echoed back in executions, cancels and order views
     ///
     /// # Arguments
     ///
-    /// * `id` - The unique identifier of the order to cancel
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The price of the order
+    /// * `qty` - The quantity of the order
+    /// * `tag` - The user payload of the order
     ///
     /// # Returns
     ///
-    /// The result of the cancel operation
-    pub fn cancel(&mut self, id: OrderId) -> CancelResult {
-        match self.order_loc.remove(&id) {
-            None => CancelResult::NotFound,
-            Some((side, price)) => {
-                match side {
-                    Side::Bid => &mut self.bids,
-                    Side::Ask => &mut self.asks,
-                }
-                .price_levels[price]
-                    .retain(|o| o.id != id);
-                CancelResult::Canceled
-            }
+    /// The unique identifier for the order, or the reason the order was rejected
+    pub fn add_tagged(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        tag: Tag,
+    ) -> Result<OrderId, RejectReason> {
+        self.release_deferred_cancels();
+        self.check_order(owner, side, price, qty)?;
+        let id = self.next_id();
+        let order = Order {
+            id,
+            owner,
+            qty,
+            tag,
+            seq: 0,
+            entered: min_rest::UNTIMED,
+        };
+        self.record(AuditRecord::Add {
+            id,
+            owner,
+            side,
+            price,
+            qty,
+            tag,
+        });
+        self.insert(order, side, price);
+        self.notify_bbo();
+        Ok(id)
+    }
+
+    /// Run the checks an order must pass before it is accepted
+    ///
+    /// The order is checked against the instrument, the market-maker protection of
+    /// its owner, the credit check and the hooks, in that order.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The reason of the first check refusing the order, if any
+    fn check_order(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+    ) -> Result<(), RejectReason> {
+        self.instrument.check(price, qty)?;
+        if self.is_mmp_triggered(owner) {
+            return Err(RejectReason::MmpTriggered);
+        }
+        if let Some(credit) = self.credit.as_mut() {
+            let request = CreditRequest {
+                owner,
+                side,
+                price,
+                qty,
+                notional: price as u128 * qty as u128,
+            };
+            let position = self
+                .positions
+                .as_ref()
+                .map(|positions| positions.position(owner))
+                .unwrap_or_default();
+            credit
+                .check(&request, &position)
+                .map_err(RejectReason::Credit)?;
         }
+        if let Some(hooks) = self.hooks.as_mut() {
+            hooks
+                .pre_match(owner, side, price, qty)
+                .map_err(RejectReason::Hook)?;
+        }
+        Ok(())
+    }
+
+    /// Insert an order at the back of its price level
+    fn insert(&mut self, mut order: Order, side: Side, price: Price) {
+        order.seq = self.next_seq;
+        self.next_seq += 1;
+        if self.min_rest.is_some() {
+            order.entered = self.clock.now();
+        }
+        let book = match side {
+            Side::Ask => &mut self.asks,
+            Side::Bid => &mut self.bids,
+        };
+        self.owner_orders
+            .entry(order.owner)
+            .or_default()
+            .insert(order.id);
+        let id = order.id;
+        let idx = book.push(price, order);
+        self.order_loc.insert(id, (side, idx));
+    }
+
+    /// Execute a limit order against the order book
+    ///
+    /// The order is matched against the opposite side at prices equal to or better than
+    /// its limit price, in price-time priority. Any remaining quantity is added to the
+    /// order book at the limit price. Resting orders of the same owner are handled
+    /// according to the self-trade prevention policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The result of the execution
+    pub fn execute(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+    ) -> FillResult {
+        self.execute_tagged(owner, side, price, qty, 0)
+    }
+
+    /// Execute a limit order carrying a user payload against the order book
+    ///
+    /// Behaves like `execute`, the tag is echoed back in executions and, if the order
+    /// rests, in cancels and order views
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    /// * `tag` - The user payload of the order
+    ///
+    /// # Returns
+    ///
+    /// The result of the execution
+    pub fn execute_tagged(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        tag: Tag,
+    ) -> FillResult {
+        let mut result = FillResult::new();
+        self.execute_into(owner, side, price, qty, tag, &mut result);
+        result
+    }
+
+    /// Execute a limit order carrying a user payload, reusing a result as buffer
+    ///
+    /// Behaves like `execute_tagged`. Matching does not allocate once warmed up: when
+    /// the fills fit in the capacity of the buffer, the prices the remainder rests at
+    /// held orders before and the owner already has resting orders. Enabled audit
+    /// trails, credit checks, hooks and subscribers may allocate on their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    /// * `tag` - The user payload of the order
+    /// * `result` - The result to overwrite with the result of the execution
+    pub fn execute_into(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        tag: Tag,
+        result: &mut FillResult,
+    ) {
+        self.release_deferred_cancels();
+        let order = Incoming {
+            id: self.next_id(),
+            owner,
+            side,
+            price,
+            qty,
+            tag,
+        };
+        self.match_order(order, result);
+        self.run_stops();
+        self.enforce_reduce_only();
+    }
+
+    /// Match a limit order against the order book, resting its remainder
+    fn match_order(&mut self, order: Incoming, result: &mut FillResult) {
+        self.match_incoming(order, result);
+        self.notify_bbo();
+    }
+
+    /// Match a limit order against the order book, resting its remainder, without
+    /// notifying the subscriber to the best bid and offer
+    fn match_incoming(&mut self, order: Incoming, result: &mut FillResult) {
+        let Incoming {
+            id,
+            owner,
+            side,
+            price,
+            qty,
+            tag,
+        } = order;
+        result.id = None;
+        result.remaining = qty;
+        result.status = OrderStatus::Unititialized;
+        result.orders.clear();
+
+        if let Err(reason) = self.check_order(owner, side, price, qty) {
+            result.status = OrderStatus::Rejected(reason);
+            return;
+        }
+        self.record(AuditRecord::Execute {
+            id,
+            owner,
+            side,
+            price,
+            qty,
+            tag,
+        });
+
+        let book = match side {
+            Side::Bid => &mut self.asks,
+            Side::Ask => &mut self.bids,
+        };
+        let now = match self.mmp.is_empty() {
+            true => 0,
+            false => self.clock.now(),
+        };
+        let mut triggers: Vec<MmpTrigger> = Vec::new();
+        let mut canceled = false;
+        let levels = match self.phase {
+            TradingPhase::Continuous => book.prices.len(),
+            TradingPhase::PreOpen => 0,
+        };
+        'levels: for pos in (0..levels).rev() {
+            let level_price = book.prices[pos];
+            if !crosses(side, price, level_price) {
+                break;
+            }
+            book.touch(level_price, book.qtys[pos]);
+            let level = &mut book.price_levels[book.queues[pos]];
+            let level_qty = &mut book.qtys[pos];
+            let sizes = &book.sizes[book.queues[pos]];
+            'pass: loop {
+                let qty = result.remaining.min(*level_qty);
+                pro_rata::plan_pass(&self.matching, level, qty, &mut self.allocations);
+                let (mut at, mut slot) = (0, 0);
+                while let Some(maker) = level.get_mut(at) {
+                    if result.remaining == 0 {
+                        break;
+                    }
+                    let (allocations, remaining) = (&self.allocations, result.remaining);
+                    let fill = pro_rata::pass_fill(
+                        &self.matching,
+                        allocations,
+                        slot,
+                        maker.qty,
+                        remaining,
+                    );
+                    slot += 1;
+                    if fill == 0 {
+                        at += 1;
+                        continue;
+                    }
+                    let pulled = triggers.iter_mut().find(|t| t.owner == maker.owner);
+                    if let Some(trigger) = pulled {
+                        trigger.canceled += 1;
+                        let pulled = pull_at(
+                            level,
+                            at,
+                            level_qty,
+                            sizes,
+                            &mut self.order_loc,
+                            &mut self.owner_orders,
+                        );
+                        let record = AuditRecord::Canceled {
+                            id: pulled.id,
+                            qty: pulled.qty,
+                        };
+                        audit::append_to(&mut self.audit, &mut self.history, &*self.clock, record);
+                        continue;
+                    }
+                    if maker.owner == owner && self.stp != StpPolicy::Allow {
+                        if self.stp != StpPolicy::CancelIncoming {
+                            let pulled = pull_at(
+                                level,
+                                at,
+                                level_qty,
+                                sizes,
+                                &mut self.order_loc,
+                                &mut self.owner_orders,
+                            );
+                            let record = AuditRecord::Canceled {
+                                id: pulled.id,
+                                qty: pulled.qty,
+                            };
+                            audit::append_to(
+                                &mut self.audit,
+                                &mut self.history,
+                                &*self.clock,
+                                record,
+                            );
+                        }
+                        if self.stp == StpPolicy::CancelResting {
+                            continue;
+                        }
+                        canceled = true;
+                        break 'levels;
+                    }
+                    let execution = Execution {
+                        trade: self.next_trade,
+                        maker: maker.id,
+                        maker_owner: maker.owner,
+                        maker_tag: maker.tag,
+                        taker: id,
+                        taker_owner: owner,
+                        taker_tag: tag,
+                        side,
+                        price: level_price,
+                        qty: fill,
+                        tick: TickDirection::of(self.last_trade, level_price),
+                    };
+                    if let Some(hooks) = self.hooks.as_mut() {
+                        match hooks.on_fill(&execution) {
+                            FillAction::Fill => {}
+                            FillAction::CancelResting => {
+                                let pulled = pull_at(
+                                    level,
+                                    at,
+                                    level_qty,
+                                    sizes,
+                                    &mut self.order_loc,
+                                    &mut self.owner_orders,
+                                );
+                                let record = AuditRecord::Canceled {
+                                    id: pulled.id,
+                                    qty: pulled.qty,
+                                };
+                                audit::append_to(
+                                    &mut self.audit,
+                                    &mut self.history,
+                                    &*self.clock,
+                                    record,
+                                );
+                                continue;
+                            }
+                            FillAction::CancelIncoming => {
+                                canceled = true;
+                                break 'levels;
+                            }
+                        }
+                    }
+                    stats::resize(sizes, maker.qty, maker.qty - fill);
+                    maker.qty -= fill;
+                    *level_qty -= fill;
+                    result.remaining -= fill;
+                    result.orders.push((level_price, fill));
+                    self.next_trade += 1;
+                    self.last_trade = Some(LastTrade::of(&execution));
+                    self.traded = Some(match self.traded {
+                        Some((low, high)) => (low.min(level_price), high.max(level_price)),
+                        None => (level_price, level_price),
+                    });
+                    self.volume += fill;
+                    if self.tape_capacity > 0 {
+                        let trade = Trade {
+                            time: self.clock.now(),
+                            execution,
+                            busted: false,
+                            maker_seq: maker.seq,
+                            maker_left: maker.qty,
+                        };
+                        bust::push_trade(&mut self.tape, self.tape_capacity, trade);
+                    }
+                    audit::append_to(
+                        &mut self.audit,
+                        &mut self.history,
+                        &*self.clock,
+                        AuditRecord::Fill(execution),
+                    );
+                    if let Some(positions) = self.positions.as_mut() {
+                        positions.on_execution(&execution);
+                    }
+                    if let Some(sink) = self.drop_copy.as_mut() {
+                        sink.on_execution(&execution);
+                    }
+                    if let Some(state) = self.mmp.get_mut(&maker.owner) {
+                        if let Some((fills, volume)) = state.record(now, fill) {
+                            triggers.push(MmpTrigger {
+                                owner: maker.owner,
+                                time: now,
+                                fills,
+                                volume,
+                                canceled: 0,
+                            });
+                        }
+                    }
+                    if maker.qty == 0 {
+                        self.order_loc.remove(&maker.id);
+                        forget_owner(&mut self.owner_orders, maker.owner, maker.id);
+                        level.remove(at);
+                    } else {
+                        at += 1;
+                    }
+                }
+                if result.remaining == 0 || level.is_empty() || self.matching == MatchingMode::Fifo
+                {
+                    break 'pass;
+                }
+            }
+            if result.remaining == 0 {
+                break;
+            }
+        }
+
+        book.pop_empty();
+
+        result.status = match (result.orders.is_empty(), result.remaining) {
+            (_, 0) => OrderStatus::Filled,
+            _ if canceled => OrderStatus::Canceled,
+            (true, _) => OrderStatus::Created,
+            (false, _) => OrderStatus::PartiallyFilled,
+        };
+        if result.remaining > 0 && !canceled {
+            let qty = result.remaining;
+            let order = Order {
+                id,
+                owner,
+                qty,
+                tag,
+                seq: 0,
+                entered: min_rest::UNTIMED,
+            };
+            self.insert(order, side, price);
+            self.record(AuditRecord::Rested { id, qty });
+            result.id = Some(id);
+        }
+        for mut trigger in triggers {
+            trigger.canceled += self.remove_all(trigger.owner);
+            if let Some(listener) = self.mmp_listener.as_mut() {
+                listener.on_mmp_triggered(&trigger);
+            }
+        }
+        if let Some(hooks) = self.hooks.as_mut() {
+            hooks.post_trade(result);
+        }
+    }
+
+    /// Preview the execution of a limit order without modifying the order book
+    ///
+    /// The fills are computed as `execute` would, including the instrument increments,
+    /// triggered market-maker protections, self-trade prevention and the trading phase,
+    /// without consuming liquidity or notifying any subscriber. The credit check and
+    /// hooks are not called and protections the fills themselves would trigger are not
+    /// applied, so the execution can still end differently when they are set.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The result the execution would have, without an order identifier
+    pub fn preview(&self, owner: OwnerId, side: Side, price: Price, qty: OrderQty) -> FillResult {
+        let mut result = FillResult::new();
+        result.remaining = qty;
+        if let Err(reason) = self.instrument.check(price, qty) {
+            result.status = OrderStatus::Rejected(reason);
+            return result;
+        }
+        if self.is_mmp_triggered(owner) {
+            result.status = OrderStatus::Rejected(RejectReason::MmpTriggered);
+            return result;
+        }
+        if self.phase != TradingPhase::Continuous {
+            result.status = OrderStatus::Created;
+            return result;
+        }
+        let book = match side {
+            Side::Bid => &self.asks,
+            Side::Ask => &self.bids,
+        };
+        let crossing = (book.prices.iter().zip(&book.queues).zip(&book.qtys).rev())
+            .take_while(|((level_price, _), _)| crosses(side, price, **level_price));
+        let mut allocations = Vec::new();
+        let mut level = VecDeque::new();
+        let mut canceled = false;
+        'levels: for ((&level_price, &idx), &qty) in crossing {
+            // Match a copy of the level the way `match_order` does, pass by pass
+            level.clone_from(&book.price_levels[idx]);
+            let mut level_qty = qty;
+            'pass: loop {
+                let qty = result.remaining.min(level_qty);
+                pro_rata::plan_pass(&self.matching, &level, qty, &mut allocations);
+                let (mut at, mut slot) = (0, 0);
+                while let Some(maker) = level.get_mut(at) {
+                    if result.remaining == 0 {
+                        break;
+                    }
+                    let fill = pro_rata::pass_fill(
+                        &self.matching,
+                        &allocations,
+                        slot,
+                        maker.qty,
+                        result.remaining,
+                    );
+                    slot += 1;
+                    if fill == 0 {
+                        at += 1;
+                        continue;
+                    }
+                    if maker.owner == owner && self.stp != StpPolicy::Allow {
+                        if self.stp == StpPolicy::CancelResting {
+                            level_qty -= maker.qty;
+                            level.remove(at);
+                            continue;
+                        }
+                        canceled = true;
+                        break 'levels;
+                    }
+                    maker.qty -= fill;
+                    level_qty -= fill;
+                    result.remaining -= fill;
+                    result.orders.push((level_price, fill));
+                    if maker.qty == 0 {
+                        level.remove(at);
+                    } else {
+                        at += 1;
+                    }
+                }
+                if result.remaining == 0 || level.is_empty() || self.matching == MatchingMode::Fifo
+                {
+                    break 'pass;
+                }
+            }
+            if result.remaining == 0 {
+                break;
+            }
+        }
+        result.status = match (result.orders.is_empty(), result.remaining) {
+            (_, 0) => OrderStatus::Filled,
+            _ if canceled => OrderStatus::Canceled,
+            (true, _) => OrderStatus::Created,
+            (false, _) => OrderStatus::PartiallyFilled,
+        };
+        result
+    }
+
+    /// Cancel an order
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order to cancel
+    ///
+    /// # Returns
+    ///
+    /// The result of the cancel operation
+    pub fn cancel(&mut self, id: OrderId) -> CancelResult {
+        self.release_deferred_cancels();
+        if let Some(held) = self.hold_cancel(id) {
+            return held;
+        }
+        self.force_cancel(id)
+    }
+
+    /// Cancel an order regardless of the minimum resting time, for cancels the order
+    /// book makes itself rather than on behalf of the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order to cancel
+    ///
+    /// # Returns
+    ///
+    /// The result of the cancel operation, never held back
+    pub(crate) fn force_cancel(&mut self, id: OrderId) -> CancelResult {
+        match self.remove(id) {
+            None => CancelResult::NotFound,
+            Some(order) => {
+                self.notify_bbo();
+                CancelResult::Canceled(order.tag)
+            }
+        }
+    }
+
+    /// Reduce the quantity of a resting order, keeping its place in the queue
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order to reduce
+    /// * `qty` - The quantity to take off the order
+    ///
+    /// # Returns
+    ///
+    /// The quantity left resting, zero if the order was canceled as a result, `None` if
+    /// the order is not resting in the order book. Reducing the whole quantity is a
+    /// cancel, held back by the minimum resting time like `cancel`, in which case the
+    /// order is left as it was.
+    pub fn reduce(&mut self, id: OrderId, qty: OrderQty) -> Option<OrderQty> {
+        self.reduce_by(id, qty, false)
+    }
+
+    /// Reduce the quantity of a resting order regardless of the minimum resting time
+    pub(crate) fn force_reduce(&mut self, id: OrderId, qty: OrderQty) -> Option<OrderQty> {
+        self.reduce_by(id, qty, true)
+    }
+
+    /// Reduce the quantity of a resting order, holding back a full reduction by the
+    /// minimum resting time unless forced
+    fn reduce_by(&mut self, id: OrderId, qty: OrderQty, force: bool) -> Option<OrderQty> {
+        let (side, idx) = *self.order_loc.get(&id)?;
+        let book = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        let order = book.price_levels[idx].iter_mut().find(|o| o.id == id)?;
+        if qty >= order.qty {
+            let left = order.qty;
+            let canceled = match force {
+                true => self.force_cancel(id),
+                false => self.cancel(id),
+            };
+            return Some(match canceled {
+                CancelResult::Canceled(_) => 0,
+                _ => left,
+            });
+        }
+        stats::resize(&book.sizes[idx], order.qty, order.qty - qty);
+        order.qty -= qty;
+        let left = order.qty;
+        book.take(idx, qty);
+        self.record(AuditRecord::Reduced { id, qty: left });
+        self.notify_bbo();
+        Some(left)
+    }
+
+    /// Remove a resting order from the order book
+    fn remove(&mut self, id: OrderId) -> Option<Order> {
+        let (side, idx) = self.order_loc.remove(&id)?;
+        let book = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        let level = &mut book.price_levels[idx];
+        let pos = level.iter().position(|o| o.id == id)?;
+        let order = level.remove(pos)?;
+        stats::resize(&book.sizes[idx], order.qty, 0);
+        book.take(idx, order.qty);
+        forget_owner(&mut self.owner_orders, order.owner, id);
+        self.record(AuditRecord::Canceled { id, qty: order.qty });
+        Some(order)
+    }
+
+    /// Cancel every resting order of an owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner whose orders to cancel
+    ///
+    /// # Returns
+    ///
+    /// The number of orders canceled
+    pub fn cancel_all(&mut self, owner: OwnerId) -> usize {
+        let canceled = self.remove_all(owner);
+        if canceled > 0 {
+            self.notify_bbo();
+        }
+        canceled
+    }
+
+    /// Remove every resting order of an owner from the order book
+    fn remove_all(&mut self, owner: OwnerId) -> usize {
+        let ids = self.owner_orders.remove(&owner).unwrap_or_default();
+        ids.into_iter()
+            .filter(|id| self.remove(*id).is_some())
+            .count()
+    }
+
+    /// Get a view of a resting order
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    ///
+    /// # Returns
+    ///
+    /// The view of the order, `None` if the order is not resting in the order book
+    pub fn order(&self, id: OrderId) -> Option<OrderView> {
+        let (side, idx) = *self.order_loc.get(&id)?;
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        let order = book.price_levels[idx].iter().find(|o| o.id == id)?;
+        Some(OrderView {
+            id,
+            owner: order.owner,
+            side,
+            price: book.level_prices[idx],
+            qty: order.qty,
+            tag: order.tag,
+        })
+    }
+
+    /// Iterate over the resting orders of a side
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order book
+    ///
+    /// # Returns
+    ///
+    /// The view of each order in priority order, from the best price outwards
+    pub fn orders(&self, side: Side) -> impl Iterator<Item = OrderView> + '_ {
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        (0..book.prices.len()).rev().flat_map(move |pos| {
+            let price = book.prices[pos];
+            (book.price_levels[book.queues[pos]].iter()).map(move |order| OrderView {
+                id: order.id,
+                owner: order.owner,
+                side,
+                price,
+                qty: order.qty,
+                tag: order.tag,
+            })
+        })
+    }
+
+    /// Get the place of a resting order in the queue of its price level
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    ///
+    /// # Returns
+    ///
+    /// The number of orders and the total quantity ahead of the order in its queue,
+    /// `None` if the order is not resting in the order book
+    pub fn queue_position(&self, id: OrderId) -> Option<(usize, OrderQty)> {
+        let (side, idx) = *self.order_loc.get(&id)?;
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        let queue = &book.price_levels[idx];
+        let position = queue.iter().position(|o| o.id == id)?;
+        let qty_ahead = queue.iter().take(position).map(|o| o.qty).sum();
+        Some((position, qty_ahead))
     }
 
     /// Update the best bid and ask prices
@@ -180,55 +1716,137 @@ impl OrderBook {
     ///
     /// A tuple containing the best bid and ask prices, respectively
     pub fn update_best_bid_ask(&mut self) -> (Price, Price) {
-        for (price, idx) in self.asks.price_map.iter() {
-            match self.asks.price_levels[*idx].is_empty() {
-                false => {
-                    self.best_ask = *price;
-                    break;
-                }
-                true => continue,
-            }
+        if let Some(price) = self.asks.prices.last() {
+            self.best_ask = *price;
         }
-        for (price, idx) in self.bids.price_map.iter().rev() {
-            match self.bids.price_levels[*idx].is_empty() {
-                false => {
-                    self.best_bid = *price;
-                    break;
-                }
-                true => continue,
-            }
+        if let Some(price) = self.bids.prices.last() {
+            self.best_bid = *price;
         }
         (self.best_bid, self.best_ask)
     }
-}
 
-// TODO: Implement the fill method
+    /// Take a snapshot of the aggregated depth of the order book
+    ///
+    /// Empty price levels are skipped
+    ///
+    /// # Returns
+    ///
+    /// The bid and ask levels, each ordered from the best price outwards
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            bids: self.bids.levels().collect(),
+            asks: self.asks.levels().collect(),
+        }
+    }
+
+    /// Take a snapshot of the best levels of the order book
+    ///
+    /// The levels are read from the end of the sorted level arrays of each side, so
+    /// there is no bound on how many of the best levels are cheap to query.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The maximum number of levels per side
+    ///
+    /// # Returns
+    ///
+    /// The best bid and ask levels, each ordered from the best price outwards
+    pub fn depth(&self, levels: usize) -> Snapshot {
+        Snapshot {
+            bids: self.bids.levels().take(levels).collect(),
+            asks: self.asks.levels().take(levels).collect(),
+        }
+    }
 
-#[derive(Debug)]
-enum OrderStatus {
+    /// Iterate over the levels of a side with the running total of their quantities
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order book
+    ///
+    /// # Returns
+    ///
+    /// The price, total quantity and cumulative quantity up to and including each
+    /// level, from the best price outwards
+    pub fn cumulative_depth(
+        &self,
+        side: Side,
+    ) -> impl Iterator<Item = (Price, OrderQty, OrderQty)> + '_ {
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        book.levels().scan(0, |cumulative, (price, qty)| {
+            *cumulative += qty;
+            Some((price, qty, *cumulative))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderStatus {
+    /// Order has not been processed yet
     Unititialized,
+
+    /// Order was added to the order book without any fill
     Created,
+
+    /// Order was completely filled
     Filled,
+
+    /// Order was partially filled, the remaining quantity was added to the order book
     PartiallyFilled,
+
+    /// Order was rejected before matching
+    Rejected(RejectReason),
+
+    /// Order was canceled during matching, the remaining quantity was not added to the
+    /// order book
+    Canceled,
+
+    /// Post-only order that would have crossed was added to the order book without any
+    /// fill at the given price, inside the opposite best price
+    Repriced(Price),
 }
 
-#[derive(Debug)]
-struct FillResult {
-    remaining: u64,
-    status: OrderStatus,
-    orders: Vec<(u64, u64)>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillResult {
+    /// Identifier of the order added to the order book with the remaining quantity, if any
+    pub id: Option<OrderId>,
+
+    /// Quantity left unfilled
+    pub remaining: u64,
+
+    /// Status of the order
+    pub status: OrderStatus,
+
+    /// Fills of the order as (price, quantity) pairs, in execution order
+    pub orders: Vec<(u64, u64)>,
+}
+
+impl Default for FillResult {
+    fn default() -> Self {
+        FillResult::new()
+    }
 }
 
 impl FillResult {
-    fn new() -> Self {
+    /// Create an empty result, e.g. to reuse as the buffer of `execute_into`
+    pub fn new() -> Self {
         FillResult {
+            id: None,
             orders: Vec::new(),
             remaining: u64::MAX,
             status: OrderStatus::Unititialized,
         }
     }
 
-    fn avg_price(&self) -> f64 {
+    /// Get the average price of the fills
+    ///
+    /// # Returns
+    ///
+    /// The quantity-weighted average fill price, NaN if there were no fills
+    pub fn avg_price(&self) -> f64 {
         let (total, quantity) = self.orders.iter().fold((0, 0), |(total, quantity), (price, qty)| {
             (total + price * qty, quantity + qty)
         });
@@ -239,24 +1857,193 @@ impl FillResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::watchdog::{allocations, CountingAllocator};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
 
     #[test]
     fn test_order_book() {
         let mut book = OrderBook::new();
-        book.add(Side::Bid, 100, 10);
-        book.add(Side::Ask, 101, 10);
-        book.add(Side::Ask, 101, 10);
-        book.add(Side::Ask, 102, 10);
-        book.add(Side::Bid, 99, 10);
-        book.add(Side::Bid, 98, 10);
-        book.add(Side::Ask, 103, 10);
-        book.add(Side::Ask, 104, 10);
-        let id = book.add(Side::Bid, 105, 10);
-        assert_eq!(book.cancel(id), CancelResult::Canceled);
+        book.add(1, Side::Bid, 100, 10).unwrap();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 102, 10).unwrap();
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        book.add(1, Side::Bid, 98, 10).unwrap();
+        book.add(1, Side::Ask, 103, 10).unwrap();
+        book.add(1, Side::Ask, 104, 10).unwrap();
+        let id = book.add(1, Side::Bid, 105, 10).unwrap();
+        assert_eq!(book.cancel(id), CancelResult::Canceled(0));
         let (bid, ask) = book.update_best_bid_ask();
         assert_eq!(bid, 100);
         assert_eq!(ask, 101);
         assert_eq!(book.get_total_qty(Side::Bid, bid), 10);
         assert_eq!(book.get_total_qty(Side::Ask, ask), 20);
     }
+
+    #[test]
+    fn test_execute() {
+        let mut book = OrderBook::new();
+        let maker = book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 103, 10).unwrap();
+
+        let result = book.execute(2, Side::Bid, 100, 5);
+        assert_eq!(result.status, OrderStatus::Created);
+        assert!(result.orders.is_empty());
+
+        let result = book.execute(2, Side::Bid, 102, 15);
+        assert_eq!(result.status, OrderStatus::Filled);
+        assert_eq!(result.orders, vec![(101, 10), (101, 5)]);
+        assert_eq!(result.id, None);
+        assert_eq!(book.cancel(maker), CancelResult::NotFound);
+
+        let result = book.execute(2, Side::Bid, 103, 20);
+        assert_eq!(result.status, OrderStatus::PartiallyFilled);
+        assert_eq!(result.orders, vec![(101, 5), (103, 10)]);
+        assert_eq!(result.remaining, 5);
+        assert_eq!(book.get_total_qty(Side::Bid, 103), 5);
+
+        let result = book.execute(3, Side::Ask, 100, 8);
+        assert_eq!(result.orders, vec![(103, 5), (100, 3)]);
+        assert_eq!(result.avg_price(), 101.875);
+        assert_eq!(book.snapshot().bids, vec![(100, 2)]);
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 102, 10).unwrap();
+        let before = book.snapshot();
+
+        let preview = book.preview(2, Side::Bid, 102, 15);
+        assert_eq!(preview.status, OrderStatus::Filled);
+        assert_eq!(preview.orders, vec![(101, 10), (102, 5)]);
+        assert_eq!(book.snapshot(), before);
+
+        let preview = book.preview(2, Side::Bid, 101, 15);
+        assert_eq!(preview.status, OrderStatus::PartiallyFilled);
+        assert_eq!(preview.remaining, 5);
+        assert_eq!(preview.id, None);
+
+        let result = book.execute(2, Side::Bid, 101, 15);
+        assert_eq!(result.orders, preview.orders);
+
+        book.pre_open();
+        let preview = book.preview(2, Side::Bid, 102, 5);
+        assert_eq!(
+            (preview.status, preview.remaining),
+            (OrderStatus::Created, 5)
+        );
+        assert_eq!(preview.orders, book.execute(2, Side::Bid, 102, 5).orders);
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut book = OrderBook::new();
+        let maker = book.add_tagged(1, Side::Ask, 101, 10, 7).unwrap();
+        assert_eq!(
+            book.order(maker),
+            Some(OrderView {
+                id: maker,
+                owner: 1,
+                side: Side::Ask,
+                price: 101,
+                qty: 10,
+                tag: 7,
+            })
+        );
+        let result = book.execute_tagged(2, Side::Bid, 101, 4, 8);
+        assert_eq!(result.status, OrderStatus::Filled);
+        assert_eq!(book.order(maker).unwrap().qty, 6);
+        assert_eq!(book.cancel(maker), CancelResult::Canceled(7));
+        assert_eq!(book.order(maker), None);
+    }
+
+    #[test]
+    fn test_level_qty() {
+        let mut book = OrderBook::new();
+        let first = book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(2, Side::Ask, 101, 15).unwrap();
+        book.add(3, Side::Ask, 102, 5).unwrap();
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 25);
+
+        book.execute(4, Side::Bid, 101, 12);
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 13);
+        book.cancel(first);
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 13);
+        book.cancel_all(2);
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 0);
+        assert_eq!(book.snapshot().asks, vec![(102, 5)]);
+    }
+
+    #[test]
+    fn test_depth() {
+        let mut book = OrderBook::new();
+        for price in 0..30 {
+            book.add(1, Side::Bid, 100 + price, 1).unwrap();
+            book.add(1, Side::Ask, 200 + price, 1).unwrap();
+        }
+        let full = book.snapshot();
+        assert_eq!(book.depth(10).bids, full.bids[..10]);
+        assert_eq!(book.depth(3).asks, vec![(200, 1), (201, 1), (202, 1)]);
+
+        book.execute(2, Side::Bid, 214, 15);
+        book.execute(2, Side::Ask, 125, 100);
+        let full = book.snapshot();
+        assert_eq!(book.depth(10).asks, full.asks[..10]);
+        assert_eq!(book.depth(10).bids, full.bids[..10]);
+        assert_eq!(book.depth(15).bids, full.bids[..15]);
+        assert_eq!(book.bbo().ask, Some((125, 95)));
+    }
+
+    #[test]
+    fn test_cumulative_depth() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        book.add(1, Side::Bid, 100, 5).unwrap();
+        book.add(1, Side::Bid, 100, 2).unwrap();
+        book.add(1, Side::Bid, 97, 1).unwrap();
+        let depth: Vec<_> = book.cumulative_depth(Side::Bid).collect();
+        assert_eq!(depth, vec![(100, 7, 7), (99, 10, 17), (97, 1, 18)]);
+        assert_eq!(book.cumulative_depth(Side::Ask).next(), None);
+    }
+
+    #[test]
+    fn test_execute_into_allocation_free() {
+        let mut book = OrderBook::new();
+        for price in 100..110 {
+            book.add(1, Side::Ask, price, 10).unwrap();
+        }
+        book.add(1, Side::Bid, 90, 10).unwrap();
+        let mut result = FillResult::new();
+        result.orders.reserve(16);
+        book.execute_into(1, Side::Bid, 95, 5, 0, &mut result);
+        book.execute_into(2, Side::Ask, 95, 5, 0, &mut result);
+
+        let before = allocations();
+        book.execute_into(2, Side::Bid, 101, 15, 0, &mut result);
+        assert_eq!(result.orders, [(100, 10), (101, 5)]);
+        book.execute_into(1, Side::Bid, 95, 5, 0, &mut result);
+        assert_eq!(allocations(), before);
+        assert_eq!(result.status, OrderStatus::Created);
+        assert!(result.orders.is_empty());
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let mut book = OrderBook::new();
+        let first = book.add(1, Side::Bid, 100, 10).unwrap();
+        let second = book.add(2, Side::Bid, 100, 5).unwrap();
+        let third = book.add(3, Side::Bid, 100, 7).unwrap();
+        assert_eq!(book.queue_position(first), Some((0, 0)));
+        assert_eq!(book.queue_position(third), Some((2, 15)));
+
+        book.execute(4, Side::Ask, 100, 4);
+        book.cancel(second);
+        assert_eq!(book.queue_position(third), Some((1, 6)));
+        assert_eq!(book.queue_position(second), None);
+    }
 }
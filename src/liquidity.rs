@@ -0,0 +1,239 @@
+use crate::{OrderBook, OrderQty, Price, Side};
+use std::fmt;
+use std::mem;
+use std::sync::mpsc::Sender;
+
+/// Identifier of a registered liquidity alert
+pub type AlertId = u64;
+
+/// Predicate over the state of an order book
+pub trait LiquidityPredicate: fmt::Debug {
+    /// Check whether the predicate holds
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book after a change
+    fn holds(&self, book: &OrderBook) -> bool;
+}
+
+/// Common liquidity conditions, with distances in ticks of the instrument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityCondition {
+    /// Quantity resting within a distance of the best price of a side is below a
+    /// threshold, an empty side having none
+    DepthBelow {
+        /// Side of the order book
+        side: Side,
+
+        /// Distance from the best price, in ticks
+        ticks: Price,
+
+        /// Threshold quantity
+        qty: OrderQty,
+    },
+
+    /// Spread exceeds a number of ticks, a missing side counting as exceeding it
+    SpreadAbove {
+        /// Spread threshold, in ticks
+        ticks: Price,
+    },
+}
+
+impl LiquidityPredicate for LiquidityCondition {
+    fn holds(&self, book: &OrderBook) -> bool {
+        let tick = book.instrument.tick_size.max(1);
+        match *self {
+            LiquidityCondition::DepthBelow { side, ticks, qty } => {
+                let mut levels = book.cumulative_depth(side).peekable();
+                let Some(&(best, _, _)) = levels.peek() else {
+                    return qty > 0;
+                };
+                let depth = levels
+                    .take_while(|&(price, _, _)| price.abs_diff(best) <= ticks * tick)
+                    .last()
+                    .map_or(0, |(_, _, cumulative)| cumulative);
+                depth < qty
+            }
+            LiquidityCondition::SpreadAbove { ticks } => {
+                let bbo = book.bbo();
+                match (bbo.bid, bbo.ask) {
+                    (Some((bid, _)), Some((ask, _))) => ask.saturating_sub(bid) > ticks * tick,
+                    _ => true,
+                }
+            }
+        }
+    }
+}
+
+/// Change of the state of a liquidity alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidityAlert {
+    /// Identifier of the alert
+    pub id: AlertId,
+
+    /// Whether the predicate started holding, or stopped holding
+    pub raised: bool,
+
+    /// Time of the change, from the clock of the order book
+    pub time: u64,
+}
+
+/// Subscriber to liquidity alerts
+pub trait LiquidityListener: fmt::Debug {
+    /// Called whenever the predicate of an alert starts or stops holding
+    ///
+    /// # Arguments
+    ///
+    /// * `alert` - The change of the alert
+    fn on_alert(&mut self, alert: &LiquidityAlert);
+}
+
+/// Forward alerts over a channel, dropping them once the receiver hangs up
+impl LiquidityListener for Sender<LiquidityAlert> {
+    fn on_alert(&mut self, alert: &LiquidityAlert) {
+        let _ = self.send(*alert);
+    }
+}
+
+/// Registered liquidity alert
+#[derive(Debug)]
+pub(crate) struct Registered {
+    /// Identifier of the alert
+    id: AlertId,
+
+    /// Predicate of the alert
+    predicate: Box<dyn LiquidityPredicate>,
+
+    /// Whether the predicate held at the last evaluation
+    active: bool,
+}
+
+impl OrderBook {
+    /// Set the subscriber to liquidity alerts
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_liquidity_listener<L: LiquidityListener + 'static>(&mut self, listener: L) {
+        self.liquidity_listener = Some(Box::new(listener));
+    }
+
+    /// Register a liquidity alert
+    ///
+    /// The predicate is evaluated whenever the order book changes and the subscriber is
+    /// told when it starts and stops holding, so strategies need not poll the order
+    /// book. An alert whose predicate already holds is raised at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - The predicate of the alert
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the alert
+    pub fn add_liquidity_alert<P: LiquidityPredicate + 'static>(
+        &mut self,
+        predicate: P,
+    ) -> AlertId {
+        self.next_alert += 1;
+        self.liquidity_alerts.push(Registered {
+            id: self.next_alert,
+            predicate: Box::new(predicate),
+            active: false,
+        });
+        self.evaluate_alerts();
+        self.next_alert
+    }
+
+    /// Remove a liquidity alert
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the alert
+    ///
+    /// # Returns
+    ///
+    /// Whether the alert was registered
+    pub fn remove_liquidity_alert(&mut self, id: AlertId) -> bool {
+        let before = self.liquidity_alerts.len();
+        self.liquidity_alerts.retain(|alert| alert.id != id);
+        self.liquidity_alerts.len() != before
+    }
+
+    /// Check whether the predicate of a liquidity alert currently holds
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the alert
+    ///
+    /// # Returns
+    ///
+    /// Whether the alert is raised, `None` if it is not registered
+    pub fn is_alert_raised(&self, id: AlertId) -> Option<bool> {
+        (self.liquidity_alerts.iter())
+            .find(|alert| alert.id == id)
+            .map(|alert| alert.active)
+    }
+
+    /// Evaluate the liquidity alerts after a change, notifying the subscriber of those
+    /// that were raised or cleared
+    pub(crate) fn evaluate_alerts(&mut self) {
+        if self.liquidity_alerts.is_empty() {
+            return;
+        }
+        let mut alerts = mem::take(&mut self.liquidity_alerts);
+        let time = self.clock.now();
+        for alert in &mut alerts {
+            let holds = alert.predicate.holds(self);
+            if holds == alert.active {
+                continue;
+            }
+            alert.active = holds;
+            if let Some(listener) = self.liquidity_listener.as_mut() {
+                listener.on_alert(&LiquidityAlert {
+                    id: alert.id,
+                    raised: holds,
+                    time,
+                });
+            }
+        }
+        self.liquidity_alerts = alerts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_liquidity_alerts() {
+        let mut book = OrderBook::new();
+        let (tx, rx) = channel();
+        book.set_liquidity_listener(tx);
+        book.add(1, Side::Bid, 100, 10).unwrap();
+        book.add(1, Side::Ask, 101, 300).unwrap();
+        book.add(1, Side::Ask, 103, 300).unwrap();
+        book.add(1, Side::Ask, 105, 300).unwrap();
+
+        let thin = book.add_liquidity_alert(LiquidityCondition::DepthBelow {
+            side: Side::Ask,
+            ticks: 3,
+            qty: 500,
+        });
+        let wide = book.add_liquidity_alert(LiquidityCondition::SpreadAbove { ticks: 2 });
+        assert_eq!(rx.try_iter().count(), 0);
+
+        book.execute(2, Side::Bid, 101, 300);
+        let alerts: Vec<_> = rx.try_iter().map(|a| (a.id, a.raised)).collect();
+        assert_eq!(alerts, vec![(wide, true)]);
+        assert_eq!(book.is_alert_raised(thin), Some(false));
+
+        book.execute(2, Side::Bid, 103, 200);
+        book.add(1, Side::Bid, 102, 10).unwrap();
+        let alerts: Vec<_> = rx.try_iter().map(|a| (a.id, a.raised)).collect();
+        assert_eq!(alerts, vec![(thin, true), (wide, false)]);
+        assert!(book.remove_liquidity_alert(thin));
+        assert_eq!(book.is_alert_raised(thin), None);
+    }
+}
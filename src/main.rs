@@ -2,14 +2,14 @@ use execution::{OrderBook, Side};
 
 fn main() {
     let mut book = OrderBook::new();
-    book.add(Side::Bid, 100, 10);
-    book.add(Side::Ask, 101, 10);
-    book.add(Side::Ask, 102, 10);
-    book.add(Side::Bid, 99, 10);
-    book.add(Side::Bid, 98, 10);
-    book.add(Side::Ask, 103, 10);
-    book.add(Side::Ask, 104, 10);
-    let id = book.add(Side::Bid, 105, 10);
+    book.add(1, Side::Bid, 100, 10).unwrap();
+    book.add(1, Side::Ask, 101, 10).unwrap();
+    book.add(1, Side::Ask, 102, 10).unwrap();
+    book.add(1, Side::Bid, 99, 10).unwrap();
+    book.add(1, Side::Bid, 98, 10).unwrap();
+    book.add(1, Side::Ask, 103, 10).unwrap();
+    book.add(1, Side::Ask, 104, 10).unwrap();
+    let id = book.add(1, Side::Bid, 105, 10).unwrap();
     println!("{:?}", book.cancel(id));
     let (bid, ask) = book.update_best_bid_ask();
     println!("Best bid: {:?}, best ask: {:?}", bid, ask);
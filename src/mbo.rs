@@ -0,0 +1,467 @@
+use crate::{Execution, OrderBook, OrderId, Price, Side, SYNTHETIC_OWNER};
+use std::collections::HashMap;
+use std::io;
+
+/// Record type of market-by-order records
+pub const MBO_RTYPE: u8 = 0xA0;
+
+/// Length in bytes of an encoded market-by-order record
+pub const MBO_RECORD_LEN: usize = 56;
+
+/// Flag marking the last record of an event
+pub const F_LAST: u8 = 1 << 7;
+
+/// Flag marking a record that is part of a snapshot of the order book
+pub const F_SNAPSHOT: u8 = 1 << 5;
+
+/// Price of records that carry none
+pub const UNDEF_PRICE: i64 = i64::MAX;
+
+/// Action of a market-by-order record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MboAction {
+    /// An order was added
+    Add,
+
+    /// An order was partially or fully canceled
+    Cancel,
+
+    /// The price or size of an order changed
+    Modify,
+
+    /// The order book was cleared
+    Clear,
+
+    /// An aggressing order traded
+    Trade,
+
+    /// A resting order was filled
+    Fill,
+
+    /// The record carries no action
+    None,
+}
+
+impl MboAction {
+    /// Get the character code of the action
+    fn code(self) -> u8 {
+        match self {
+            MboAction::Add => b'A',
+            MboAction::Cancel => b'C',
+            MboAction::Modify => b'M',
+            MboAction::Clear => b'R',
+            MboAction::Trade => b'T',
+            MboAction::Fill => b'F',
+            MboAction::None => b'N',
+        }
+    }
+
+    /// Parse the character code of an action
+    fn from_code(code: u8) -> Option<MboAction> {
+        match code {
+            b'A' => Some(MboAction::Add),
+            b'C' => Some(MboAction::Cancel),
+            b'M' => Some(MboAction::Modify),
+            b'R' => Some(MboAction::Clear),
+            b'T' => Some(MboAction::Trade),
+            b'F' => Some(MboAction::Fill),
+            b'N' => Some(MboAction::None),
+            _ => None,
+        }
+    }
+}
+
+/// Market-by-order record of the normalized schema used by Databento
+///
+/// Prices are signed fixed-point numbers in units of 1e-9, `UNDEF_PRICE` when absent,
+/// and times are nanoseconds since the UNIX epoch. The binary encoding is the 56-byte
+/// little-endian layout of the schema, record header included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MboRecord {
+    /// Identifier of the publisher of the record
+    pub publisher_id: u16,
+
+    /// Identifier of the instrument
+    pub instrument_id: u32,
+
+    /// Time of the event at the venue
+    pub ts_event: u64,
+
+    /// Identifier of the order at the venue
+    pub order_id: u64,
+
+    /// Price of the order or trade
+    pub price: i64,
+
+    /// Quantity of the order or trade
+    pub size: u32,
+
+    /// Bit field of `F_LAST`, `F_SNAPSHOT` and other flags
+    pub flags: u8,
+
+    /// Channel of the venue the record was received on
+    pub channel_id: u8,
+
+    /// Action of the record
+    pub action: MboAction,
+
+    /// Side of the order, of the aggressor for trades, `None` if unknown
+    pub side: Option<Side>,
+
+    /// Time the record was received
+    pub ts_recv: u64,
+
+    /// Delay between the sending and the receipt of the record, in nanoseconds
+    pub ts_in_delta: i32,
+
+    /// Sequence number of the message at the venue
+    pub sequence: u32,
+}
+
+impl MboRecord {
+    /// Encode the record in the binary layout of the schema
+    pub fn encode(&self) -> [u8; MBO_RECORD_LEN] {
+        let mut buf = [0; MBO_RECORD_LEN];
+        buf[0] = (MBO_RECORD_LEN / 4) as u8;
+        buf[1] = MBO_RTYPE;
+        buf[2..4].copy_from_slice(&self.publisher_id.to_le_bytes());
+        buf[4..8].copy_from_slice(&self.instrument_id.to_le_bytes());
+        buf[8..16].copy_from_slice(&self.ts_event.to_le_bytes());
+        buf[16..24].copy_from_slice(&self.order_id.to_le_bytes());
+        buf[24..32].copy_from_slice(&self.price.to_le_bytes());
+        buf[32..36].copy_from_slice(&self.size.to_le_bytes());
+        buf[36] = self.flags;
+        buf[37] = self.channel_id;
+        buf[38] = self.action.code();
+        buf[39] = match self.side {
+            Some(Side::Bid) => b'B',
+            Some(Side::Ask) => b'A',
+            None => b'N',
+        };
+        buf[40..48].copy_from_slice(&self.ts_recv.to_le_bytes());
+        buf[48..52].copy_from_slice(&self.ts_in_delta.to_le_bytes());
+        buf[52..56].copy_from_slice(&self.sequence.to_le_bytes());
+        buf
+    }
+
+    /// Decode a record from the binary layout of the schema
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - The encoded record, trailing bytes are ignored
+    ///
+    /// # Returns
+    ///
+    /// The record, or an error if the bytes do not hold a market-by-order record
+    pub fn decode(buf: &[u8]) -> io::Result<MboRecord> {
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if buf.len() < MBO_RECORD_LEN || buf[0] as usize * 4 != MBO_RECORD_LEN {
+            return Err(invalid("truncated market-by-order record"));
+        }
+        if buf[1] != MBO_RTYPE {
+            return Err(invalid("not a market-by-order record"));
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(buf[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| u64::from_le_bytes(buf[at..at + 8].try_into().unwrap());
+        Ok(MboRecord {
+            publisher_id: u16::from_le_bytes([buf[2], buf[3]]),
+            instrument_id: u32_at(4),
+            ts_event: u64_at(8),
+            order_id: u64_at(16),
+            price: u64_at(24) as i64,
+            size: u32_at(32),
+            flags: buf[36],
+            channel_id: buf[37],
+            action: MboAction::from_code(buf[38]).ok_or_else(|| invalid("unknown action"))?,
+            side: match buf[39] {
+                b'B' => Some(Side::Bid),
+                b'A' => Some(Side::Ask),
+                b'N' => None,
+                _ => return Err(invalid("unknown side")),
+            },
+            ts_recv: u64_at(40),
+            ts_in_delta: u32_at(48) as i32,
+            sequence: u32_at(52),
+        })
+    }
+}
+
+/// Converter between market-by-order records of an instrument and an order book
+///
+/// Applied records rest orders owned by `SYNTHETIC_OWNER`, tracked by venue order
+/// identifier. Exported records carry the identifiers of the order book.
+#[derive(Debug, Clone)]
+pub struct MboConverter {
+    /// Identifier of the instrument of the order book
+    instrument_id: u32,
+
+    /// Fixed-point price units per tick of the order book
+    tick: i64,
+
+    /// Orders of the order book by venue order identifier
+    orders: HashMap<u64, OrderId>,
+
+    /// Sequence number of the last exported record
+    sequence: u32,
+}
+
+impl MboConverter {
+    /// Create a new converter
+    ///
+    /// # Arguments
+    ///
+    /// * `instrument_id` - The identifier of the instrument of the order book
+    /// * `tick` - The fixed-point price of one tick of the order book, in units of 1e-9
+    pub fn new(instrument_id: u32, tick: i64) -> MboConverter {
+        MboConverter {
+            instrument_id,
+            tick: tick.max(1),
+            orders: HashMap::new(),
+            sequence: 0,
+        }
+    }
+
+    /// Convert a fixed-point price to ticks
+    fn to_price(&self, price: i64) -> io::Result<Price> {
+        if price < 0 || price == UNDEF_PRICE || price % self.tick != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "price is not a tick of the order book",
+            ));
+        }
+        Ok((price / self.tick) as Price)
+    }
+
+    /// Convert a price in ticks to fixed-point
+    fn to_fixed(&self, price: Price) -> i64 {
+        (price as i64).saturating_mul(self.tick)
+    }
+
+    /// Apply a record to the order book
+    ///
+    /// Records of other instruments, trades and fills are ignored, the resulting
+    /// changes to resting orders being carried by their own records. Cancels of
+    /// unknown orders are ignored and modifies of unknown orders add them, as happens
+    /// when a recording starts mid-session.
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to apply the record to
+    /// * `record` - The record to apply
+    ///
+    /// # Returns
+    ///
+    /// Nothing, or an error if the record cannot be represented in the order book
+    pub fn apply(&mut self, book: &mut OrderBook, record: &MboRecord) -> io::Result<()> {
+        if record.instrument_id != self.instrument_id {
+            return Ok(());
+        }
+        match record.action {
+            MboAction::Add => self.add(book, record)?,
+            MboAction::Cancel => {
+                if let Some(&id) = self.orders.get(&record.order_id) {
+                    if book.force_reduce(id, record.size as u64) != Some(0) {
+                        return Ok(());
+                    }
+                    self.orders.remove(&record.order_id);
+                }
+            }
+            MboAction::Modify => match self.orders.get(&record.order_id) {
+                Some(&id) => {
+                    let price = self.to_price(record.price)?;
+                    let size = record.size as u64;
+                    match book.order(id) {
+                        Some(order) if order.price == price && size > 0 && size <= order.qty => {
+                            book.force_reduce(id, order.qty - size);
+                        }
+                        _ => {
+                            book.force_cancel(id);
+                            self.orders.remove(&record.order_id);
+                            self.add(book, record)?;
+                        }
+                    }
+                }
+                None => self.add(book, record)?,
+            },
+            MboAction::Clear => {
+                for (_, id) in self.orders.drain() {
+                    book.force_cancel(id);
+                }
+            }
+            MboAction::Trade | MboAction::Fill | MboAction::None => {}
+        }
+        Ok(())
+    }
+
+    /// Rest the order of an add record
+    fn add(&mut self, book: &mut OrderBook, record: &MboRecord) -> io::Result<()> {
+        let side = record
+            .side
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "order without a side"))?;
+        let price = self.to_price(record.price)?;
+        if record.size > 0 {
+            let id = book
+                .add(SYNTHETIC_OWNER, side, price, record.size as u64)
+                .map_err(|reason| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{reason:?}"))
+                })?;
+            self.orders.insert(record.order_id, id);
+        }
+        Ok(())
+    }
+
+    /// Build an exported record, consuming a sequence number
+    fn record(&mut self, ts: u64, action: MboAction, side: Option<Side>) -> MboRecord {
+        self.sequence = self.sequence.wrapping_add(1);
+        MboRecord {
+            publisher_id: 0,
+            instrument_id: self.instrument_id,
+            ts_event: ts,
+            order_id: 0,
+            price: UNDEF_PRICE,
+            size: 0,
+            flags: 0,
+            channel_id: 0,
+            action,
+            side,
+            ts_recv: ts,
+            ts_in_delta: 0,
+            sequence: self.sequence,
+        }
+    }
+
+    /// Export an execution as a trade record of the taker followed by a fill record of
+    /// the maker
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The execution to export
+    /// * `ts` - The time of the execution
+    ///
+    /// # Returns
+    ///
+    /// The trade and fill records
+    pub fn export_execution(&mut self, execution: &Execution, ts: u64) -> [MboRecord; 2] {
+        let maker_side = match execution.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let mut trade = self.record(ts, MboAction::Trade, Some(execution.side));
+        trade.order_id = execution.taker.0;
+        let mut fill = self.record(ts, MboAction::Fill, Some(maker_side));
+        fill.order_id = execution.maker.0;
+        fill.flags = F_LAST;
+        for record in [&mut trade, &mut fill] {
+            record.price = self.to_fixed(execution.price);
+            record.size = execution.qty.min(u32::MAX as u64) as u32;
+        }
+        [trade, fill]
+    }
+
+    /// Export the resting orders of an order book as a snapshot: a clear record
+    /// followed by an add record per order, bids then asks, each from the best level
+    /// in queue order
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to export
+    /// * `ts` - The time of the snapshot
+    ///
+    /// # Returns
+    ///
+    /// The records of the snapshot, the last one flagged `F_LAST`
+    pub fn export_book(&mut self, book: &OrderBook, ts: u64) -> Vec<MboRecord> {
+        let mut records = vec![self.record(ts, MboAction::Clear, None)];
+        for (side, half) in [(Side::Bid, &book.bids), (Side::Ask, &book.asks)] {
+            for &idx in half.queues.iter().rev() {
+                for order in &half.price_levels[idx] {
+                    let mut record = self.record(ts, MboAction::Add, Some(side));
+                    record.order_id = order.id.0;
+                    record.price = self.to_fixed(half.level_prices[idx]);
+                    record.size = order.qty.min(u32::MAX as u64) as u32;
+                    records.push(record);
+                }
+            }
+        }
+        for record in &mut records {
+            record.flags |= F_SNAPSHOT;
+        }
+        if let Some(last) = records.last_mut() {
+            last.flags |= F_LAST;
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(order_id: u64, side: Side, price: i64, size: u32) -> MboRecord {
+        MboRecord {
+            publisher_id: 1,
+            instrument_id: 7,
+            ts_event: 1_000,
+            order_id,
+            price,
+            size,
+            flags: F_LAST,
+            channel_id: 0,
+            action: MboAction::Add,
+            side: Some(side),
+            ts_recv: 1_100,
+            ts_in_delta: -5,
+            sequence: order_id as u32,
+        }
+    }
+
+    #[test]
+    fn test_mbo_apply_export() {
+        let record = add(11, Side::Bid, 100_250_000_000, 10);
+        assert_eq!(MboRecord::decode(&record.encode()).unwrap(), record);
+        assert!(MboRecord::decode(&record.encode()[..40]).is_err());
+
+        let mut converter = MboConverter::new(7, 250_000_000);
+        let mut book = OrderBook::builder().tape(4).build();
+        let records = [
+            record,
+            add(12, Side::Bid, 100_250_000_000, 5),
+            add(13, Side::Ask, 100_500_000_000, 8),
+            MboRecord {
+                action: MboAction::Cancel,
+                size: 4,
+                ..add(11, Side::Bid, 100_250_000_000, 0)
+            },
+            MboRecord {
+                action: MboAction::Modify,
+                ..add(12, Side::Bid, 100_000_000_000, 5)
+            },
+        ];
+        for record in &records {
+            converter.apply(&mut book, record).unwrap();
+        }
+        assert!(converter
+            .apply(&mut book, &add(14, Side::Ask, 1, 1))
+            .is_err());
+        assert_eq!(book.snapshot().bids, vec![(401, 6), (400, 5)]);
+
+        let result = book.execute(1, Side::Ask, 401, 6);
+        assert_eq!(result.orders, vec![(401, 6)]);
+        let trade = book.tape().next().unwrap().execution;
+        let [taker, maker] = converter.export_execution(&trade, 2_000);
+        assert_eq!(
+            (taker.action, taker.side),
+            (MboAction::Trade, Some(Side::Ask))
+        );
+        assert_eq!((maker.action, maker.size), (MboAction::Fill, 6));
+        assert_eq!(maker.price, 100_250_000_000);
+        let snapshot = converter.export_book(&book, 2_000);
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot[0].action, MboAction::Clear);
+        assert_eq!((snapshot[1].price, snapshot[1].size), (100_000_000_000, 5));
+        assert_eq!(snapshot[2].flags, F_SNAPSHOT | F_LAST);
+
+        converter.apply(&mut book, &snapshot[0]).unwrap();
+        assert_eq!(book.snapshot(), Default::default());
+    }
+}
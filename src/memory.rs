@@ -0,0 +1,110 @@
+use crate::{HalfBook, Order, OrderBook, OrderId, OrderQty, OwnerId, Price, Side};
+use std::collections::{HashSet, VecDeque};
+use std::mem::size_of;
+
+/// Memory usage of an order book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of orders resting in the order book
+    pub resting_orders: usize,
+
+    /// Number of order queues allocated, one per price ever used
+    pub level_slots: usize,
+
+    /// Number of non-empty price levels
+    pub used_levels: usize,
+
+    /// Number of entries the map of order id to location can hold without growing
+    pub order_map_capacity: usize,
+
+    /// Number of entries of the maps of price to order queue
+    pub price_map_len: usize,
+
+    /// Number of owners with resting orders
+    pub owners: usize,
+
+    /// Approximate heap bytes allocated by the order book
+    pub approx_bytes: usize,
+}
+
+impl MemoryStats {
+    /// Get the number of allocated order queues whose price level is empty, which
+    /// compacting the order book would release
+    pub fn empty_level_slots(&self) -> usize {
+        self.level_slots - self.used_levels
+    }
+}
+
+/// Approximate heap bytes of a hash map, one control byte per bucket
+fn map_bytes<K, V>(capacity: usize) -> usize {
+    capacity * (size_of::<(K, V)>() + 1)
+}
+
+impl HalfBook {
+    /// Approximate heap bytes allocated by this half of the order book
+    fn heap_bytes(&self) -> usize {
+        let queued: usize = self.price_levels.iter().map(VecDeque::capacity).sum();
+        self.prices.capacity() * size_of::<Price>()
+            + self.qtys.capacity() * size_of::<OrderQty>()
+            + self.queues.capacity() * size_of::<usize>()
+            + map_bytes::<Price, usize>(self.price_map.capacity())
+            + self.price_levels.capacity() * size_of::<VecDeque<Order>>()
+            + queued * size_of::<Order>()
+            + self.level_prices.capacity() * size_of::<Price>()
+    }
+}
+
+impl OrderBook {
+    /// Report the memory usage of the order book
+    ///
+    /// Order queues are kept once allocated, so a growing number of empty level slots
+    /// hints that the order book should be compacted
+    ///
+    /// # Returns
+    ///
+    /// The counts and approximate heap bytes of the order book structures
+    pub fn memory_stats(&self) -> MemoryStats {
+        let halves = [&self.bids, &self.asks];
+        let owned: usize = self.owner_orders.values().map(HashSet::capacity).sum();
+        let approx_bytes = halves.iter().map(|book| book.heap_bytes()).sum::<usize>()
+            + map_bytes::<OrderId, (Side, usize)>(self.order_loc.capacity())
+            + map_bytes::<OwnerId, HashSet<OrderId>>(self.owner_orders.capacity())
+            + owned * (size_of::<OrderId>() + 1);
+        MemoryStats {
+            resting_orders: self.order_loc.len(),
+            level_slots: halves.iter().map(|book| book.price_levels.len()).sum(),
+            used_levels: halves.iter().map(|book| book.prices.len()).sum(),
+            order_map_capacity: self.order_loc.capacity(),
+            price_map_len: halves.iter().map(|book| book.price_map.len()).sum(),
+            owners: self.owner_orders.len(),
+            approx_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_stats() {
+        let mut book = OrderBook::new();
+        let empty = book.memory_stats();
+        for price in 100..110 {
+            book.add(1, Side::Ask, price, 10).unwrap();
+        }
+        book.add(2, Side::Bid, 90, 10).unwrap();
+        book.execute(3, Side::Bid, 104, 50);
+
+        let stats = book.memory_stats();
+        assert_eq!(stats.resting_orders, 6);
+        assert_eq!((stats.level_slots, stats.used_levels), (11, 6));
+        assert_eq!(stats.empty_level_slots(), 5);
+        assert_eq!(stats.owners, 2);
+        assert!(stats.approx_bytes > empty.approx_bytes);
+
+        assert!(empty.approx_bytes < 4_096);
+        let reserved = OrderBook::builder().level_capacity(1_000).build();
+        assert!(reserved.memory_stats().approx_bytes > 1_000 * 2 * size_of::<Price>());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{CancelResult, OrderBook, OrderId, Side};
+
+/// Resting time of orders that rested while no minimum resting time applied
+pub(crate) const UNTIMED: u64 = u64::MAX;
+
+/// What happens to a cancel that arrives before an order rested for the minimum time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarlyCancel {
+    /// Reject the cancel, the order keeps resting
+    Reject,
+
+    /// Apply the cancel once the minimum resting time elapsed
+    Defer,
+}
+
+/// Minimum time an order must rest before it can be canceled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinRestingTime {
+    /// Minimum resting time, in the unit of the clock of the order book
+    pub duration: u64,
+
+    /// Handling of cancels arriving earlier
+    pub action: EarlyCancel,
+}
+
+impl OrderBook {
+    /// Set the minimum time orders must rest before they can be canceled
+    ///
+    /// The rule applies to `cancel` and to reducing the whole quantity of an order,
+    /// using the clock of the order book, and is meant to curb the flicker of resting
+    /// orders. Mass cancels, market-maker protection, self-trade prevention and the
+    /// cancels the order book makes itself, such as the unfilled remainder of algo and
+    /// spread legs, expired orders of a delisted instrument and replayed venue records,
+    /// are not held back. Neither is the replacement of two-sided quotes, whose orders
+    /// can be replaced at any time. Orders resting before the rule was set can be
+    /// canceled at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The minimum resting time, `None` to cancel orders at any time
+    pub fn set_min_resting_time(&mut self, rule: Option<MinRestingTime>) {
+        self.min_rest = rule;
+    }
+
+    /// Check a cancel against the minimum resting time
+    ///
+    /// # Returns
+    ///
+    /// The result of the cancel if it is held back, `None` if it may proceed
+    pub(crate) fn hold_cancel(&mut self, id: OrderId) -> Option<CancelResult> {
+        let rule = self.min_rest?;
+        let (side, idx) = *self.order_loc.get(&id)?;
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        let order = book.price_levels[idx].iter().find(|o| o.id == id)?;
+        if order.entered == UNTIMED {
+            return None;
+        }
+        let allowed = order.entered.saturating_add(rule.duration);
+        if self.clock.now() >= allowed {
+            return None;
+        }
+        Some(match rule.action {
+            EarlyCancel::Reject => CancelResult::TooEarly(allowed),
+            EarlyCancel::Defer => {
+                self.deferred_cancels.insert((allowed, id));
+                CancelResult::Deferred(allowed)
+            }
+        })
+    }
+
+    /// Apply the deferred cancels whose minimum resting time elapsed
+    ///
+    /// Deferred cancels are also applied before every add, execute and cancel, so this
+    /// only needs calling to release them while the order book is otherwise idle.
+    ///
+    /// # Returns
+    ///
+    /// The number of orders canceled
+    pub fn release_deferred_cancels(&mut self) -> usize {
+        if self.deferred_cancels.is_empty() {
+            return 0;
+        }
+        let now = self.clock.now();
+        let mut canceled = 0;
+        while let Some(&(allowed, id)) = self.deferred_cancels.first() {
+            if allowed > now {
+                break;
+            }
+            self.deferred_cancels.pop_first();
+            canceled += self.remove(id).is_some() as usize;
+        }
+        if canceled > 0 {
+            self.notify_bbo();
+        }
+        canceled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Algo, AlgoStrategy, ManualClock};
+
+    #[test]
+    fn test_min_resting_time() {
+        let clock = ManualClock::new(1_000);
+        let mut book = OrderBook::builder().clock(clock.clone()).build();
+        book.set_min_resting_time(Some(MinRestingTime {
+            duration: 500,
+            action: EarlyCancel::Reject,
+        }));
+        let id = book.add(1, Side::Bid, 100, 10).unwrap();
+        clock.set(1_499);
+        assert_eq!(book.cancel(id), CancelResult::TooEarly(1_500));
+        assert_eq!(book.reduce(id, 10), Some(10));
+        clock.set(1_500);
+        assert_eq!(book.cancel(id), CancelResult::Canceled(0));
+
+        book.set_min_resting_time(Some(MinRestingTime {
+            duration: 500,
+            action: EarlyCancel::Defer,
+        }));
+        let id = book.add(1, Side::Bid, 100, 10).unwrap();
+        assert_eq!(book.cancel(id), CancelResult::Deferred(2_000));
+        assert_eq!(book.release_deferred_cancels(), 0);
+        clock.set(2_000);
+        book.add(2, Side::Ask, 105, 1).unwrap();
+        assert_eq!(book.get_total_qty(Side::Bid, 100), 0);
+        assert_eq!(book.cancel(id), CancelResult::NotFound);
+    }
+
+    #[test]
+    fn test_internal_cancels_not_held() {
+        let clock = ManualClock::new(0);
+        let mut book = OrderBook::builder().clock(clock.clone()).build();
+        book.set_min_resting_time(Some(MinRestingTime {
+            duration: 500,
+            action: EarlyCancel::Reject,
+        }));
+        book.add(1, Side::Ask, 100, 5).unwrap();
+        let strategy = AlgoStrategy::Twap {
+            start: 0,
+            end: 0,
+            slices: 1,
+        };
+        let mut algo = Algo::new(2, Side::Bid, 100, 20, strategy);
+        assert_eq!(algo.poll(&mut book).unwrap().remaining, 15);
+        assert_eq!(book.bbo().bid, None);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{OrderBook, OrderQty, OwnerId};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+/// Market-maker protection limits of an owner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MmpConfig {
+    /// Length of the rolling window, in clock units
+    pub window: u64,
+
+    /// Number of fills within the window triggering the protection
+    pub max_fills: u64,
+
+    /// Filled quantity within the window triggering the protection
+    pub max_volume: OrderQty,
+}
+
+/// Report of a triggered market-maker protection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MmpTrigger {
+    /// Owner whose protection triggered
+    pub owner: OwnerId,
+
+    /// Time the protection triggered
+    pub time: u64,
+
+    /// Number of fills within the window
+    pub fills: u64,
+
+    /// Filled quantity within the window
+    pub volume: OrderQty,
+
+    /// Number of resting orders canceled
+    pub canceled: usize,
+}
+
+/// Subscriber to triggered market-maker protections
+pub trait MmpListener: fmt::Debug {
+    /// Called when the protection of an owner triggers
+    ///
+    /// # Arguments
+    ///
+    /// * `trigger` - The triggered protection
+    fn on_mmp_triggered(&mut self, trigger: &MmpTrigger);
+}
+
+/// Forward triggers over a channel, dropping them once the receiver hangs up
+impl MmpListener for Sender<MmpTrigger> {
+    fn on_mmp_triggered(&mut self, trigger: &MmpTrigger) {
+        let _ = self.send(*trigger);
+    }
+}
+
+/// Market-maker protection counters of an owner
+#[derive(Debug)]
+pub(crate) struct MmpState {
+    /// Limits of the owner
+    config: MmpConfig,
+
+    /// Time and quantity of the fills within the window
+    fills: VecDeque<(u64, OrderQty)>,
+
+    /// Total quantity of the fills within the window
+    volume: OrderQty,
+
+    /// Whether the protection triggered and was not reset yet
+    pub(crate) triggered: bool,
+}
+
+impl MmpState {
+    fn new(config: MmpConfig) -> MmpState {
+        MmpState {
+            config,
+            fills: VecDeque::new(),
+            volume: 0,
+            triggered: false,
+        }
+    }
+
+    /// Record a fill of a resting order of the owner
+    ///
+    /// # Returns
+    ///
+    /// The number of fills and the volume within the window if the fill triggered
+    /// the protection
+    pub(crate) fn record(&mut self, now: u64, qty: OrderQty) -> Option<(u64, OrderQty)> {
+        while let Some((time, old)) = self.fills.front() {
+            if now.saturating_sub(*time) < self.config.window {
+                break;
+            }
+            self.volume -= old;
+            self.fills.pop_front();
+        }
+        self.fills.push_back((now, qty));
+        self.volume += qty;
+        let fills = self.fills.len() as u64;
+        let breached = fills >= self.config.max_fills || self.volume >= self.config.max_volume;
+        if self.triggered || !breached {
+            return None;
+        }
+        self.triggered = true;
+        Some((fills, self.volume))
+    }
+}
+
+impl OrderBook {
+    /// Enable market-maker protection for an owner
+    ///
+    /// Fills of the resting orders of the owner are counted over a rolling window. When
+    /// the number of fills or the filled quantity reaches its limit, every resting order
+    /// of the owner is canceled and its new executions and quotes are rejected until the
+    /// protection is reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to protect
+    /// * `config` - The limits of the owner
+    pub fn set_mmp(&mut self, owner: OwnerId, config: MmpConfig) {
+        self.mmp.insert(owner, MmpState::new(config));
+    }
+
+    /// Reset the market-maker protection of an owner, clearing its counters
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to reset the protection of
+    pub fn reset_mmp(&mut self, owner: OwnerId) {
+        if let Some(state) = self.mmp.get_mut(&owner) {
+            *state = MmpState::new(state.config);
+        }
+    }
+
+    /// Check whether the market-maker protection of an owner triggered
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to check
+    ///
+    /// # Returns
+    ///
+    /// Whether the protection triggered and was not reset yet
+    pub fn is_mmp_triggered(&self, owner: OwnerId) -> bool {
+        self.mmp.get(&owner).is_some_and(|state| state.triggered)
+    }
+
+    /// Set the subscriber to triggered market-maker protections
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_mmp_listener<L: MmpListener + 'static>(&mut self, listener: L) {
+        self.mmp_listener = Some(Box::new(listener));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ManualClock, OrderStatus, QuoteStatus, RejectReason, Side};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_mmp_trigger() {
+        let clock = ManualClock::new(0);
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_clock(clock.clone());
+        book.set_mmp_listener(tx);
+        book.set_mmp(
+            1,
+            MmpConfig {
+                window: 100,
+                max_fills: 3,
+                max_volume: 1_000,
+            },
+        );
+        for price in 100..105 {
+            book.add(1, Side::Ask, price, 10).unwrap();
+        }
+        book.add(2, Side::Ask, 104, 10).unwrap();
+
+        book.execute(3, Side::Bid, 100, 10);
+        clock.advance(100);
+        book.execute(3, Side::Bid, 101, 10);
+        assert!(!book.is_mmp_triggered(1));
+
+        let result = book.execute(3, Side::Bid, 104, 40);
+        assert_eq!(result.orders, vec![(102, 10), (103, 10), (104, 10)]);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![MmpTrigger {
+                owner: 1,
+                time: 100,
+                fills: 3,
+                volume: 30,
+                canceled: 1,
+            }]
+        );
+        assert_eq!(book.snapshot().asks, vec![]);
+        assert_eq!(book.snapshot().bids, vec![(104, 10)]);
+
+        let result = book.execute(1, Side::Ask, 110, 10);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::MmpTriggered)
+        );
+        assert_eq!(
+            book.quote(1, 90, 10, 110, 10).status,
+            QuoteStatus::MmpTriggered
+        );
+        assert_eq!(
+            book.add(1, Side::Ask, 110, 10),
+            Err(RejectReason::MmpTriggered)
+        );
+        book.reset_mmp(1);
+        let result = book.execute(1, Side::Ask, 110, 10);
+        assert_eq!(result.status, OrderStatus::Created);
+    }
+}
@@ -0,0 +1,418 @@
+use crate::feed::{FeedAdapter, FeedDiff};
+use crate::{LevelUpdate, OrderQty, Price, Side, Snapshot};
+use std::io;
+use std::mem::size_of;
+use std::net::{SocketAddr, UdpSocket};
+use std::os::unix::io::AsRawFd;
+
+/// Largest UDP payload that fits in a standard Ethernet frame
+pub const MAX_PACKET_SIZE: usize = 1472;
+
+/// Size of an encoded level: side, price and quantity
+const LEVEL_SIZE: usize = 17;
+
+/// Size of an incremental packet header: first sequence number and message count
+const INCREMENTAL_HEADER: usize = 10;
+
+/// Size of a snapshot packet header: last sequence number, fragment index,
+/// fragment count and level count
+const SNAPSHOT_HEADER: usize = 14;
+
+/// A single packet of a snapshot sent on the recovery channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotFragment {
+    /// Sequence number of the last incremental message reflected in the snapshot
+    pub last_seq: u64,
+
+    /// Index of this fragment within the snapshot
+    pub fragment: u16,
+
+    /// Total number of fragments of the snapshot
+    pub fragments: u16,
+
+    /// Levels carried by this fragment, bids before asks
+    pub levels: Vec<LevelUpdate>,
+}
+
+fn encode_level(buf: &mut Vec<u8>, side: Side, price: Price, qty: OrderQty) {
+    buf.push(match side {
+        Side::Bid => 0,
+        Side::Ask => 1,
+    });
+    buf.extend_from_slice(&price.to_le_bytes());
+    buf.extend_from_slice(&qty.to_le_bytes());
+}
+
+fn decode_levels(data: &[u8], count: usize) -> Option<Vec<LevelUpdate>> {
+    if data.len() != count * LEVEL_SIZE {
+        return None;
+    }
+    data.chunks_exact(LEVEL_SIZE)
+        .map(|level| {
+            let side = match level[0] {
+                0 => Side::Bid,
+                1 => Side::Ask,
+                _ => return None,
+            };
+            Some(LevelUpdate {
+                side,
+                price: u64::from_le_bytes(level[1..9].try_into().unwrap()),
+                qty: u64::from_le_bytes(level[9..17].try_into().unwrap()),
+            })
+        })
+        .collect()
+}
+
+/// Set the hop limit of the IPv6 multicast packets sent from a socket, which the
+/// standard library has no setter for
+fn set_multicast_hops_v6(socket: &UdpSocket, hops: u32) -> io::Result<()> {
+    let hops = (libc::c_int::try_from(hops).ok())
+        .filter(|hops| *hops <= 255)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "hop limit above 255"))?;
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MULTICAST_HOPS,
+            &hops as *const libc::c_int as *const libc::c_void,
+            size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Decode a packet received on the incremental channel
+///
+/// # Arguments
+///
+/// * `packet` - The payload of the packet
+///
+/// # Returns
+///
+/// The sequence number of the first update and the updates, `None` if malformed
+pub fn decode_incremental(packet: &[u8]) -> Option<(u64, Vec<LevelUpdate>)> {
+    if packet.len() < INCREMENTAL_HEADER {
+        return None;
+    }
+    let seq = u64::from_le_bytes(packet[0..8].try_into().unwrap());
+    let count = u16::from_le_bytes(packet[8..10].try_into().unwrap()) as usize;
+    Some((seq, decode_levels(&packet[INCREMENTAL_HEADER..], count)?))
+}
+
+/// Decode a packet received on the snapshot/recovery channel
+///
+/// # Arguments
+///
+/// * `packet` - The payload of the packet
+///
+/// # Returns
+///
+/// The snapshot fragment, `None` if malformed
+pub fn decode_snapshot(packet: &[u8]) -> Option<SnapshotFragment> {
+    if packet.len() < SNAPSHOT_HEADER {
+        return None;
+    }
+    let count = u16::from_le_bytes(packet[12..14].try_into().unwrap()) as usize;
+    Some(SnapshotFragment {
+        last_seq: u64::from_le_bytes(packet[0..8].try_into().unwrap()),
+        fragment: u16::from_le_bytes(packet[8..10].try_into().unwrap()),
+        fragments: u16::from_le_bytes(packet[10..12].try_into().unwrap()),
+        levels: decode_levels(&packet[SNAPSHOT_HEADER..], count)?,
+    })
+}
+
+/// Adapter of the incremental channel of a [`MulticastPublisher`], fetching snapshots
+/// with a closure, e.g. by assembling the fragments of the recovery channel
+#[derive(Debug)]
+pub struct MulticastAdapter<F: FnMut() -> io::Result<(u64, Snapshot)>> {
+    /// Source of snapshots, returning the last sequence number reflected and the snapshot
+    fetch: F,
+}
+
+impl<F: FnMut() -> io::Result<(u64, Snapshot)>> MulticastAdapter<F> {
+    /// Create a new multicast adapter
+    ///
+    /// # Arguments
+    ///
+    /// * `fetch` - The source of snapshots
+    pub fn new(fetch: F) -> MulticastAdapter<F> {
+        MulticastAdapter { fetch }
+    }
+}
+
+impl<F: FnMut() -> io::Result<(u64, Snapshot)>> FeedAdapter for MulticastAdapter<F> {
+    type Message = [u8];
+
+    fn fetch_snapshot(&mut self) -> io::Result<(u64, Snapshot)> {
+        (self.fetch)()
+    }
+
+    fn decode(&mut self, packet: &[u8]) -> io::Result<FeedDiff> {
+        let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed packet");
+        let (seq, updates) = decode_incremental(packet).ok_or_else(malformed)?;
+        let end = seq
+            .checked_add(updates.len() as u64)
+            .ok_or_else(malformed)?;
+        Ok(FeedDiff {
+            first_seq: seq,
+            last_seq: end.saturating_sub(1),
+            updates,
+            sequenced_updates: true,
+        })
+    }
+}
+
+/// Disseminates book updates as sequenced UDP multicast packets
+///
+/// Every level update is a message with its own sequence number, starting at 1, packed
+/// into as few packets as possible. Receivers detect gaps from the sequence numbers and
+/// resynchronize from the snapshots periodically sent on the separate recovery channel,
+/// which are tagged with the last sequence number they reflect.
+#[derive(Debug)]
+pub struct MulticastPublisher {
+    /// Socket the packets are sent from
+    socket: UdpSocket,
+
+    /// Destination of the incremental channel
+    incremental: SocketAddr,
+
+    /// Destination of the snapshot/recovery channel
+    recovery: SocketAddr,
+
+    /// Sequence number of the next message
+    next_seq: u64,
+
+    /// Buffer the packets are encoded into
+    buf: Vec<u8>,
+}
+
+impl MulticastPublisher {
+    /// Create a new publisher
+    ///
+    /// # Arguments
+    ///
+    /// * `incremental` - The group address and port of the incremental channel
+    /// * `recovery` - The group address and port of the snapshot/recovery channel
+    /// * `ttl` - The multicast time-to-live of the packets, their hop limit for IPv6
+    ///   groups
+    ///
+    /// # Returns
+    ///
+    /// The publisher, or an error if the socket could not be set up
+    pub fn new(
+        incremental: SocketAddr,
+        recovery: SocketAddr,
+        ttl: u32,
+    ) -> io::Result<MulticastPublisher> {
+        let socket = match incremental {
+            SocketAddr::V4(_) => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.set_multicast_ttl_v4(ttl)?;
+                socket
+            }
+            SocketAddr::V6(_) => {
+                let socket = UdpSocket::bind("[::]:0")?;
+                set_multicast_hops_v6(&socket, ttl)?;
+                socket
+            }
+        };
+        Ok(MulticastPublisher {
+            socket,
+            incremental,
+            recovery,
+            next_seq: 1,
+            buf: Vec::with_capacity(MAX_PACKET_SIZE),
+        })
+    }
+
+    /// Get the sequence number the next update will be sent with
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Publish level updates on the incremental channel
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - The level updates to publish, in order
+    ///
+    /// # Returns
+    ///
+    /// The number of packets sent
+    pub fn publish(&mut self, updates: &[LevelUpdate]) -> io::Result<usize> {
+        let per_packet = (MAX_PACKET_SIZE - INCREMENTAL_HEADER) / LEVEL_SIZE;
+        let mut packets = 0;
+        for chunk in updates.chunks(per_packet) {
+            self.buf.clear();
+            self.buf.extend_from_slice(&self.next_seq.to_le_bytes());
+            self.buf
+                .extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+            for update in chunk {
+                encode_level(&mut self.buf, update.side, update.price, update.qty);
+            }
+            self.socket.send_to(&self.buf, self.incremental)?;
+            self.next_seq += chunk.len() as u64;
+            packets += 1;
+        }
+        Ok(packets)
+    }
+
+    /// Publish the updates turning one snapshot into another on the incremental channel
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The snapshot last published
+    /// * `new` - The current snapshot
+    ///
+    /// # Returns
+    ///
+    /// The number of packets sent
+    pub fn publish_diff(&mut self, old: &Snapshot, new: &Snapshot) -> io::Result<usize> {
+        self.publish(&old.diff(new))
+    }
+
+    /// Publish a full snapshot on the recovery channel
+    ///
+    /// The snapshot must reflect every update published so far on the incremental channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The snapshot to publish
+    ///
+    /// # Returns
+    ///
+    /// The number of packets sent
+    pub fn publish_snapshot(&mut self, snapshot: &Snapshot) -> io::Result<usize> {
+        let per_packet = (MAX_PACKET_SIZE - SNAPSHOT_HEADER) / LEVEL_SIZE;
+        let levels: Vec<_> = snapshot
+            .bids
+            .iter()
+            .map(|level| (Side::Bid, level))
+            .chain(snapshot.asks.iter().map(|level| (Side::Ask, level)))
+            .collect();
+        let fragments = levels.len().div_ceil(per_packet).max(1);
+        if fragments > u16::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "snapshot too large",
+            ));
+        }
+        let last_seq = self.next_seq - 1;
+        for fragment in 0..fragments {
+            let start = fragment * per_packet;
+            let chunk = &levels[start..levels.len().min(start + per_packet)];
+            self.buf.clear();
+            self.buf.extend_from_slice(&last_seq.to_le_bytes());
+            self.buf.extend_from_slice(&(fragment as u16).to_le_bytes());
+            self.buf
+                .extend_from_slice(&(fragments as u16).to_le_bytes());
+            self.buf
+                .extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+            for (side, (price, qty)) in chunk {
+                encode_level(&mut self.buf, *side, *price, *qty);
+            }
+            self.socket.send_to(&self.buf, self.recovery)?;
+        }
+        Ok(fragments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FeedEvent, FeedSync};
+    use std::time::Duration;
+
+    fn receiver() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        socket
+    }
+
+    #[test]
+    fn test_publish_sequenced() {
+        let (incremental, recovery) = (receiver(), receiver());
+        let mut publisher = MulticastPublisher::new(
+            incremental.local_addr().unwrap(),
+            recovery.local_addr().unwrap(),
+            1,
+        )
+        .unwrap();
+
+        let updates: Vec<_> = (0..100)
+            .map(|i| LevelUpdate {
+                side: Side::Bid,
+                price: 1_000 - i,
+                qty: i + 1,
+            })
+            .collect();
+        assert_eq!(publisher.publish(&updates).unwrap(), 2);
+
+        let mut buf = [0u8; MAX_PACKET_SIZE];
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let len = incremental.recv(&mut buf).unwrap();
+            let (seq, packet) = decode_incremental(&buf[..len]).unwrap();
+            assert_eq!(seq, received.len() as u64 + 1);
+            received.extend(packet);
+        }
+        assert_eq!(received, updates);
+
+        let mut snapshot = Snapshot::default();
+        received.iter().for_each(|u| snapshot.apply(u));
+        assert_eq!(publisher.publish_snapshot(&snapshot).unwrap(), 2);
+        let mut levels = Vec::new();
+        for i in 0..2 {
+            let len = recovery.recv(&mut buf).unwrap();
+            let fragment = decode_snapshot(&buf[..len]).unwrap();
+            assert_eq!(fragment.last_seq, 100);
+            assert_eq!((fragment.fragment, fragment.fragments), (i, 2));
+            levels.extend(fragment.levels);
+        }
+        assert_eq!(levels, updates);
+
+        let update = LevelUpdate {
+            side: Side::Ask,
+            price: 1_001,
+            qty: 3,
+        };
+        publisher.publish(&[update]).unwrap();
+        let recovered = snapshot.clone();
+        let mut feed = FeedSync::new(MulticastAdapter::new(move || Ok((100, recovered.clone()))));
+        let len = incremental.recv(&mut buf).unwrap();
+        assert_eq!(feed.on_message(&buf[..len]).unwrap(), FeedEvent::Resynced);
+        assert_eq!(feed.book().asks, vec![(1_001, 3)]);
+        assert_eq!(feed.last_seq(), Some(101));
+
+        let mut packet = buf[..len].to_vec();
+        packet[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut adapter = MulticastAdapter::new(|| Ok((0, Snapshot::default())));
+        let error = adapter.decode(&packet).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_ipv6_hops() {
+        let group: SocketAddr = "[ff02::1]:5000".parse().unwrap();
+        let publisher = MulticastPublisher::new(group, group, 7).unwrap();
+        let mut hops: libc::c_int = 0;
+        let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                publisher.socket.as_raw_fd(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_MULTICAST_HOPS,
+                &mut hops as *mut libc::c_int as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        assert_eq!((result, hops), (0, 7));
+        let error = MulticastPublisher::new(group, group, 256).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Bbo, BboListener, OrderQty, Price, Side};
+use std::sync::{Arc, Mutex};
+
+/// Best price of one side across venues
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NbboSide {
+    /// Best price among the venues
+    pub price: Price,
+
+    /// Total quantity at the best price over the venues quoting it
+    pub qty: OrderQty,
+
+    /// Venues quoting the best price, in increasing order
+    pub venues: Vec<usize>,
+}
+
+/// National best bid and offer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NbboQuote {
+    /// Best bid across venues, `None` if no venue has bids
+    pub bid: Option<NbboSide>,
+
+    /// Best ask across venues, `None` if no venue has asks
+    pub ask: Option<NbboSide>,
+}
+
+/// Consolidates the best bids and offers of several order books trading the same
+/// instrument, each order book being a venue identified by its index
+#[derive(Debug, Clone, Default)]
+pub struct Nbbo {
+    /// Last best bid and offer of each venue
+    venues: Vec<Bbo>,
+}
+
+impl Nbbo {
+    /// Create a new NBBO without venues
+    pub fn new() -> Nbbo {
+        Nbbo::default()
+    }
+
+    /// Apply a change of the best bid and offer of a venue
+    ///
+    /// # Arguments
+    ///
+    /// * `venue` - The index of the venue
+    /// * `bbo` - The new best bid and offer of the venue
+    pub fn update(&mut self, venue: usize, bbo: &Bbo) {
+        if venue >= self.venues.len() {
+            self.venues.resize(venue + 1, Bbo::default());
+        }
+        self.venues[venue] = *bbo;
+    }
+
+    /// Get the best bid and offer of a venue
+    ///
+    /// # Arguments
+    ///
+    /// * `venue` - The index of the venue
+    ///
+    /// # Returns
+    ///
+    /// The last best bid and offer of the venue, empty if never updated
+    pub fn venue(&self, venue: usize) -> Bbo {
+        self.venues.get(venue).copied().unwrap_or_default()
+    }
+
+    /// Get the national best bid and offer
+    ///
+    /// # Returns
+    ///
+    /// The best bid and ask across venues, with the venues contributing to each
+    pub fn quote(&self) -> NbboQuote {
+        NbboQuote {
+            bid: self.best(Side::Bid),
+            ask: self.best(Side::Ask),
+        }
+    }
+
+    /// Get the best price of one side across venues
+    fn best(&self, side: Side) -> Option<NbboSide> {
+        let mut best: Option<NbboSide> = None;
+        for (venue, bbo) in self.venues.iter().enumerate() {
+            let level = match side {
+                Side::Bid => bbo.bid,
+                Side::Ask => bbo.ask,
+            };
+            let Some((price, qty)) = level else { continue };
+            let better = |best: &NbboSide| match side {
+                Side::Bid => price > best.price,
+                Side::Ask => price < best.price,
+            };
+            match best.as_mut() {
+                Some(b) if b.price == price => {
+                    b.qty += qty;
+                    b.venues.push(venue);
+                }
+                Some(b) if !better(b) => {}
+                _ => {
+                    best = Some(NbboSide {
+                        price,
+                        qty,
+                        venues: vec![venue],
+                    })
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Subscriber feeding the changes of the best bid and offer of a venue into a shared NBBO
+///
+/// A listener only sees changes made after it was set, so the NBBO should be updated
+/// with the current best bid and offer of an order book that is not empty.
+#[derive(Debug, Clone)]
+pub struct NbboListener {
+    /// Index of the venue
+    venue: usize,
+
+    /// NBBO to update
+    nbbo: Arc<Mutex<Nbbo>>,
+}
+
+impl NbboListener {
+    /// Create a new listener
+    ///
+    /// # Arguments
+    ///
+    /// * `nbbo` - The NBBO to update
+    /// * `venue` - The index of the venue of the order book listened to
+    pub fn new(nbbo: Arc<Mutex<Nbbo>>, venue: usize) -> NbboListener {
+        NbboListener { venue, nbbo }
+    }
+}
+
+impl BboListener for NbboListener {
+    fn on_bbo(&mut self, _old: &Bbo, new: &Bbo) {
+        if let Ok(mut nbbo) = self.nbbo.lock() {
+            nbbo.update(self.venue, new);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderBook;
+
+    #[test]
+    fn test_nbbo() {
+        let nbbo = Arc::new(Mutex::new(Nbbo::new()));
+        let mut books: Vec<OrderBook> = (0..3).map(|_| OrderBook::new()).collect();
+        for (venue, book) in books.iter_mut().enumerate() {
+            book.set_bbo_listener(NbboListener::new(nbbo.clone(), venue));
+        }
+        books[0].add(1, Side::Bid, 100, 10).unwrap();
+        books[1].add(1, Side::Bid, 101, 5).unwrap();
+        books[2].add(1, Side::Bid, 101, 7).unwrap();
+        books[0].add(1, Side::Ask, 103, 10).unwrap();
+        books[2].add(1, Side::Ask, 104, 10).unwrap();
+
+        let quote = nbbo.lock().unwrap().quote();
+        assert_eq!(
+            quote.bid,
+            Some(NbboSide {
+                price: 101,
+                qty: 12,
+                venues: vec![1, 2],
+            })
+        );
+        assert_eq!(quote.ask.map(|a| (a.price, a.venues)), Some((103, vec![0])));
+
+        books[1].execute(2, Side::Ask, 101, 5);
+        books[0].execute(2, Side::Bid, 103, 10);
+        let quote = nbbo.lock().unwrap().quote();
+        assert_eq!(quote.bid.map(|b| (b.qty, b.venues)), Some((7, vec![2])));
+        assert_eq!(quote.ask.map(|a| (a.price, a.venues)), Some((104, vec![2])));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Execution, OrderQty, OwnerId, Price, Side};
+use std::collections::HashMap;
+
+/// Net position of a single owner
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Position {
+    /// Net quantity held, positive when long and negative when short
+    pub qty: i64,
+
+    /// Average entry price of the open quantity, zero when flat
+    pub avg_price: f64,
+
+    /// Profit and loss realized by closing quantity
+    pub realized_pnl: f64,
+}
+
+impl Position {
+    /// Apply a fill to the position
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side the owner traded on
+    /// * `price` - The price of the fill
+    /// * `qty` - The quantity of the fill
+    fn apply(&mut self, side: Side, price: Price, qty: OrderQty) {
+        let signed = match side {
+            Side::Bid => qty as i64,
+            Side::Ask => -(qty as i64),
+        };
+        let price = price as f64;
+        if self.qty == 0 || self.qty.signum() == signed.signum() {
+            let open = self.qty.unsigned_abs() as f64;
+            self.avg_price = (self.avg_price * open + price * qty as f64) / (open + qty as f64);
+            self.qty += signed;
+            return;
+        }
+        let closed = self.qty.unsigned_abs().min(qty) as f64;
+        self.realized_pnl += closed * (price - self.avg_price) * self.qty.signum() as f64;
+        let before = self.qty;
+        self.qty += signed;
+        if self.qty == 0 {
+            self.avg_price = 0.0;
+        } else if self.qty.signum() != before.signum() {
+            self.avg_price = price;
+        }
+    }
+
+    /// Get the unrealized profit and loss of the open quantity
+    ///
+    /// # Arguments
+    ///
+    /// * `mid` - The price to mark the open quantity at
+    ///
+    /// # Returns
+    ///
+    /// The unrealized profit and loss
+    pub fn unrealized_pnl(&self, mid: f64) -> f64 {
+        self.qty as f64 * (mid - self.avg_price)
+    }
+
+    /// Express the position in another currency
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The value of one unit of the quote currency in the other currency,
+    ///   e.g. `OrderBook::conversion_rate`
+    ///
+    /// # Returns
+    ///
+    /// The position with its average price and realized profit and loss converted
+    pub fn converted(&self, rate: f64) -> Position {
+        Position {
+            qty: self.qty,
+            avg_price: self.avg_price * rate,
+            realized_pnl: self.realized_pnl * rate,
+        }
+    }
+}
+
+/// Tracks the positions of every owner from the executions of an order book
+///
+/// Feed it the trade stream, e.g. from a drop-copy channel, one execution at a time.
+/// Both the maker and the taker side of every execution are accounted for.
+#[derive(Debug, Default)]
+pub struct PositionTracker {
+    /// Map of owner to position
+    positions: HashMap<OwnerId, Position>,
+}
+
+impl PositionTracker {
+    pub fn new() -> PositionTracker {
+        PositionTracker::default()
+    }
+
+    /// Update the positions of the owners involved in an execution
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The execution to account for
+    pub fn on_execution(&mut self, execution: &Execution) {
+        let maker_side = match execution.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        self.positions
+            .entry(execution.taker_owner)
+            .or_default()
+            .apply(execution.side, execution.price, execution.qty);
+        self.positions
+            .entry(execution.maker_owner)
+            .or_default()
+            .apply(maker_side, execution.price, execution.qty);
+    }
+
+    /// Get the position of an owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to get the position for
+    ///
+    /// # Returns
+    ///
+    /// The position, or a flat position if the owner never traded
+    pub fn position(&self, owner: OwnerId) -> Position {
+        self.positions.get(&owner).copied().unwrap_or_default()
+    }
+
+    /// Get the unrealized profit and loss of an owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner to get the unrealized profit and loss for
+    /// * `mid` - The price to mark the open quantity at, usually the current mid price
+    ///
+    /// # Returns
+    ///
+    /// The unrealized profit and loss
+    pub fn unrealized_pnl(&self, owner: OwnerId, mid: f64) -> f64 {
+        self.position(owner).unrealized_pnl(mid)
+    }
+
+    /// Iterate over the positions of every owner that traded
+    pub fn positions(&self) -> impl Iterator<Item = (OwnerId, &Position)> {
+        self.positions.iter().map(|(owner, pos)| (*owner, pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderBook;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_position_tracking() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_drop_copy(tx);
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(1, Side::Ask, 110, 10).unwrap();
+        book.execute(2, Side::Bid, 110, 20);
+        book.add(1, Side::Bid, 120, 15).unwrap();
+        book.execute(2, Side::Ask, 120, 15);
+
+        let mut tracker = PositionTracker::new();
+        rx.try_iter().for_each(|e| tracker.on_execution(&e));
+
+        let pos = tracker.position(2);
+        assert_eq!(pos.qty, 5);
+        assert_eq!(pos.avg_price, 105.0);
+        assert_eq!(pos.realized_pnl, 225.0);
+        assert_eq!(tracker.unrealized_pnl(2, 100.0), -25.0);
+
+        let pos = tracker.position(1);
+        assert_eq!(pos.qty, -5);
+        assert_eq!(pos.realized_pnl, -225.0);
+        assert_eq!(tracker.position(3), Position::default());
+    }
+}
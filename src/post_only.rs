@@ -0,0 +1,99 @@
+use crate::{
+    FillResult, OrderBook, OrderQty, OrderStatus, OwnerId, Price, RejectReason, Side, Tag,
+};
+
+/// What to do with a post-only order that would cross the opposite best price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostOnly {
+    /// Reject the order
+    Reject,
+
+    /// Move the order one tick inside the opposite best price
+    Reprice,
+}
+
+impl OrderBook {
+    /// Add a post-only order, which must not take liquidity
+    ///
+    /// An order at or through the opposite best price is rejected with
+    /// `RejectReason::WouldCross` or, when repricing, placed one tick of the instrument
+    /// inside the opposite best price and reported as `OrderStatus::Repriced`. The order
+    /// then goes through the same checks as executed orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    /// * `tag` - The user payload of the order
+    /// * `on_cross` - What to do if the order would cross
+    ///
+    /// # Returns
+    ///
+    /// The result of the order, with no fills
+    pub fn add_post_only(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        tag: Tag,
+        on_cross: PostOnly,
+    ) -> FillResult {
+        let tick = self.instrument.tick_size.max(1);
+        let inside = match side {
+            Side::Bid => match self.asks.prices.last() {
+                Some(&ask) if price >= ask => Some(ask.checked_sub(tick)),
+                _ => None,
+            },
+            Side::Ask => match self.bids.prices.last() {
+                Some(&bid) if price <= bid => Some(bid.checked_add(tick)),
+                _ => None,
+            },
+        };
+        let price = match (inside, on_cross) {
+            (None, _) => price,
+            (Some(Some(inside)), PostOnly::Reprice) => inside,
+            (Some(_), _) => {
+                let mut result = FillResult::new();
+                result.remaining = qty;
+                result.status = OrderStatus::Rejected(RejectReason::WouldCross);
+                return result;
+            }
+        };
+        let mut result = self.execute_tagged(owner, side, price, qty, tag);
+        if inside.is_some() && result.status == OrderStatus::Created {
+            result.status = OrderStatus::Repriced(price);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_only() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Bid, 98, 10).unwrap();
+
+        let result = book.add_post_only(2, Side::Bid, 102, 5, 0, PostOnly::Reject);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::WouldCross)
+        );
+        assert_eq!(result.id, None);
+        let result = book.add_post_only(2, Side::Bid, 102, 5, 0, PostOnly::Reprice);
+        assert_eq!(result.status, OrderStatus::Repriced(100));
+        assert_eq!(book.order(result.id.unwrap()).unwrap().price, 100);
+        let result = book.add_post_only(2, Side::Ask, 99, 5, 0, PostOnly::Reprice);
+        assert_eq!(result.status, OrderStatus::Repriced(101));
+        assert!(result.orders.is_empty());
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 15);
+        let result = book.add_post_only(2, Side::Ask, 103, 5, 0, PostOnly::Reject);
+        assert_eq!(result.status, OrderStatus::Created);
+    }
+}
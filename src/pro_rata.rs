@@ -0,0 +1,220 @@
+use crate::{Order, OrderBook, OrderQty};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+/// Distribution of the quantity left over once pro-rata shares are rounded down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProRataRemainder {
+    /// One more unit to each order by largest fractional share, ties to the earliest
+    /// order, the rest by time priority
+    #[default]
+    LargestRemainder,
+
+    /// Residual to the order with time priority, then to the following orders
+    TopOrder,
+}
+
+/// Rounding rules of pro-rata matching, which differ between venues
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProRataRules {
+    /// Distribution of the rounding residual
+    pub remainder: ProRataRemainder,
+
+    /// Smallest share allocated to an order, smaller shares are dropped and added to
+    /// the residual
+    pub min_allocation: OrderQty,
+}
+
+/// Allocation of incoming orders among the resting orders of a price level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchingMode {
+    /// Orders are filled in time priority
+    #[default]
+    Fifo,
+
+    /// Orders are filled in proportion to their quantity
+    ProRata(ProRataRules),
+}
+
+/// Allocate a quantity among the orders of a price level in proportion to their
+/// quantity
+///
+/// The allocations always add up to the quantity, capped at the quantity of the level,
+/// and never exceed the quantity of an order.
+///
+/// # Arguments
+///
+/// * `rules` - The rounding rules
+/// * `level` - The orders of the level, in time priority
+/// * `qty` - The quantity to allocate
+/// * `allocations` - The allocation of each order of the level, overwritten
+pub(crate) fn allocate(
+    rules: &ProRataRules,
+    level: &VecDeque<Order>,
+    qty: OrderQty,
+    allocations: &mut Vec<OrderQty>,
+) {
+    allocations.clear();
+    let total: OrderQty = level.iter().map(|order| order.qty).sum();
+    let qty = qty.min(total);
+    if qty == 0 {
+        allocations.resize(level.len(), 0);
+        return;
+    }
+    let scaled = |order: &Order| qty as u128 * order.qty as u128;
+    allocations.extend(level.iter().map(|order| {
+        let share = (scaled(order) / total as u128) as OrderQty;
+        if share < rules.min_allocation {
+            0
+        } else {
+            share
+        }
+    }));
+    let mut residual = qty - allocations.iter().sum::<OrderQty>();
+
+    if rules.remainder == ProRataRemainder::LargestRemainder && residual > 0 {
+        let mut ranked: Vec<_> = (0..level.len())
+            .filter(|&i| allocations[i] < level[i].qty)
+            .filter(|&i| allocations[i] + 1 >= rules.min_allocation)
+            .collect();
+        ranked.sort_by_key(|&i| Reverse(scaled(&level[i]) % total as u128));
+        for i in ranked.into_iter().take(residual as usize) {
+            allocations[i] += 1;
+            residual -= 1;
+        }
+    }
+    for (allocation, order) in allocations.iter_mut().zip(level) {
+        if residual == 0 {
+            break;
+        }
+        let extra = (order.qty - *allocation).min(residual);
+        *allocation += extra;
+        residual -= extra;
+    }
+}
+
+/// Plan a matching pass of an incoming order over the orders of a price level
+///
+/// Under pro-rata matching the quantity is allocated among the orders of the level,
+/// while under FIFO matching orders are filled in turn and there is nothing to plan.
+///
+/// # Arguments
+///
+/// * `mode` - The matching mode
+/// * `level` - The orders of the level, in time priority
+/// * `qty` - The quantity left to fill
+/// * `allocations` - The allocation of each order of the level, overwritten
+pub(crate) fn plan_pass(
+    mode: &MatchingMode,
+    level: &VecDeque<Order>,
+    qty: OrderQty,
+    allocations: &mut Vec<OrderQty>,
+) {
+    if let MatchingMode::ProRata(rules) = mode {
+        allocate(rules, level, qty, allocations);
+    }
+}
+
+/// Get the quantity an order fills when an incoming order reaches it during a pass
+///
+/// # Arguments
+///
+/// * `mode` - The matching mode
+/// * `allocations` - The allocations planned for the pass
+/// * `slot` - The position of the order in the level when the pass was planned
+/// * `order` - The quantity of the order
+/// * `remaining` - The quantity of the incoming order left to fill
+pub(crate) fn pass_fill(
+    mode: &MatchingMode,
+    allocations: &[OrderQty],
+    slot: usize,
+    order: OrderQty,
+    remaining: OrderQty,
+) -> OrderQty {
+    match mode {
+        MatchingMode::Fifo => order.min(remaining),
+        MatchingMode::ProRata(_) => allocations[slot].min(remaining),
+    }
+}
+
+impl OrderBook {
+    /// Set how incoming orders are allocated among the resting orders of a price level
+    ///
+    /// Under pro-rata matching, each crossed level is allocated in proportion to the
+    /// quantity of its orders following the rounding rules; an order canceled while
+    /// matching, e.g. by self-trade prevention, forfeits its allocation, which is
+    /// allocated again among the orders left. The opening auction still allocates in
+    /// time priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The matching mode
+    pub fn set_matching_mode(&mut self, mode: MatchingMode) {
+        self.matching = mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OwnerId, Side, StpPolicy};
+
+    #[test]
+    fn test_pro_rata_rounding() {
+        let rules = |remainder, min_allocation| {
+            MatchingMode::ProRata(ProRataRules {
+                remainder,
+                min_allocation,
+            })
+        };
+        let fills = |mode| {
+            let mut book = OrderBook::builder().matching_mode(mode).build();
+            let ids: Vec<_> = [10, 30, 60, 3]
+                .into_iter()
+                .map(|qty| book.add(1, Side::Ask, 100, qty).unwrap())
+                .collect();
+            let result = book.execute(2, Side::Bid, 100, 11);
+            assert_eq!(result.remaining, 0);
+            let left: Vec<_> = ids
+                .iter()
+                .map(|&id| book.order(id).map_or(0, |order| order.qty))
+                .collect();
+            [10 - left[0], 30 - left[1], 60 - left[2], 3 - left[3]]
+        };
+
+        assert_eq!(fills(MatchingMode::Fifo), [10, 1, 0, 0]);
+        assert_eq!(
+            fills(rules(ProRataRemainder::LargestRemainder, 0)),
+            [1, 3, 7, 0]
+        );
+        assert_eq!(fills(rules(ProRataRemainder::TopOrder, 0)), [2, 3, 6, 0]);
+        assert_eq!(
+            fills(rules(ProRataRemainder::LargestRemainder, 4)),
+            [4, 0, 7, 0]
+        );
+        assert_eq!(fills(rules(ProRataRemainder::TopOrder, 4)), [5, 0, 6, 0]);
+    }
+
+    #[test]
+    fn test_pro_rata_preview() {
+        let rules = ProRataRules {
+            remainder: ProRataRemainder::TopOrder,
+            min_allocation: 2,
+        };
+        let mut book = (OrderBook::builder())
+            .matching_mode(MatchingMode::ProRata(rules))
+            .stp(StpPolicy::CancelResting)
+            .build();
+        let asks = [(100, 7), (100, 12), (100, 1), (101, 5), (101, 9), (102, 3)];
+        for (i, (price, qty)) in asks.into_iter().enumerate() {
+            book.add(1 + (i == 1) as OwnerId, Side::Ask, price, qty)
+                .unwrap();
+        }
+        for qty in [5, 23, 9, 40] {
+            let preview = book.preview(2, Side::Bid, 101, qty);
+            let result = book.execute(2, Side::Bid, 101, qty);
+            assert_eq!(preview.orders, result.orders);
+            assert_eq!(preview.remaining, result.remaining);
+        }
+    }
+}
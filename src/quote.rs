@@ -0,0 +1,397 @@
+use crate::{
+    AuditRecord, FillResult, Incoming, OrderBook, OrderId, OrderQty, OwnerId, Price, RejectReason,
+    Side,
+};
+
+/// What to do with a quote that would cross
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossAction {
+    /// Reject the quote
+    Reject,
+
+    /// Move the crossing side of the quote until it no longer crosses
+    Adjust,
+}
+
+/// Protection applied to every quote against crossing prices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteProtection {
+    /// What to do with a crossing quote
+    pub action: CrossAction,
+
+    /// Whether quotes must not cross the rest of the market either
+    pub post_only: bool,
+}
+
+/// Crossing that caused a quote to be rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteCross {
+    /// The bid was at or above the ask of the same quote
+    SelfCross,
+
+    /// The bid was at or above the best ask, or the ask at or below the best bid
+    MarketCross,
+}
+
+/// Outcome of a quote
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteStatus {
+    /// Quote was placed at the requested prices
+    #[default]
+    Accepted,
+
+    /// Quote was placed at adjusted prices
+    Adjusted {
+        /// Price the bid was placed at
+        bid_price: Price,
+
+        /// Price the ask was placed at
+        ask_price: Price,
+    },
+
+    /// Quote was rejected, the previous quote of the owner was still canceled
+    Rejected(QuoteCross),
+
+    /// Quote was rejected because the owner has a triggered market-maker protection
+    MmpTriggered,
+
+    /// Quote was rejected because a price is not a multiple of the tick size of the
+    /// instrument, the previous quote of the owner was kept
+    InvalidTick,
+
+    /// Quote was rejected because a quantity is not a multiple of the lot size of the
+    /// instrument, the previous quote of the owner was kept
+    InvalidLot,
+}
+
+/// Orders making up the two-sided quote of an owner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteResult {
+    /// Identifier of the resting bid order, `None` if the quote has no bid or it did
+    /// not rest
+    pub bid: Option<OrderId>,
+
+    /// Identifier of the resting ask order, `None` if the quote has no ask or it did
+    /// not rest
+    pub ask: Option<OrderId>,
+
+    /// Outcome of the quote
+    pub status: QuoteStatus,
+}
+
+impl OrderBook {
+    /// Set the protection applied to quotes against crossing prices
+    ///
+    /// Without protection, quotes are placed at the requested prices
+    ///
+    /// # Arguments
+    ///
+    /// * `protection` - The protection to apply
+    pub fn set_quote_protection(&mut self, protection: QuoteProtection) {
+        self.quote_protection = Some(protection);
+    }
+
+    /// Replace the two-sided quote of an owner
+    ///
+    /// The orders of the previous quote of the owner, if still resting, are canceled and
+    /// the new bid and ask are added to the order book as a single operation: subscribers
+    /// only observe the order book before and after the replacement. A side quoted with a
+    /// zero quantity is left empty. Prices and quantities must match the increments of
+    /// the instrument.
+    ///
+    /// With quote protection set, a bid at or above the ask of the same quote is rejected
+    /// or, when adjusting, the ask is moved one tick of the instrument above the bid. Under
+    /// post-only, a bid at or above the best ask of the rest of the market is rejected or
+    /// moved one tick below it, and likewise for an ask against the best bid.
+    ///
+    /// Each side is then matched like an executed order, bid first: a side crossing the
+    /// market trades, subject to the credit check, hooks and self-trade prevention, and
+    /// only its remainder rests. A side refused or fully filled is left without an order.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the quote
+    /// * `bid_price` - The price of the bid
+    /// * `bid_qty` - The quantity of the bid
+    /// * `ask_price` - The price of the ask
+    /// * `ask_qty` - The quantity of the ask
+    ///
+    /// # Returns
+    ///
+    /// The identifiers of the new bid and ask orders
+    pub fn quote(
+        &mut self,
+        owner: OwnerId,
+        bid_price: Price,
+        bid_qty: OrderQty,
+        ask_price: Price,
+        ask_qty: OrderQty,
+    ) -> QuoteResult {
+        if self.is_mmp_triggered(owner) {
+            return QuoteResult {
+                status: QuoteStatus::MmpTriggered,
+                ..QuoteResult::default()
+            };
+        }
+        let sides = [(bid_price, bid_qty), (ask_price, ask_qty)];
+        let invalid = (sides.into_iter())
+            .filter(|&(_, qty)| qty > 0)
+            .find_map(|(price, qty)| self.instrument.check(price, qty).err());
+        if let Some(reason) = invalid {
+            let status = match reason {
+                RejectReason::InvalidLot => QuoteStatus::InvalidLot,
+                _ => QuoteStatus::InvalidTick,
+            };
+            return QuoteResult {
+                status,
+                ..QuoteResult::default()
+            };
+        }
+        self.record(AuditRecord::Quote {
+            owner,
+            bid_price,
+            bid_qty,
+            ask_price,
+            ask_qty,
+        });
+        if let Some(old) = self.quotes.remove(&owner) {
+            for id in [old.bid, old.ask].into_iter().flatten() {
+                self.remove(id);
+            }
+        }
+
+        let (bid_price, ask_price, status) =
+            match self.protect_quote(bid_price, bid_qty, ask_price, ask_qty) {
+                Ok(adjusted) => adjusted,
+                Err(cross) => {
+                    let quote = QuoteResult {
+                        status: QuoteStatus::Rejected(cross),
+                        ..QuoteResult::default()
+                    };
+                    self.notify_bbo();
+                    return quote;
+                }
+            };
+        let mut result = FillResult::new();
+        let mut place = |side, price, qty| {
+            if qty == 0 {
+                return None;
+            }
+            let order = Incoming {
+                id: self.next_id(),
+                owner,
+                side,
+                price,
+                qty,
+                tag: 0,
+            };
+            self.match_incoming(order, &mut result);
+            result.id
+        };
+        let quote = QuoteResult {
+            bid: place(Side::Bid, bid_price, bid_qty),
+            ask: place(Side::Ask, ask_price, ask_qty),
+            status,
+        };
+        self.quotes.insert(owner, quote);
+        self.notify_bbo();
+        self.run_stops();
+        self.enforce_reduce_only();
+        quote
+    }
+
+    /// Apply the quote protection to the prices of a quote
+    ///
+    /// # Returns
+    ///
+    /// The prices to place the quote at and the status reporting any adjustment, or the
+    /// crossing that rejects the quote
+    fn protect_quote(
+        &self,
+        mut bid_price: Price,
+        bid_qty: OrderQty,
+        mut ask_price: Price,
+        ask_qty: OrderQty,
+    ) -> Result<(Price, Price, QuoteStatus), QuoteCross> {
+        let protection = match self.quote_protection {
+            Some(protection) => protection,
+            None => return Ok((bid_price, ask_price, QuoteStatus::Accepted)),
+        };
+        let (requested_bid, requested_ask) = (bid_price, ask_price);
+        let adjust = protection.action == CrossAction::Adjust;
+        let tick = self.instrument.tick_size.max(1);
+
+        if bid_qty > 0 && ask_qty > 0 && bid_price >= ask_price {
+            if !adjust {
+                return Err(QuoteCross::SelfCross);
+            }
+            ask_price = bid_price.checked_add(tick).ok_or(QuoteCross::SelfCross)?;
+        }
+        if protection.post_only {
+            let bbo = self.bbo();
+            if let Some((best_ask, _)) = bbo.ask {
+                if bid_qty > 0 && bid_price >= best_ask {
+                    if !adjust {
+                        return Err(QuoteCross::MarketCross);
+                    }
+                    bid_price = best_ask.checked_sub(tick).ok_or(QuoteCross::MarketCross)?;
+                }
+            }
+            if let Some((best_bid, _)) = bbo.bid {
+                if ask_qty > 0 && ask_price <= best_bid {
+                    if !adjust {
+                        return Err(QuoteCross::MarketCross);
+                    }
+                    ask_price = best_bid.checked_add(tick).ok_or(QuoteCross::MarketCross)?;
+                }
+            }
+        }
+
+        let status = match (bid_price, ask_price) == (requested_bid, requested_ask) {
+            true => QuoteStatus::Accepted,
+            false => QuoteStatus::Adjusted {
+                bid_price,
+                ask_price,
+            },
+        };
+        Ok((bid_price, ask_price, status))
+    }
+
+    /// Get the current quote of an owner
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the quote
+    ///
+    /// # Returns
+    ///
+    /// The identifiers of the orders of the last quote, which may have been filled since
+    pub fn get_quote(&self, owner: OwnerId) -> Option<QuoteResult> {
+        self.quotes.get(&owner).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bbo, CancelResult, Instrument};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_quote_replace() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_bbo_listener(tx);
+
+        let first = book.quote(1, 99, 10, 101, 10);
+        let second = book.quote(1, 100, 5, 102, 0);
+        assert_eq!(book.get_quote(1), Some(second));
+        assert_eq!(second.ask, None);
+        assert_eq!(book.cancel(first.bid.unwrap()), CancelResult::NotFound);
+        assert_eq!(book.cancel(first.ask.unwrap()), CancelResult::NotFound);
+        assert_eq!(book.order(second.bid.unwrap()).unwrap().price, 100);
+
+        let changes: Vec<_> = rx.try_iter().map(|(_, new)| new).collect();
+        assert_eq!(
+            changes,
+            vec![
+                Bbo {
+                    bid: Some((99, 10)),
+                    ask: Some((101, 10)),
+                    last: None,
+                },
+                Bbo {
+                    bid: Some((100, 5)),
+                    ask: None,
+                    last: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quote_protection() {
+        let mut book = OrderBook::new();
+        book.add(2, Side::Bid, 95, 10).unwrap();
+        book.add(2, Side::Ask, 105, 10).unwrap();
+        book.set_quote_protection(QuoteProtection {
+            action: CrossAction::Adjust,
+            post_only: true,
+        });
+
+        let quote = book.quote(1, 102, 10, 101, 10);
+        assert_eq!(
+            quote.status,
+            QuoteStatus::Adjusted {
+                bid_price: 102,
+                ask_price: 103,
+            }
+        );
+        let quote = book.quote(1, 110, 10, 90, 10);
+        assert_eq!(
+            quote.status,
+            QuoteStatus::Adjusted {
+                bid_price: 104,
+                ask_price: 111,
+            }
+        );
+        assert_eq!(book.order(quote.bid.unwrap()).unwrap().price, 104);
+
+        book.set_quote_protection(QuoteProtection {
+            action: CrossAction::Reject,
+            post_only: true,
+        });
+        let quote = book.quote(1, 100, 10, 95, 10);
+        assert_eq!(quote.status, QuoteStatus::Rejected(QuoteCross::SelfCross));
+        let quote = book.quote(1, 100, 10, 95, 0);
+        assert_eq!(quote.status, QuoteStatus::Accepted);
+        let quote = book.quote(1, 0, 0, 95, 10);
+        assert_eq!(quote.status, QuoteStatus::Rejected(QuoteCross::MarketCross));
+        assert_eq!(quote.ask, None);
+        assert_eq!(book.bbo().bid, Some((95, 10)));
+    }
+
+    #[test]
+    fn test_quote_matching() {
+        let instrument = Instrument {
+            tick_size: 5,
+            ..Instrument::default()
+        };
+        let mut book = OrderBook::builder().instrument(instrument).build();
+        book.add(2, Side::Ask, 100, 4).unwrap();
+
+        let quote = book.quote(1, 100, 10, 110, 10);
+        assert_eq!(quote.status, QuoteStatus::Accepted);
+        assert_eq!(book.order(quote.bid.unwrap()).unwrap().qty, 6);
+        assert_eq!(book.bbo().bid, Some((100, 6)));
+        assert_eq!(book.bbo().ask, Some((110, 10)));
+        assert_eq!(book.last_trade().map(|t| (t.price, t.qty)), Some((100, 4)));
+
+        let rejected = book.quote(1, 101, 10, 110, 10);
+        assert_eq!(rejected.status, QuoteStatus::InvalidTick);
+        assert_eq!(book.get_quote(1), Some(quote));
+        assert_eq!(book.bbo().bid, Some((100, 6)));
+
+        book.add(2, Side::Ask, 115, 5).unwrap();
+        let quote = book.quote(1, 115, 5, 120, 10);
+        assert_eq!(quote.bid, None);
+        assert_eq!(book.bbo().bid, None);
+        assert_eq!(book.bbo().ask, Some((120, 10)));
+
+        book.set_quote_protection(QuoteProtection {
+            action: CrossAction::Adjust,
+            post_only: true,
+        });
+        book.add(2, Side::Ask, 120, 5).unwrap();
+        let quote = book.quote(1, 125, 5, 125, 5);
+        assert_eq!(
+            quote.status,
+            QuoteStatus::Adjusted {
+                bid_price: 115,
+                ask_price: 130,
+            }
+        );
+        assert_eq!(book.order(quote.bid.unwrap()).unwrap().price, 115);
+        assert_eq!(book.order(quote.ask.unwrap()).unwrap().price, 130);
+    }
+}
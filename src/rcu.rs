@@ -0,0 +1,167 @@
+use crate::{OrderBook, Snapshot};
+use std::cell::UnsafeCell;
+use std::hint;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Depth of an order book published for concurrent readers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepthView {
+    /// Number of views published before this one
+    pub version: u64,
+
+    /// Best levels of the order book when the view was published
+    pub snapshot: Snapshot,
+}
+
+/// Double buffer of views, shared between the publisher and the readers
+#[derive(Debug, Default)]
+struct Slots {
+    /// Index of the buffer holding the current view
+    current: AtomicUsize,
+
+    /// Buffers, only written by the publisher while no reader is in them
+    views: [UnsafeCell<Arc<DepthView>>; 2],
+
+    /// Number of readers cloning the view of each buffer
+    readers: [AtomicUsize; 2],
+}
+
+// A buffer is only written by the single publisher once its readers left, and readers
+// only enter the current buffer, which the publisher never writes
+unsafe impl Send for Slots {}
+unsafe impl Sync for Slots {}
+
+/// Slots holding the current view, shared between the publisher and the readers
+type Slot = Arc<Slots>;
+
+/// Writer side of published depth views, kept by the thread running the order book
+///
+/// Each publication builds a new immutable view and swaps it in, read-copy-update
+/// style: readers holding an older view keep reading it undisturbed and it is freed
+/// once the last of them drops it. Views are double buffered behind an atomic index:
+/// the publisher fills the spare buffer and flips the index, readers never wait and
+/// the publisher only waits for readers still cloning the pointer of the spare buffer.
+#[derive(Debug)]
+pub struct DepthPublisher {
+    /// Shared slots of the current view
+    slot: Slot,
+
+    /// Maximum number of levels per side in a view
+    levels: usize,
+
+    /// Version of the next view
+    version: u64,
+}
+
+/// Reader side of published depth views, cheap to clone and send to other threads
+#[derive(Debug, Clone)]
+pub struct DepthReader {
+    /// Shared slots of the current view
+    slot: Slot,
+}
+
+/// Create a publisher of depth views and a reader of them
+///
+/// # Arguments
+///
+/// * `levels` - The maximum number of levels per side in a view
+///
+/// # Returns
+///
+/// The publisher and a reader, which starts with an empty view
+pub fn depth_views(levels: usize) -> (DepthPublisher, DepthReader) {
+    let slot: Slot = Arc::default();
+    let publisher = DepthPublisher {
+        slot: slot.clone(),
+        levels,
+        version: 1,
+    };
+    (publisher, DepthReader { slot })
+}
+
+impl DepthPublisher {
+    /// Publish the current depth of an order book, typically after each command batch
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to publish the depth of
+    pub fn publish(&mut self, book: &OrderBook) {
+        let view = Arc::new(DepthView {
+            version: self.version,
+            snapshot: book.depth(self.levels),
+        });
+        self.version += 1;
+        let slots = &*self.slot;
+        let spare = 1 - slots.current.load(Ordering::SeqCst);
+        while slots.readers[spare].load(Ordering::SeqCst) != 0 {
+            hint::spin_loop();
+        }
+        // No reader is in the spare buffer, and later ones leave it without reading
+        // until the index flips to it
+        let old = std::mem::replace(unsafe { &mut *slots.views[spare].get() }, view);
+        slots.current.store(spare, Ordering::SeqCst);
+        // The old view is freed, if no reader holds it, after the index flipped
+        drop(old);
+    }
+}
+
+impl DepthReader {
+    /// Get the last published view
+    ///
+    /// # Returns
+    ///
+    /// The view, consistent as of the publication and valid for as long as it is held
+    pub fn load(&self) -> Arc<DepthView> {
+        let slots = &*self.slot;
+        loop {
+            let current = slots.current.load(Ordering::SeqCst);
+            slots.readers[current].fetch_add(1, Ordering::SeqCst);
+            // The buffer may have become the spare one before the reader entered it
+            let view = (slots.current.load(Ordering::SeqCst) == current)
+                .then(|| unsafe { (*slots.views[current].get()).clone() });
+            slots.readers[current].fetch_sub(1, Ordering::SeqCst);
+            if let Some(view) = view {
+                return view;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+    use std::thread;
+
+    #[test]
+    fn test_depth_views() {
+        let (mut publisher, reader) = depth_views(2);
+        let mut book = OrderBook::new();
+        assert_eq!(reader.load().version, 0);
+
+        let handle = {
+            let reader = reader.clone();
+            thread::spawn(move || loop {
+                let view = reader.load();
+                let bids = &view.snapshot.bids;
+                assert!(bids.windows(2).all(|w| w[0].0 > w[1].0));
+                assert!(bids.iter().all(|(price, qty)| *qty == *price));
+                if view.version == 100 {
+                    break;
+                }
+            })
+        };
+        for price in 1..=100 {
+            book.add(1, Side::Bid, price, price).unwrap();
+            publisher.publish(&book);
+        }
+        handle.join().unwrap();
+
+        let held = reader.load();
+        book.execute(2, Side::Ask, 100, 100);
+        publisher.publish(&book);
+        assert_eq!(held.snapshot.bids, vec![(100, 100), (99, 99)]);
+        assert_eq!(reader.load().snapshot.bids, vec![(99, 99), (98, 98)]);
+    }
+}
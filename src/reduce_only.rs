@@ -0,0 +1,164 @@
+use crate::{
+    FillResult, OrderBook, OrderQty, OrderStatus, OwnerId, Price, RejectReason, Side, Tag,
+};
+
+impl OrderBook {
+    /// Track the position of every owner from the executions of the order book
+    ///
+    /// Positions are tracked from then on, so this should be called before trading
+    /// starts. Order books with a credit check already track positions.
+    pub fn track_positions(&mut self) {
+        self.positions.get_or_insert_with(Default::default);
+    }
+
+    /// Get the quantity an order of an owner can trade without increasing its exposure
+    fn reducible(&self, owner: OwnerId, side: Side) -> Option<OrderQty> {
+        let qty = self.positions.as_ref()?.position(owner).qty;
+        Some(match side {
+            Side::Bid => (-qty).max(0) as OrderQty,
+            Side::Ask => qty.max(0) as OrderQty,
+        })
+    }
+
+    /// Get the quantity of the resting reduce-only orders of an owner on a side
+    fn reduce_only_qty(&self, owner: OwnerId, side: Side) -> OrderQty {
+        (self.reduce_only.iter())
+            .filter_map(|&id| self.order(id))
+            .filter(|order| order.owner == owner && order.side == side)
+            .map(|order| order.qty)
+            .sum()
+    }
+
+    /// Execute a reduce-only limit order, which may only reduce the position of its owner
+    ///
+    /// The quantity of the order is capped at the opposite exposure of the owner left
+    /// over by its resting reduce-only orders on the same side, and an owner without
+    /// such exposure sees the order rejected with `RejectReason::ReduceOnly`. While they
+    /// rest, the reduce-only orders of an owner are trimmed, the ones furthest from
+    /// execution first, whenever their total exceeds the position of the owner, and
+    /// canceled once the position is flat or flipped, so together they can never open a
+    /// position. Positions must be tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `price` - The limit price of the order
+    /// * `qty` - The quantity of the order
+    /// * `tag` - The user payload of the order
+    ///
+    /// # Returns
+    ///
+    /// The result of the execution, whose remaining quantity reflects any cap
+    pub fn execute_reduce_only(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        price: Price,
+        qty: OrderQty,
+        tag: Tag,
+    ) -> FillResult {
+        let cap = (self.reducible(owner, side).unwrap_or(0))
+            .saturating_sub(self.reduce_only_qty(owner, side));
+        if cap == 0 {
+            let mut result = FillResult::new();
+            result.remaining = qty;
+            result.status = OrderStatus::Rejected(RejectReason::ReduceOnly);
+            return result;
+        }
+        let result = self.execute_tagged(owner, side, price, qty.min(cap), tag);
+        if let Some(id) = result.id {
+            self.reduce_only.insert(id);
+        }
+        result
+    }
+
+    /// Trim or cancel the resting reduce-only orders of each owner whose total exceeds
+    /// the exposure of the owner, keeping the orders closest to execution
+    pub(crate) fn enforce_reduce_only(&mut self) {
+        if self.reduce_only.is_empty() {
+            return;
+        }
+        let ids: Vec<_> = self.reduce_only.iter().copied().collect();
+        let mut orders = Vec::new();
+        for id in ids {
+            let Some(order) = self.order(id) else {
+                self.reduce_only.remove(&id);
+                continue;
+            };
+            let (place, _) = self.queue_position(id).unwrap_or_default();
+            let (slot, rank) = match order.side {
+                Side::Bid => (0, Price::MAX - order.price),
+                Side::Ask => (1, order.price),
+            };
+            orders.push(((order.owner, slot, rank, place), order));
+        }
+        orders.sort_unstable_by_key(|&(key, _)| key);
+
+        let mut changed = false;
+        let mut budget = None;
+        for ((owner, slot, ..), order) in orders {
+            let left = match budget {
+                Some((key, ref mut left)) if key == (owner, slot) => left,
+                _ => {
+                    let cap = self.reducible(owner, order.side).unwrap_or(0);
+                    &mut budget.insert(((owner, slot), cap)).1
+                }
+            };
+            let keep = order.qty.min(*left);
+            *left -= keep;
+            if keep == 0 {
+                self.remove(order.id);
+                self.reduce_only.remove(&order.id);
+                changed = true;
+            } else if keep < order.qty {
+                self.reduce(order.id, order.qty - keep);
+            }
+        }
+        if changed {
+            self.notify_bbo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduce_only() {
+        let mut book = OrderBook::builder().track_positions().build();
+        let result = book.execute_reduce_only(1, Side::Ask, 100, 10, 0);
+        assert_eq!(
+            result.status,
+            OrderStatus::Rejected(RejectReason::ReduceOnly)
+        );
+
+        book.add(2, Side::Ask, 100, 30).unwrap();
+        book.execute(1, Side::Bid, 100, 30);
+        let first = book.execute_reduce_only(1, Side::Ask, 110, 20, 0);
+        assert_eq!(first.remaining, 20);
+        let second = book.execute_reduce_only(1, Side::Ask, 111, 50, 0);
+        assert_eq!(second.remaining, 10);
+        let third = book.execute_reduce_only(1, Side::Ask, 112, 10, 0);
+        assert_eq!(
+            third.status,
+            OrderStatus::Rejected(RejectReason::ReduceOnly)
+        );
+        let (first, second) = (first.id.unwrap(), second.id.unwrap());
+
+        book.add(3, Side::Bid, 90, 10).unwrap();
+        book.execute(1, Side::Ask, 90, 10);
+        assert_eq!(book.order(first).unwrap().qty, 20);
+        assert!(book.order(second).is_none());
+        assert_eq!(
+            book.amend(first, 110, 25),
+            crate::AmendResult::Amended(false)
+        );
+        assert_eq!(book.order(first).unwrap().qty, 20);
+
+        book.execute(4, Side::Bid, 111, 30);
+        assert!(book.order(first).is_none());
+        assert_eq!(book.positions().unwrap().position(1).qty, 0);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{HalfBook, OrderBook, OrderQty, Price};
+
+/// Shape of the depth of one side of the order book
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SideShape {
+    /// Quantity of each level, best price first
+    pub depth: Vec<OrderQty>,
+
+    /// Quantity of each level relative to the best level, best price first, which
+    /// shows how fast depth decays away from the touch
+    pub decay: Vec<f64>,
+
+    /// Total quantity of the levels
+    pub total_qty: OrderQty,
+
+    /// Share of the total quantity resting at the best level
+    pub top_share: f64,
+
+    /// Herfindahl index of the quantities of the levels, from the inverse of the
+    /// number of levels when liquidity is spread evenly to one when it all rests at a
+    /// single level
+    pub concentration: f64,
+
+    /// Number of empty ticks between consecutive levels, best price first, zero for
+    /// levels less than a tick apart
+    pub gaps: Vec<Price>,
+
+    /// Largest number of empty ticks between consecutive levels
+    pub max_gap: Price,
+}
+
+/// Shape features of the depth of an order book, for research and monitoring
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookShape {
+    /// Shape of the bid side
+    pub bids: SideShape,
+
+    /// Shape of the ask side
+    pub asks: SideShape,
+}
+
+impl HalfBook {
+    /// Compute the shape of the best levels in a single pass over them
+    fn shape(&self, levels: usize, tick: Price) -> SideShape {
+        let mut shape = SideShape::default();
+        let mut squares = 0.0;
+        let mut previous: Option<Price> = None;
+        for pos in (0..self.prices.len()).rev().take(levels) {
+            let (price, qty) = (self.prices[pos], self.qtys[pos]);
+            let best = *shape.depth.first().unwrap_or(&qty);
+            shape.depth.push(qty);
+            shape.decay.push(qty as f64 / best as f64);
+            shape.total_qty += qty;
+            squares += (qty as f64).powi(2);
+            if let Some(previous) = previous {
+                let gap = (previous.abs_diff(price) / tick).saturating_sub(1);
+                shape.gaps.push(gap);
+                shape.max_gap = shape.max_gap.max(gap);
+            }
+            previous = Some(price);
+        }
+        if shape.total_qty > 0 {
+            let total = shape.total_qty as f64;
+            shape.top_share = shape.depth[0] as f64 / total;
+            shape.concentration = squares / (total * total);
+        }
+        shape
+    }
+}
+
+impl OrderBook {
+    /// Compute the shape features of the best levels of both sides
+    ///
+    /// Gaps are counted in ticks of the instrument.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The maximum number of levels of each side
+    ///
+    /// # Returns
+    ///
+    /// The shape of the order book
+    pub fn book_shape(&self, levels: usize) -> BookShape {
+        let tick = self.instrument.tick_size.max(1);
+        BookShape {
+            bids: self.bids.shape(levels, tick),
+            asks: self.asks.shape(levels, tick),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_book_shape() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Bid, 100, 40).unwrap();
+        book.add(1, Side::Bid, 99, 20).unwrap();
+        book.add(1, Side::Bid, 96, 20).unwrap();
+        book.add(1, Side::Bid, 90, 20).unwrap();
+        book.add(1, Side::Ask, 101, 10).unwrap();
+
+        let shape = book.book_shape(3);
+        assert_eq!(shape.bids.depth, vec![40, 20, 20]);
+        assert_eq!(shape.bids.decay, vec![1.0, 0.5, 0.5]);
+        assert_eq!(shape.bids.total_qty, 80);
+        assert_eq!(shape.bids.top_share, 0.5);
+        assert_eq!(shape.bids.concentration, 0.375);
+        assert_eq!(shape.bids.gaps, vec![0, 2]);
+        assert_eq!(shape.bids.max_gap, 2);
+        assert_eq!(shape.asks.concentration, 1.0);
+        assert!(shape.asks.gaps.is_empty());
+        assert_eq!(OrderBook::new().book_shape(5), BookShape::default());
+        assert_eq!(book.bids.shape(3, 5).gaps, vec![0, 0]);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{OrderQty, Price, Snapshot};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+
+/// Magic word at the start of every snapshot region
+const MAGIC: u64 = u64::from_le_bytes(*b"OBSHM001");
+
+/// Number of header words before the level data
+const HEADER_WORDS: usize = 5;
+
+const MAGIC_WORD: usize = 0;
+const SEQ_WORD: usize = 1;
+const CAPACITY_WORD: usize = 2;
+const BIDS_WORD: usize = 3;
+const ASKS_WORD: usize = 4;
+
+/// Get the number of words of a region holding a number of levels per side
+///
+/// # Returns
+///
+/// The number of words, `None` if it overflows
+fn region_words(capacity: usize) -> Option<usize> {
+    capacity.checked_mul(4)?.checked_add(HEADER_WORDS)
+}
+
+/// A shared mapping of a file viewed as an array of atomic words
+#[derive(Debug)]
+struct Region {
+    /// Start of the mapping
+    ptr: *mut AtomicU64,
+
+    /// Length of the mapping in words
+    words: usize,
+
+    /// File backing the mapping
+    _file: File,
+}
+
+// The mapping is only ever accessed through atomics
+unsafe impl Send for Region {}
+unsafe impl Sync for Region {}
+
+impl Region {
+    fn map(file: File, words: usize, writable: bool) -> io::Result<Region> {
+        let prot = match writable {
+            true => libc::PROT_READ | libc::PROT_WRITE,
+            false => libc::PROT_READ,
+        };
+        let len = words
+            .checked_mul(8)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "region too large"))?;
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                prot,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Region {
+            ptr: ptr as *mut AtomicU64,
+            words,
+            _file: file,
+        })
+    }
+
+    fn word(&self, idx: usize) -> &AtomicU64 {
+        assert!(idx < self.words);
+        unsafe { &*self.ptr.add(idx) }
+    }
+}
+
+impl Drop for Region {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.words * 8);
+        }
+    }
+}
+
+/// Publishes the latest depth snapshot into a shared memory region
+///
+/// The region is a file (typically under `/dev/shm`) mapped by both the publisher and
+/// any number of [`ShmReader`]s, guarded by a seqlock: the sequence word is odd while a
+/// write is in progress, so readers never block the publisher and retry on a torn read.
+#[derive(Debug)]
+pub struct ShmPublisher {
+    /// Mapped snapshot region
+    region: Region,
+
+    /// Maximum number of levels published per side
+    capacity: usize,
+}
+
+impl ShmPublisher {
+    /// Create the snapshot region, replacing any existing file at `path`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file backing the region
+    /// * `capacity` - The maximum number of levels published per side
+    ///
+    /// # Returns
+    ///
+    /// The publisher, or an error if the region could not be created
+    pub fn create<P: AsRef<Path>>(path: P, capacity: usize) -> io::Result<ShmPublisher> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "capacity too large");
+        let words = region_words(capacity).ok_or_else(too_large)?;
+        let len = words.checked_mul(8).ok_or_else(too_large)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+        let region = Region::map(file, words, true)?;
+        region
+            .word(CAPACITY_WORD)
+            .store(capacity as u64, Ordering::Relaxed);
+        region.word(MAGIC_WORD).store(MAGIC, Ordering::Release);
+        Ok(ShmPublisher { region, capacity })
+    }
+
+    /// Publish a snapshot, truncating each side to the region capacity
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The snapshot to publish
+    pub fn publish(&mut self, snapshot: &Snapshot) {
+        let seq = self.region.word(SEQ_WORD);
+        let start = seq.load(Ordering::Relaxed);
+        seq.store(start.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let bids = &snapshot.bids[..snapshot.bids.len().min(self.capacity)];
+        let asks = &snapshot.asks[..snapshot.asks.len().min(self.capacity)];
+        self.region
+            .word(BIDS_WORD)
+            .store(bids.len() as u64, Ordering::Relaxed);
+        self.region
+            .word(ASKS_WORD)
+            .store(asks.len() as u64, Ordering::Relaxed);
+        let asks_start = HEADER_WORDS + 2 * self.capacity;
+        for (base, levels) in [(HEADER_WORDS, bids), (asks_start, asks)] {
+            for (i, (price, qty)) in levels.iter().enumerate() {
+                self.region
+                    .word(base + 2 * i)
+                    .store(*price, Ordering::Relaxed);
+                self.region
+                    .word(base + 2 * i + 1)
+                    .store(*qty, Ordering::Relaxed);
+            }
+        }
+
+        seq.store(start.wrapping_add(2), Ordering::Release);
+    }
+}
+
+/// Reads consistent depth snapshots published by a [`ShmPublisher`]
+#[derive(Debug)]
+pub struct ShmReader {
+    /// Mapped snapshot region
+    region: Region,
+
+    /// Maximum number of levels published per side
+    capacity: usize,
+}
+
+impl ShmReader {
+    /// Open an existing snapshot region
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file backing the region
+    ///
+    /// # Returns
+    ///
+    /// The reader, or an error if the file is not a snapshot region
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<ShmReader> {
+        let file = File::open(path)?;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a snapshot region");
+        let words = usize::try_from(file.metadata()?.len() / 8).map_err(|_| invalid())?;
+        if words < HEADER_WORDS {
+            return Err(invalid());
+        }
+        let region = Region::map(file, words, false)?;
+        if region.word(MAGIC_WORD).load(Ordering::Acquire) != MAGIC {
+            return Err(invalid());
+        }
+        let capacity = region.word(CAPACITY_WORD).load(Ordering::Relaxed) as usize;
+        if region_words(capacity).is_none_or(|needed| words < needed) {
+            return Err(invalid());
+        }
+        Ok(ShmReader { region, capacity })
+    }
+
+    /// Attempt a single read of the latest snapshot
+    ///
+    /// # Returns
+    ///
+    /// The version and snapshot, or `None` if a write was in progress
+    pub fn try_read(&self) -> Option<(u64, Snapshot)> {
+        let seq = self.region.word(SEQ_WORD);
+        let start = seq.load(Ordering::Acquire);
+        if start % 2 == 1 {
+            return None;
+        }
+
+        let read_levels = |base: usize, len: u64| -> Vec<(Price, OrderQty)> {
+            (0..(len as usize).min(self.capacity))
+                .map(|i| {
+                    (
+                        self.region.word(base + 2 * i).load(Ordering::Relaxed),
+                        self.region.word(base + 2 * i + 1).load(Ordering::Relaxed),
+                    )
+                })
+                .collect()
+        };
+        let bids_len = self.region.word(BIDS_WORD).load(Ordering::Relaxed);
+        let asks_len = self.region.word(ASKS_WORD).load(Ordering::Relaxed);
+        let snapshot = Snapshot {
+            bids: read_levels(HEADER_WORDS, bids_len),
+            asks: read_levels(HEADER_WORDS + 2 * self.capacity, asks_len),
+        };
+
+        fence(Ordering::Acquire);
+        match seq.load(Ordering::Relaxed) == start {
+            true => Some((start / 2, snapshot)),
+            false => None,
+        }
+    }
+
+    /// Read the latest snapshot, retrying until a consistent view is obtained
+    ///
+    /// # Returns
+    ///
+    /// The version of the snapshot, incremented on every publish, and the snapshot
+    pub fn read(&self) -> (u64, Snapshot) {
+        loop {
+            if let Some(read) = self.try_read() {
+                return read;
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderBook, Side};
+
+    #[test]
+    fn test_publish_read() {
+        let path = std::env::temp_dir().join(format!("orderbook-shm-{}", std::process::id()));
+        let mut publisher = ShmPublisher::create(&path, 2).unwrap();
+        let reader = ShmReader::open(&path).unwrap();
+        assert_eq!(reader.read(), (0, Snapshot::default()));
+
+        let mut book = OrderBook::new();
+        book.add(1, Side::Bid, 100, 10).unwrap();
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        book.add(1, Side::Bid, 98, 10).unwrap();
+        book.add(1, Side::Ask, 101, 5).unwrap();
+        publisher.publish(&book.snapshot());
+
+        let (version, snapshot) = reader.read();
+        assert_eq!(version, 1);
+        assert_eq!(snapshot.bids, vec![(100, 10), (99, 10)]);
+        assert_eq!(snapshot.asks, vec![(101, 5)]);
+
+        publisher
+            .region
+            .word(CAPACITY_WORD)
+            .store(u64::MAX, Ordering::Relaxed);
+        let error = ShmReader::open(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = ShmPublisher::create(&path, usize::MAX).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        std::fs::remove_file(path).unwrap();
+    }
+}
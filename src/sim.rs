@@ -0,0 +1,350 @@
+use crate::{Bbo, Clock, Command, CommandResult, ManualClock, OrderBook, OrderBookBuilder};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Distribution of a latency, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyModel {
+    /// Every message arrives at once
+    Zero,
+
+    /// Every message takes the same time
+    Constant(u64),
+
+    /// Latencies are drawn uniformly from a range
+    Uniform {
+        /// Smallest latency
+        min: u64,
+
+        /// Largest latency
+        max: u64,
+    },
+
+    /// Latencies are a floor plus an exponentially distributed delay, modelling the
+    /// long tail of queueing on the way
+    Exponential {
+        /// Smallest latency
+        min: u64,
+
+        /// Mean of the delay on top of the floor
+        mean: f64,
+    },
+}
+
+impl LatencyModel {
+    /// Draw a latency
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The source of randomness
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match *self {
+            LatencyModel::Zero => 0,
+            LatencyModel::Constant(latency) => latency,
+            LatencyModel::Uniform { min, max } => rng.gen_range(min..=max.max(min)),
+            LatencyModel::Exponential { min, mean } => {
+                let delay = -(1.0 - rng.gen::<f64>()).ln() * mean;
+                min.saturating_add(delay as u64)
+            }
+        }
+    }
+}
+
+/// Command waiting to reach the order book
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    /// Time the command reaches the order book
+    time: u64,
+
+    /// Sequence number, ordering commands arriving at the same time
+    seq: u64,
+
+    /// Whether the command was sent by the simulated strategy
+    strategy: bool,
+
+    /// Command to apply
+    command: Command,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Pending) -> bool {
+        (self.time, self.seq) == (other.time, other.seq)
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Pending) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Pending) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+/// Command applied to the order book during a simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimEvent {
+    /// Time the command reached the order book
+    pub time: u64,
+
+    /// Whether the command was sent by the simulated strategy
+    pub strategy: bool,
+
+    /// The command
+    pub command: Command,
+
+    /// Result of the command
+    pub result: CommandResult,
+}
+
+/// Best bid and offer delivered to the simulated strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketUpdate {
+    /// Time the order book changed
+    pub sent: u64,
+
+    /// Time the update reaches the strategy
+    pub delivered: u64,
+
+    /// Best bid and offer after the change
+    pub bbo: Bbo,
+}
+
+/// Discrete-event simulation of a strategy trading against an order book
+///
+/// Commands of the strategy reach the order book after an order entry latency and the
+/// changes of the best bid and offer reach the strategy after a market data latency,
+/// so that other flow, injected at the time it reached the venue, can get ahead of
+/// the strategy in the queue as it would live. Each channel is FIFO: a message never
+/// overtakes one sent before it. The order book runs on a manual clock driven by the
+/// simulation.
+#[derive(Debug)]
+pub struct Simulator {
+    /// Simulated order book
+    book: OrderBook,
+
+    /// Clock of the order book
+    clock: ManualClock,
+
+    /// Source of randomness of the latencies
+    rng: StdRng,
+
+    /// Latency from a decision of the strategy to the order book
+    order_latency: LatencyModel,
+
+    /// Latency from a change of the order book to the strategy
+    data_latency: LatencyModel,
+
+    /// Commands waiting to reach the order book, earliest first
+    pending: BinaryHeap<Reverse<Pending>>,
+
+    /// Sequence number of the last scheduled command
+    seq: u64,
+
+    /// Latest arrival time of a command of the strategy
+    last_arrival: u64,
+
+    /// Market data on its way to the strategy, in delivery order
+    updates: VecDeque<MarketUpdate>,
+
+    /// Best bid and offer last published
+    last_bbo: Bbo,
+}
+
+impl Simulator {
+    /// Create a new simulation starting at time zero
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The configuration of the order book, whose clock is replaced
+    /// * `order_latency` - The latency from the strategy to the order book
+    /// * `data_latency` - The latency from the order book to the strategy
+    /// * `seed` - The seed of the latency draws, making runs reproducible
+    pub fn new(
+        builder: OrderBookBuilder,
+        order_latency: LatencyModel,
+        data_latency: LatencyModel,
+        seed: u64,
+    ) -> Simulator {
+        let clock = ManualClock::new(0);
+        Simulator {
+            book: builder.clock(clock.clone()).build(),
+            clock,
+            rng: StdRng::seed_from_u64(seed),
+            order_latency,
+            data_latency,
+            pending: BinaryHeap::new(),
+            seq: 0,
+            last_arrival: 0,
+            updates: VecDeque::new(),
+            last_bbo: Bbo::default(),
+        }
+    }
+
+    /// Get the simulated order book
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Get the current simulated time
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    /// Schedule a command to reach the order book
+    fn schedule(&mut self, time: u64, strategy: bool, command: Command) {
+        self.seq += 1;
+        self.pending.push(Reverse(Pending {
+            time,
+            seq: self.seq,
+            strategy,
+            command,
+        }));
+    }
+
+    /// Send a command of the strategy, decided at the current time
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command to send
+    ///
+    /// # Returns
+    ///
+    /// The time the command will reach the order book
+    pub fn send(&mut self, command: Command) -> u64 {
+        let latency = self.order_latency.sample(&mut self.rng);
+        let time = self.now().saturating_add(latency).max(self.last_arrival);
+        self.last_arrival = time;
+        self.schedule(time, true, command);
+        time
+    }
+
+    /// Inject a command of other participants, such as recorded market flow
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time the command reaches the order book, not before now
+    /// * `command` - The command to apply
+    pub fn inject(&mut self, time: u64, command: Command) {
+        self.schedule(time.max(self.now()), false, command);
+    }
+
+    /// Run the simulation up to a time, applying the commands reaching the order book
+    ///
+    /// # Arguments
+    ///
+    /// * `until` - The time to stop at, which becomes the current time
+    ///
+    /// # Returns
+    ///
+    /// The commands applied, in order
+    pub fn advance(&mut self, until: u64) -> Vec<SimEvent> {
+        let mut events = Vec::new();
+        while let Some(Reverse(next)) = self.pending.peek() {
+            if next.time > until {
+                break;
+            }
+            let Reverse(next) = self.pending.pop().expect("peeked command");
+            self.clock.set(next.time.max(self.now()));
+            let result = next.command.apply(&mut self.book);
+            self.publish();
+            events.push(SimEvent {
+                time: next.time,
+                strategy: next.strategy,
+                command: next.command,
+                result,
+            });
+        }
+        self.clock.set(until.max(self.now()));
+        events
+    }
+
+    /// Send the best bid and offer to the strategy if it changed
+    fn publish(&mut self) {
+        let bbo = self.book.bbo();
+        if bbo == self.last_bbo {
+            return;
+        }
+        self.last_bbo = bbo;
+        let sent = self.now();
+        let latency = self.data_latency.sample(&mut self.rng);
+        let last = self.updates.back().map_or(0, |u| u.delivered);
+        self.updates.push_back(MarketUpdate {
+            sent,
+            delivered: sent.saturating_add(latency).max(last),
+            bbo,
+        });
+    }
+
+    /// Receive the market data delivered to the strategy by the current time
+    ///
+    /// # Returns
+    ///
+    /// The updates delivered since the last call, oldest first
+    pub fn market_data(&mut self) -> Vec<MarketUpdate> {
+        let now = self.now();
+        let delivered = self
+            .updates
+            .iter()
+            .take_while(|u| u.delivered <= now)
+            .count();
+        self.updates.drain(..delivered).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderStatus, Side};
+
+    #[test]
+    fn test_latency_races() {
+        let mut sim = Simulator::new(
+            OrderBook::builder(),
+            LatencyModel::Constant(100),
+            LatencyModel::Uniform { min: 20, max: 30 },
+            7,
+        );
+        let ask = |owner, price| Command::Add {
+            owner,
+            side: Side::Ask,
+            price,
+            qty: 10,
+            tag: 0,
+        };
+        sim.inject(0, ask(1, 100));
+        sim.advance(10);
+        assert!(sim.market_data().is_empty());
+        sim.advance(40);
+        let updates = sim.market_data();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].bbo.ask, Some((100, 10)));
+        assert!((20..=30).contains(&updates[0].delivered));
+
+        let buy = |owner| Command::Execute {
+            owner,
+            side: Side::Bid,
+            price: 100,
+            qty: 10,
+            tag: 0,
+        };
+        assert_eq!(sim.send(buy(2)), 140);
+        sim.inject(90, buy(3));
+        let events = sim.advance(200);
+        assert_eq!(events.len(), 2);
+        assert!(!events[0].strategy);
+        let CommandResult::Executed(result) = &events[1].result else {
+            panic!("not an execution");
+        };
+        assert_eq!((events[1].time, events[1].strategy), (140, true));
+        assert_eq!(result.status, OrderStatus::Created);
+        assert_eq!(sim.book().bbo().bid, Some((100, 10)));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{OrderBook, OrderQty, OwnerId, Price, Side};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+/// Aggregated depth of both sides of the order book at a point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Bid levels as (price, total quantity), highest price first
+    pub bids: Vec<(Price, OrderQty)>,
+
+    /// Ask levels as (price, total quantity), lowest price first
+    pub asks: Vec<(Price, OrderQty)>,
+}
+
+/// Owner of the synthetic orders seeded from an aggregate snapshot
+pub const SYNTHETIC_OWNER: OwnerId = OwnerId::MAX;
+
+/// A change to the total quantity resting at a single price level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUpdate {
+    /// Side of the level
+    pub side: Side,
+
+    /// Price of the level
+    pub price: Price,
+
+    /// New total quantity of the level, zero if the level was removed
+    pub qty: OrderQty,
+}
+
+/// Subscriber to changes of the depth of an order book
+pub trait DepthListener: fmt::Debug {
+    /// Called for every level whose total quantity changed, once each operation on the
+    /// order book completes
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The new total quantity of the level
+    fn on_level(&mut self, update: &LevelUpdate);
+}
+
+/// Forward changes over a channel, dropping them once the receiver hangs up
+impl DepthListener for Sender<LevelUpdate> {
+    fn on_level(&mut self, update: &LevelUpdate) {
+        let _ = self.send(*update);
+    }
+}
+
+/// Order two prices of the given side from the best price outwards
+fn cmp_prices(side: Side, a: Price, b: Price) -> Ordering {
+    match side {
+        Side::Bid => b.cmp(&a),
+        Side::Ask => a.cmp(&b),
+    }
+}
+
+/// Compute the level updates turning `old` into `new` for a single side
+fn diff_side(
+    side: Side,
+    old: &[(Price, OrderQty)],
+    new: &[(Price, OrderQty)],
+    updates: &mut Vec<LevelUpdate>,
+) {
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        let ord = match (old.get(i), new.get(j)) {
+            (Some(o), Some(n)) => cmp_prices(side, o.0, n.0),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+        match ord {
+            Ordering::Less => {
+                updates.push(LevelUpdate {
+                    side,
+                    price: old[i].0,
+                    qty: 0,
+                });
+                i += 1;
+            }
+            Ordering::Greater => {
+                updates.push(LevelUpdate {
+                    side,
+                    price: new[j].0,
+                    qty: new[j].1,
+                });
+                j += 1;
+            }
+            Ordering::Equal => {
+                if old[i].1 != new[j].1 {
+                    updates.push(LevelUpdate {
+                        side,
+                        price: new[j].0,
+                        qty: new[j].1,
+                    });
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+impl Snapshot {
+    /// Get the levels of one side of the snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order book
+    ///
+    /// # Returns
+    ///
+    /// The levels of the given side, best price first
+    pub fn levels(&self, side: Side) -> &[(Price, OrderQty)] {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    /// Compute the level updates that turn this snapshot into a newer one
+    ///
+    /// # Arguments
+    ///
+    /// * `newer` - The snapshot to diff against
+    ///
+    /// # Returns
+    ///
+    /// The changed levels, bids first, each side ordered from the best price outwards
+    pub fn diff(&self, newer: &Snapshot) -> Vec<LevelUpdate> {
+        let mut updates = Vec::new();
+        diff_side(Side::Bid, &self.bids, &newer.bids, &mut updates);
+        diff_side(Side::Ask, &self.asks, &newer.asks, &mut updates);
+        updates
+    }
+
+    /// Apply a level update to the snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The level update to apply
+    pub fn apply(&mut self, update: &LevelUpdate) {
+        let side = update.side;
+        let levels = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        match levels.binary_search_by(|(price, _)| cmp_prices(side, *price, update.price)) {
+            Ok(idx) if update.qty == 0 => {
+                levels.remove(idx);
+            }
+            Ok(idx) => levels[idx].1 = update.qty,
+            Err(_) if update.qty == 0 => {}
+            Err(idx) => levels.insert(idx, (update.price, update.qty)),
+        }
+    }
+}
+
+impl OrderBook {
+    /// Set the subscriber to changes of the depth
+    ///
+    /// The subscriber is sent the levels changed by each operation, best price first
+    /// within a side, bids first, and only levels whose total quantity differs from
+    /// before the operation. Applied to a snapshot taken when subscribing, they keep it
+    /// equal to the depth of the order book.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_depth_listener<L: DepthListener + 'static>(&mut self, listener: L) {
+        self.depth_listener = Some(Box::new(listener));
+        for book in [&mut self.bids, &mut self.asks] {
+            book.changed = Some(Vec::new());
+        }
+    }
+
+    /// Notify the depth subscriber of the levels changed since the last notification
+    pub(crate) fn notify_depth(&mut self) {
+        let Some(listener) = self.depth_listener.as_mut() else {
+            return;
+        };
+        for book in [&mut self.bids, &mut self.asks] {
+            let Some(mut changed) = book.changed.take() else {
+                continue;
+            };
+            changed.sort_by(|a, b| cmp_prices(book.side, a.0, b.0));
+            changed.dedup_by_key(|&mut (price, _)| price);
+            for &(price, old) in &changed {
+                let qty = book.get_total_qty(price);
+                if qty != old {
+                    let side = book.side;
+                    listener.on_level(&LevelUpdate { side, price, qty });
+                }
+            }
+            changed.clear();
+            book.changed = Some(changed);
+        }
+    }
+
+    /// Create an order book resting synthetic orders that match an aggregate snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The aggregate snapshot to match
+    /// * `granularity` - The maximum quantity of a synthetic order, zero for one order
+    ///   per level
+    ///
+    /// # Returns
+    ///
+    /// The seeded order book
+    pub fn from_l2_snapshot(snapshot: &Snapshot, granularity: OrderQty) -> OrderBook {
+        let mut book = OrderBook::new();
+        book.seed_l2(snapshot, granularity);
+        book
+    }
+
+    /// Rest synthetic orders, owned by `SYNTHETIC_OWNER`, matching an aggregate snapshot
+    ///
+    /// The orders are added without matching, so the order book should not already
+    /// hold orders crossing the snapshot. Each level is split into orders of the
+    /// granularity, the last one holding the rest of the level's quantity. Orders the
+    /// order book rejects are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The aggregate snapshot to match
+    /// * `granularity` - The maximum quantity of a synthetic order, zero for one order
+    ///   per level
+    pub fn seed_l2(&mut self, snapshot: &Snapshot, granularity: OrderQty) {
+        for side in [Side::Bid, Side::Ask] {
+            for &(price, qty) in snapshot.levels(side) {
+                let lot = if granularity == 0 { qty } else { granularity };
+                let mut left = qty;
+                while left > 0 {
+                    let qty = left.min(lot);
+                    let _ = self.add(SYNTHETIC_OWNER, side, price, qty);
+                    left -= qty;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_depth_listener() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        let mut depth = book.snapshot();
+        book.set_depth_listener(tx);
+
+        let id = book.add(1, Side::Ask, 101, 10).unwrap();
+        book.add(1, Side::Ask, 102, 10).unwrap();
+        book.amend(id, 103, 10);
+        book.execute(2, Side::Bid, 102, 15);
+        book.reduce(id, 5);
+        book.execute(2, Side::Ask, 99, 4);
+        let updates: Vec<_> = rx.try_iter().collect();
+        assert_eq!(updates.len(), 8);
+        for update in &updates {
+            depth.apply(update);
+        }
+        assert_eq!(depth, book.snapshot());
+    }
+
+    #[test]
+    fn test_diff_apply() {
+        let old = Snapshot {
+            bids: vec![(100, 10), (99, 5), (97, 1)],
+            asks: vec![(101, 10), (103, 7)],
+        };
+        let new = Snapshot {
+            bids: vec![(100, 10), (98, 3), (97, 2)],
+            asks: vec![(102, 4), (103, 7), (104, 1)],
+        };
+        let updates = old.diff(&new);
+        assert_eq!(
+            updates,
+            vec![
+                LevelUpdate {
+                    side: Side::Bid,
+                    price: 99,
+                    qty: 0
+                },
+                LevelUpdate {
+                    side: Side::Bid,
+                    price: 98,
+                    qty: 3
+                },
+                LevelUpdate {
+                    side: Side::Bid,
+                    price: 97,
+                    qty: 2
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: 101,
+                    qty: 0
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: 102,
+                    qty: 4
+                },
+                LevelUpdate {
+                    side: Side::Ask,
+                    price: 104,
+                    qty: 1
+                },
+            ]
+        );
+        let mut patched = old.clone();
+        updates.iter().for_each(|u| patched.apply(u));
+        assert_eq!(patched, new);
+    }
+
+    #[test]
+    fn test_from_l2_snapshot() {
+        let snapshot = Snapshot {
+            bids: vec![(100, 25), (99, 5)],
+            asks: vec![(101, 10)],
+        };
+        let mut book = OrderBook::from_l2_snapshot(&snapshot, 10);
+        assert_eq!(book.snapshot(), snapshot);
+        assert_eq!(book.cancel_all(SYNTHETIC_OWNER), 5);
+        assert_eq!(
+            OrderBook::from_l2_snapshot(&snapshot, 0).snapshot(),
+            snapshot
+        );
+    }
+}
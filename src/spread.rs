@@ -0,0 +1,318 @@
+use crate::{
+    FillResult, OrderBook, OrderQty, OrderStatus, OwnerId, Price, RejectReason, Side, TradeId,
+};
+
+/// Leg of a strategy order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leg {
+    /// Index of the order book of the leg
+    pub book: usize,
+
+    /// Side traded in the leg when buying one unit of the strategy
+    pub side: Side,
+
+    /// Quantity traded in the leg per unit of the strategy
+    pub ratio: OrderQty,
+}
+
+/// Strategy order spanning several order books, such as a calendar spread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpreadOrder {
+    /// Owner of the order
+    pub owner: OwnerId,
+
+    /// Legs of the strategy
+    pub legs: Vec<Leg>,
+
+    /// Number of units of the strategy to trade
+    pub qty: OrderQty,
+
+    /// Highest net price paid per unit: the cost of the bought legs minus the proceeds
+    /// of the sold legs, negative for a credit
+    pub limit: i128,
+}
+
+/// Reason a strategy order was not executed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpreadReject {
+    /// Strategy has no legs, a leg refers to no order book, two legs share an order
+    /// book or the quantity of a leg overflows
+    InvalidLegs,
+
+    /// Order book of a leg cannot fill the leg in full
+    Liquidity(usize),
+
+    /// Net price of the strategy is above the limit
+    Price(i128),
+
+    /// Order of a leg would be refused by its order book
+    Leg(usize, RejectReason),
+
+    /// Order of a leg would be canceled by self-trade prevention
+    SelfTrade(usize),
+}
+
+/// Outcome of a strategy order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpreadStatus {
+    /// Every leg was filled in full
+    Filled,
+
+    /// No leg was executed
+    Rejected(SpreadReject),
+
+    /// A leg was refused or cut short while executing, by a credit check, matching
+    /// hooks or a market-maker protection its fills triggered, and the trades of the
+    /// legs executed up to it were busted
+    Broken(usize),
+}
+
+/// Result of a strategy order
+#[derive(Debug)]
+pub struct SpreadResult {
+    /// Outcome of the order
+    pub status: SpreadStatus,
+
+    /// Net price paid for every unit traded, as for the limit
+    pub net_price: i128,
+
+    /// Result of the execution of each leg executed, in leg order, busted since if the
+    /// strategy broke
+    pub legs: Vec<FillResult>,
+}
+
+/// Net amount paid for fills of a leg, negative for proceeds
+fn signed_notional(side: Side, fills: &[(Price, OrderQty)]) -> i128 {
+    let notional: i128 = fills.iter().map(|(p, q)| *p as i128 * *q as i128).sum();
+    match side {
+        Side::Bid => notional,
+        Side::Ask => -notional,
+    }
+}
+
+/// Execute a strategy order as simultaneous executions in the order books of its legs
+///
+/// The fills of every leg are previewed first, accounting for the instrument increments,
+/// triggered market-maker protections, self-trade prevention and the trading phase,
+/// and the legs are only executed if each can fill in full and the net price is within
+/// the limit. Each leg is then executed with its worst previewed price as limit, so no
+/// leg fills at a worse price than previewed. The order books are borrowed together for
+/// the whole operation, so nothing can trade in between, and every leg must trade in an
+/// order book of its own for its preview to hold.
+///
+/// Credit checks, matching hooks and market-maker protections triggered by the fills of
+/// a leg cannot be evaluated without side effects. Should one refuse or cut short a leg
+/// during execution, the strategy is reported as broken at that leg and every trade of
+/// the legs executed so far is busted, latest first, so no leg is left partially
+/// executed. The tapes of the order books keep every trade while the strategy executes
+/// for this, and are trimmed back to their capacity afterwards. Stop orders triggered
+/// by the busted trades are not undone.
+///
+/// # Arguments
+///
+/// * `books` - The order books of the legs
+/// * `order` - The strategy order
+///
+/// # Returns
+///
+/// The result of the strategy order
+pub fn execute_spread(books: &mut [OrderBook], order: &SpreadOrder) -> SpreadResult {
+    let mut result = SpreadResult {
+        status: SpreadStatus::Filled,
+        net_price: 0,
+        legs: Vec::new(),
+    };
+    let reject = |mut result: SpreadResult, reason| {
+        result.status = SpreadStatus::Rejected(reason);
+        result
+    };
+    let valid = |leg: &Leg| leg.book < books.len() && leg.ratio > 0;
+    let shared = (order.legs.iter().enumerate())
+        .any(|(i, leg)| order.legs[..i].iter().any(|other| other.book == leg.book));
+    if order.legs.is_empty() || order.qty == 0 || !order.legs.iter().all(valid) || shared {
+        return reject(result, SpreadReject::InvalidLegs);
+    }
+    let mut qtys = Vec::with_capacity(order.legs.len());
+    for leg in &order.legs {
+        match leg.ratio.checked_mul(order.qty) {
+            Some(qty) => qtys.push(qty),
+            None => return reject(result, SpreadReject::InvalidLegs),
+        }
+    }
+
+    let mut limits = Vec::with_capacity(order.legs.len());
+    let mut net: i128 = 0;
+    for (i, (leg, &qty)) in order.legs.iter().zip(&qtys).enumerate() {
+        let book = &books[leg.book];
+        let tick = book.instrument().tick_size.max(1);
+        let price = match leg.side {
+            Side::Bid => Price::MAX / tick * tick,
+            Side::Ask => 0,
+        };
+        let preview = book.preview(order.owner, leg.side, price, qty);
+        match preview.status {
+            OrderStatus::Filled => {}
+            OrderStatus::Rejected(reason) => return reject(result, SpreadReject::Leg(i, reason)),
+            OrderStatus::Canceled => return reject(result, SpreadReject::SelfTrade(i)),
+            _ => return reject(result, SpreadReject::Liquidity(i)),
+        }
+        let prices = preview.orders.iter().map(|(price, _)| *price);
+        let worst = match leg.side {
+            Side::Bid => prices.max(),
+            Side::Ask => prices.min(),
+        };
+        let worst = worst.unwrap_or(price);
+        if let Err(reason) = book.instrument().check(worst, qty) {
+            return reject(result, SpreadReject::Leg(i, reason));
+        }
+        net += signed_notional(leg.side, &preview.orders);
+        limits.push(worst);
+    }
+    result.net_price = net / order.qty as i128;
+    if net > order.limit * order.qty as i128 {
+        let net_price = result.net_price;
+        return reject(result, SpreadReject::Price(net_price));
+    }
+
+    let mut capacities = Vec::with_capacity(order.legs.len());
+    let mut trades: Vec<(usize, TradeId)> = Vec::new();
+    for (i, ((leg, price), qty)) in order.legs.iter().zip(limits).zip(qtys).enumerate() {
+        let book = &mut books[leg.book];
+        capacities.push(book.tape_capacity);
+        book.tape_capacity = usize::MAX;
+        let first = book.next_trade;
+        let fill = book.execute(order.owner, leg.side, price, qty);
+        if let Some(id) = fill.id {
+            book.force_cancel(id);
+        }
+        let traded = fill.orders.len() as TradeId;
+        trades.extend((first..first + traded).map(|trade| (leg.book, trade)));
+        let filled = fill.status == OrderStatus::Filled;
+        result.legs.push(fill);
+        if !filled {
+            result.status = SpreadStatus::Broken(i);
+            break;
+        }
+    }
+    if let SpreadStatus::Broken(_) = result.status {
+        for &(book, trade) in trades.iter().rev() {
+            let _ = books[book].bust_trade(trade);
+        }
+    }
+    for (leg, capacity) in order.legs.iter().zip(capacities) {
+        books[leg.book].set_tape_capacity(capacity);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credit::CreditLimit;
+    use crate::StpPolicy;
+
+    #[test]
+    fn test_calendar_spread() {
+        let mut books = vec![OrderBook::new(), OrderBook::new()];
+        books[0].add(1, Side::Ask, 100, 10).unwrap();
+        books[0].add(1, Side::Ask, 101, 10).unwrap();
+        books[1].add(1, Side::Bid, 104, 5).unwrap();
+        books[1].add(1, Side::Bid, 103, 20).unwrap();
+        let mut order = SpreadOrder {
+            owner: 2,
+            legs: vec![
+                Leg {
+                    book: 0,
+                    side: Side::Bid,
+                    ratio: 1,
+                },
+                Leg {
+                    book: 1,
+                    side: Side::Ask,
+                    ratio: 1,
+                },
+            ],
+            qty: 15,
+            limit: -4,
+        };
+
+        let result = execute_spread(&mut books, &order);
+        assert_eq!(
+            result.status,
+            SpreadStatus::Rejected(SpreadReject::Price(-3))
+        );
+        assert_eq!(books[0].snapshot().asks, vec![(100, 10), (101, 10)]);
+
+        order.limit = -3;
+        let result = execute_spread(&mut books, &order);
+        assert_eq!(result.status, SpreadStatus::Filled);
+        assert_eq!(result.legs[0].orders, vec![(100, 10), (101, 5)]);
+        assert_eq!(result.legs[1].orders, vec![(104, 5), (103, 10)]);
+        assert_eq!(books[0].snapshot().asks, vec![(101, 5)]);
+        assert_eq!(books[1].snapshot().bids, vec![(103, 10)]);
+
+        order.qty = 20;
+        let result = execute_spread(&mut books, &order);
+        assert_eq!(
+            result.status,
+            SpreadStatus::Rejected(SpreadReject::Liquidity(0))
+        );
+        assert!(result.legs.is_empty());
+        assert_eq!(books[1].snapshot().bids, vec![(103, 10)]);
+
+        order.qty = 5;
+        order.limit = 0;
+        books[1].set_credit_check(CreditLimit {
+            max_position: 1,
+            max_notional: u128::MAX,
+        });
+        let result = execute_spread(&mut books, &order);
+        assert_eq!(result.status, SpreadStatus::Broken(1));
+        assert_eq!(result.legs[0].orders, vec![(101, 5)]);
+        assert_eq!(books[0].snapshot().asks, vec![(101, 5)]);
+        assert_eq!(books[0].tape().count(), 0);
+    }
+
+    #[test]
+    fn test_spread_previews() {
+        let stp = OrderBook::builder().stp(StpPolicy::CancelIncoming).build();
+        let mut books = vec![stp, OrderBook::new()];
+        books[0].add(1, Side::Ask, 100, 10).unwrap();
+        books[0].add(2, Side::Ask, 101, 10).unwrap();
+        books[1].add(1, Side::Bid, 104, 20).unwrap();
+        let leg = |book, side, ratio| Leg { book, side, ratio };
+        let mut order = SpreadOrder {
+            owner: 2,
+            legs: vec![leg(0, Side::Bid, 1), leg(1, Side::Ask, 1)],
+            qty: 15,
+            limit: 0,
+        };
+        let status =
+            |books: &mut Vec<OrderBook>, order: &SpreadOrder| execute_spread(books, order).status;
+        let rejected = |reason| SpreadStatus::Rejected(reason);
+        assert_eq!(
+            status(&mut books, &order),
+            rejected(SpreadReject::SelfTrade(0))
+        );
+
+        order.legs[1] = leg(0, Side::Ask, 1);
+        assert_eq!(
+            status(&mut books, &order),
+            rejected(SpreadReject::InvalidLegs)
+        );
+        order.legs[1] = leg(1, Side::Ask, OrderQty::MAX);
+        assert_eq!(
+            status(&mut books, &order),
+            rejected(SpreadReject::InvalidLegs)
+        );
+
+        order.legs[1] = leg(1, Side::Ask, 1);
+        order.qty = 5;
+        books[1].pre_open();
+        assert_eq!(
+            status(&mut books, &order),
+            rejected(SpreadReject::Liquidity(1))
+        );
+        assert_eq!(books[0].snapshot().asks, vec![(100, 10), (101, 10)]);
+    }
+}
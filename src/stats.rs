@@ -0,0 +1,178 @@
+use crate::{HalfBook, OrderBook, OrderQty, Price, Side};
+use std::cell::Cell;
+
+/// Statistics of the orders resting at a price level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelStats {
+    /// Price of the level
+    pub price: Price,
+
+    /// Total quantity resting at the level
+    pub qty: OrderQty,
+
+    /// Number of orders resting at the level
+    pub orders: usize,
+
+    /// Quantity of the smallest order
+    pub min_size: OrderQty,
+
+    /// Quantity of the largest order
+    pub max_size: OrderQty,
+}
+
+impl LevelStats {
+    /// Get the average quantity of the orders of the level
+    pub fn avg_size(&self) -> f64 {
+        self.qty as f64 / self.orders as f64
+    }
+}
+
+/// Smallest and largest order quantities of a price level
+///
+/// The range is kept up to date as orders arrive and is marked stale when the order
+/// holding one of its bounds shrinks, grows or leaves, in which case it is recomputed
+/// from the queue on the next query.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SizeRange {
+    /// Smallest order quantity
+    min: OrderQty,
+
+    /// Largest order quantity
+    max: OrderQty,
+
+    /// Whether the bounds must be recomputed
+    stale: bool,
+}
+
+/// Account for the quantity of an order of a level changing
+///
+/// # Arguments
+///
+/// * `range` - The size range of the level
+/// * `old` - The previous quantity of the order, zero if it just arrived
+/// * `new` - The new quantity of the order, zero if it left
+pub(crate) fn resize(range: &Cell<SizeRange>, old: OrderQty, new: OrderQty) {
+    let mut sizes = range.get();
+    if sizes.stale {
+        return;
+    }
+    let bound = old == sizes.min || old == sizes.max;
+    if (new == 0 && bound) || (old == sizes.min && new > old) || (old == sizes.max && new < old) {
+        sizes.stale = true;
+    } else if new > 0 {
+        sizes.min = sizes.min.min(new);
+        sizes.max = sizes.max.max(new);
+    }
+    range.set(sizes);
+}
+
+/// Reset the size range of a level that was empty to its first order
+pub(crate) fn reset(range: &Cell<SizeRange>, qty: OrderQty) {
+    range.set(SizeRange {
+        min: qty,
+        max: qty,
+        stale: false,
+    });
+}
+
+impl HalfBook {
+    /// Get the statistics of a non-empty level
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - The position of the level in prices
+    pub(crate) fn stats(&self, pos: usize) -> LevelStats {
+        let idx = self.queues[pos];
+        let queue = &self.price_levels[idx];
+        let mut sizes = self.sizes[idx].get();
+        if sizes.stale {
+            sizes.min = queue.iter().map(|o| o.qty).min().unwrap_or(0);
+            sizes.max = queue.iter().map(|o| o.qty).max().unwrap_or(0);
+            sizes.stale = false;
+            self.sizes[idx].set(sizes);
+        }
+        LevelStats {
+            price: self.prices[pos],
+            qty: self.qtys[pos],
+            orders: queue.len(),
+            min_size: sizes.min,
+            max_size: sizes.max,
+        }
+    }
+}
+
+impl OrderBook {
+    /// Get the statistics of the orders resting at a price level
+    ///
+    /// Counts and totals are maintained as orders change and the size bounds are only
+    /// recomputed after the orders holding them shrank or left.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order book
+    /// * `price` - The price of the level
+    ///
+    /// # Returns
+    ///
+    /// The statistics of the level, `None` if no order rests at the price
+    pub fn level_stats(&self, side: Side, price: Price) -> Option<LevelStats> {
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        book.position(price).ok().map(|pos| book.stats(pos))
+    }
+
+    /// Get the statistics of the best levels of a side
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side of the order book
+    /// * `levels` - The maximum number of levels
+    ///
+    /// # Returns
+    ///
+    /// The statistics of each level, best price first
+    pub fn depth_stats(&self, side: Side, levels: usize) -> Vec<LevelStats> {
+        let book = match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        (0..book.prices.len())
+            .rev()
+            .take(levels)
+            .map(|pos| book.stats(pos))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_stats() {
+        let mut book = OrderBook::new();
+        let small = book.add(1, Side::Ask, 100, 2).unwrap();
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        let large = book.add(1, Side::Ask, 100, 20).unwrap();
+        book.add(1, Side::Ask, 101, 5).unwrap();
+        let stats = book.level_stats(Side::Ask, 100).unwrap();
+        assert_eq!((stats.orders, stats.qty), (3, 32));
+        assert_eq!((stats.min_size, stats.max_size), (2, 20));
+
+        book.reduce(large, 15);
+        book.execute(2, Side::Bid, 100, 1);
+        let stats = book.level_stats(Side::Ask, 100).unwrap();
+        assert_eq!((stats.min_size, stats.max_size), (1, 10));
+        book.cancel(small);
+        book.add(1, Side::Ask, 100, 3).unwrap();
+        let stats = book.depth_stats(Side::Ask, 2);
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].orders, stats[0].min_size), (3, 3));
+        assert_eq!(stats[0].max_size, 10);
+        assert_eq!(stats[0].avg_size(), 6.0);
+        assert_eq!(stats[1].price, 101);
+        assert!(book.level_stats(Side::Bid, 100).is_none());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{FillResult, Incoming, OrderBook, OrderId, OrderQty, OrderView, OwnerId, Price, Side};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+/// Subscriber to triggered stop orders
+pub trait StopListener: fmt::Debug {
+    /// Called after a triggered stop order was executed
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the stop order
+    /// * `result` - The result of its execution
+    fn on_triggered(&mut self, id: OrderId, result: &FillResult);
+}
+
+/// Forward triggered stop orders over a channel, dropping them once the receiver hangs up
+impl StopListener for Sender<(OrderId, FillResult)> {
+    fn on_triggered(&mut self, id: OrderId, result: &FillResult) {
+        let _ = self.send((id, result.clone()));
+    }
+}
+
+/// Index of the pending stop orders of a side
+fn slot(side: Side) -> usize {
+    match side {
+        Side::Bid => 0,
+        Side::Ask => 1,
+    }
+}
+
+/// Map a price to the trigger key order of a side, so that the stop orders closest to
+/// the market, which trigger first, sort first
+fn key_price(side: Side, price: Price) -> Price {
+    match side {
+        Side::Bid => price,
+        Side::Ask => Price::MAX - price,
+    }
+}
+
+impl OrderBook {
+    /// Add a stop order, executed as a limit order once a trade prints at or through
+    /// its trigger price
+    ///
+    /// Buy stops trigger on trades at or above their trigger price and sell stops on
+    /// trades at or below it. A stop order whose trigger price already traded triggers
+    /// immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the order
+    /// * `side` - The side of the order
+    /// * `trigger` - The trigger price of the order
+    /// * `price` - The limit price of the order once triggered
+    /// * `qty` - The quantity of the order
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the order, which it keeps once triggered
+    pub fn add_stop(
+        &mut self,
+        owner: OwnerId,
+        side: Side,
+        trigger: Price,
+        price: Price,
+        qty: OrderQty,
+    ) -> OrderId {
+        let id = self.next_id();
+        let key = (key_price(side, trigger), self.next_seq);
+        self.next_seq += 1;
+        let stop = Incoming {
+            id,
+            owner,
+            side,
+            price,
+            qty,
+            tag: 0,
+        };
+        self.stops[slot(side)].insert(key, stop);
+        self.stop_loc.insert(id, (side, key));
+        self.run_stops();
+        id
+    }
+
+    /// Cancel a pending stop order
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the stop order
+    ///
+    /// # Returns
+    ///
+    /// Whether the stop order was pending
+    pub fn cancel_stop(&mut self, id: OrderId) -> bool {
+        match self.stop_loc.remove(&id) {
+            Some((side, key)) => self.stops[slot(side)].remove(&key).is_some(),
+            None => false,
+        }
+    }
+
+    /// Iterate over the pending stop orders
+    ///
+    /// # Returns
+    ///
+    /// The view of each stop order at its limit price, buys first, each side in the
+    /// order the stop orders trigger in
+    pub fn stop_orders(&self) -> impl Iterator<Item = OrderView> + '_ {
+        (self.stops.iter().flat_map(|stops| stops.values())).map(|stop| OrderView {
+            id: stop.id,
+            owner: stop.owner,
+            side: stop.side,
+            price: stop.price,
+            qty: stop.qty,
+            tag: stop.tag,
+        })
+    }
+
+    /// Set the maximum number of stop orders triggered by a single command
+    ///
+    /// Stop orders triggered beyond the limit stay pending, so a cascade cannot take an
+    /// unbounded amount of work. They are triggered by the next command running stop
+    /// orders, an execution, a quote, a new stop order or an uncross; adds, cancels and
+    /// the other commands leave them pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of stop orders, at least one
+    pub fn set_stop_cascade_limit(&mut self, limit: usize) {
+        self.stop_cascade_limit = limit.max(1);
+    }
+
+    /// Set the subscriber to triggered stop orders
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The subscriber to notify
+    pub fn set_stop_listener<L: StopListener + 'static>(&mut self, listener: L) {
+        self.stop_listener = Some(Box::new(listener));
+    }
+
+    /// Move the stop orders triggered by the trades since the last call, or else by the
+    /// last trade, to the back of a work queue
+    ///
+    /// Buy stops are checked against the highest price traded and sell stops against the
+    /// lowest, so a sweep through a trigger price triggers the stop order even when its
+    /// last fill prints past it.
+    fn trigger_stops(&mut self, queue: &mut VecDeque<(Side, (Price, u64), Incoming)>) {
+        let last = self.last_trade.map(|last| (last.price, last.price));
+        let Some((low, high)) = self.traded.take().or(last) else {
+            return;
+        };
+        for side in [Side::Bid, Side::Ask] {
+            let stops = &mut self.stops[slot(side)];
+            let traded = match side {
+                Side::Bid => high,
+                Side::Ask => low,
+            };
+            let threshold = (key_price(side, traded), u64::MAX);
+            while let Some(entry) = stops.first_entry() {
+                if *entry.key() > threshold {
+                    break;
+                }
+                let (key, stop) = entry.remove_entry();
+                queue.push_back((side, key, stop));
+            }
+        }
+    }
+
+    /// Execute the stop orders triggered by trades, and those triggered in turn by their
+    /// executions, in trigger order
+    ///
+    /// The cascade is processed iteratively from a work queue: stop orders triggered by
+    /// the same trade run from the one closest to the market, buys first, and stop
+    /// orders they trigger run after them.
+    pub(crate) fn run_stops(&mut self) {
+        if self.stop_loc.is_empty() {
+            self.traded = None;
+            return;
+        }
+        let mut queue = VecDeque::new();
+        self.trigger_stops(&mut queue);
+        let mut triggered = 0;
+        while let Some((side, key, stop)) = queue.pop_front() {
+            if triggered == self.stop_cascade_limit {
+                self.stops[slot(side)].insert(key, stop);
+                continue;
+            }
+            triggered += 1;
+            self.stop_loc.remove(&stop.id);
+            let mut result = FillResult::new();
+            self.match_order(stop, &mut result);
+            if let Some(listener) = self.stop_listener.as_mut() {
+                listener.on_triggered(stop.id, &result);
+            }
+            if triggered < self.stop_cascade_limit {
+                self.trigger_stops(&mut queue);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderStatus;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_stop_cascade() {
+        let (tx, rx) = channel();
+        let mut book = OrderBook::new();
+        book.set_stop_listener(tx);
+        for price in 100..105 {
+            book.add(1, Side::Ask, price, 10).unwrap();
+        }
+        let far = book.add_stop(2, Side::Bid, 102, Price::MAX, 10);
+        let near = book.add_stop(3, Side::Bid, 101, Price::MAX, 10);
+        let sell = book.add_stop(4, Side::Ask, 90, 0, 10);
+        book.execute(5, Side::Bid, 100, 10);
+        assert!(rx.try_recv().is_err());
+
+        book.execute(5, Side::Bid, 101, 1);
+        let triggered: Vec<_> = rx.try_iter().collect();
+        assert_eq!(triggered.len(), 2);
+        assert_eq!(triggered[0].0, near);
+        assert_eq!(triggered[0].1.orders, vec![(101, 9), (102, 1)]);
+        assert_eq!(triggered[1].0, far);
+        assert_eq!(triggered[1].1.orders, vec![(102, 9), (103, 1)]);
+        assert!(triggered
+            .iter()
+            .all(|(_, r)| r.status == OrderStatus::Filled));
+        assert!(!book.cancel_stop(far));
+
+        book.add(1, Side::Ask, 102, 5).unwrap();
+        book.add(6, Side::Bid, 95, 10).unwrap();
+        let swept = book.add_stop(4, Side::Ask, 102, 0, 5);
+        book.execute(5, Side::Bid, 104, 20);
+        let triggered: Vec<_> = rx.try_iter().collect();
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].0, swept);
+        assert_eq!(triggered[0].1.orders, vec![(95, 5)]);
+        assert!(book.cancel_stop(sell));
+    }
+
+    #[test]
+    fn test_stop_cascade_limit() {
+        let mut book = OrderBook::new();
+        book.set_stop_cascade_limit(1);
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(1, Side::Ask, 101, 30).unwrap();
+        let first = book.add_stop(2, Side::Bid, 100, Price::MAX, 10);
+        let second = book.add_stop(3, Side::Bid, 100, Price::MAX, 10);
+        book.execute(4, Side::Bid, 100, 1);
+        assert!(!book.cancel_stop(first));
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 29);
+
+        book.execute(4, Side::Bid, 0, 1);
+        assert!(!book.cancel_stop(second));
+        assert_eq!(book.get_total_qty(Side::Ask, 101), 19);
+    }
+}
@@ -0,0 +1,16 @@
+/// What to do when an incoming order would trade against a resting order of its owner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StpPolicy {
+    /// Let orders of the same owner trade against each other
+    #[default]
+    Allow,
+
+    /// Cancel the resting order and keep matching the incoming order
+    CancelResting,
+
+    /// Cancel the rest of the incoming order
+    CancelIncoming,
+
+    /// Cancel both the resting order and the rest of the incoming order
+    CancelBoth,
+}
@@ -0,0 +1,331 @@
+use crate::{LevelUpdate, OrderQty, Price, Side, Snapshot};
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of every snapshot stream, versioning its encoding
+const MAGIC: &[u8; 4] = b"OBS2";
+
+/// Frame tag of a full snapshot
+const KEYFRAME: u8 = 0;
+
+/// Frame tag of the level updates of each side against the previous frame
+const DELTA: u8 = 1;
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Write an unsigned LEB128 varint
+fn write_varint<W: Write>(w: &mut W, mut value: u64) -> io::Result<()> {
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    w.write_all(&buf[..len])
+}
+
+/// Read an unsigned LEB128 varint, rejecting encodings of more than 64 bits
+fn read_varint<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        r.read_exact(&mut byte)?;
+        if shift == 63 && byte[0] & 0x7e != 0 {
+            return Err(invalid_data("varint overflow"));
+        }
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint overflow"))
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_levels<W: Write>(w: &mut W, levels: &[(Price, OrderQty)]) -> io::Result<()> {
+    write_varint(w, levels.len() as u64)?;
+    let mut prev = 0;
+    for (price, qty) in levels {
+        write_varint(w, zigzag(price.wrapping_sub(prev) as i64))?;
+        write_varint(w, *qty)?;
+        prev = *price;
+    }
+    Ok(())
+}
+
+fn read_levels<R: Read>(r: &mut R) -> io::Result<Vec<(Price, OrderQty)>> {
+    let len = read_varint(r)?;
+    let mut levels = Vec::new();
+    let mut prev: Price = 0;
+    for _ in 0..len {
+        let price = prev.wrapping_add(unzigzag(read_varint(r)?) as u64);
+        levels.push((price, read_varint(r)?));
+        prev = price;
+    }
+    Ok(levels)
+}
+
+/// Writes a sequence of timestamped snapshots as a delta-compressed stream
+///
+/// Every `keyframe_interval` frames a full snapshot (keyframe) is written, every other
+/// frame only records the levels that changed since the previous frame. All integers
+/// are varint encoded, prices and timestamps relative to the preceding value.
+#[derive(Debug)]
+pub struct StreamWriter<W: Write> {
+    /// Underlying writer
+    inner: W,
+
+    /// Number of frames between two keyframes
+    keyframe_interval: usize,
+
+    /// Number of frames written since the last keyframe
+    since_keyframe: usize,
+
+    /// Timestamp of the last frame written
+    last_ts: u64,
+
+    /// Last snapshot written
+    last: Snapshot,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Create a new stream writer, writing the stream header
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The writer to write the stream to
+    /// * `keyframe_interval` - The number of frames between two keyframes
+    ///
+    /// # Returns
+    ///
+    /// The stream writer, or an error if the header could not be written
+    pub fn new(mut inner: W, keyframe_interval: usize) -> io::Result<StreamWriter<W>> {
+        inner.write_all(MAGIC)?;
+        Ok(StreamWriter {
+            inner,
+            keyframe_interval: keyframe_interval.max(1),
+            since_keyframe: 0,
+            last_ts: 0,
+            last: Snapshot::default(),
+        })
+    }
+
+    /// Write a snapshot to the stream
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The timestamp of the snapshot, must not be lower than the previous one
+    /// * `snapshot` - The snapshot to write
+    ///
+    /// # Returns
+    ///
+    /// An error if the timestamp went backwards or the frame could not be written
+    pub fn write(&mut self, timestamp: u64, snapshot: &Snapshot) -> io::Result<()> {
+        if timestamp < self.last_ts {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "timestamp went backwards",
+            ));
+        }
+        let ts_delta = timestamp - self.last_ts;
+        if self.since_keyframe == 0 {
+            self.inner.write_all(&[KEYFRAME])?;
+            write_varint(&mut self.inner, ts_delta)?;
+            write_levels(&mut self.inner, &snapshot.bids)?;
+            write_levels(&mut self.inner, &snapshot.asks)?;
+        } else {
+            let updates = self.last.diff(snapshot);
+            self.inner.write_all(&[DELTA])?;
+            write_varint(&mut self.inner, ts_delta)?;
+            // Bid updates come first, so each side is written as its own list
+            let bids = updates.iter().take_while(|u| u.side == Side::Bid).count();
+            for side in [&updates[..bids], &updates[bids..]] {
+                let levels: Vec<_> = side.iter().map(|u| (u.price, u.qty)).collect();
+                write_levels(&mut self.inner, &levels)?;
+            }
+        }
+        self.since_keyframe = (self.since_keyframe + 1) % self.keyframe_interval;
+        self.last_ts = timestamp;
+        self.last.clone_from(snapshot);
+        Ok(())
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Consume the stream writer, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads back a stream produced by a [`StreamWriter`]
+///
+/// Iterating the reader yields every frame as a full `(timestamp, snapshot)` pair.
+#[derive(Debug)]
+pub struct StreamReader<R: Read> {
+    /// Underlying reader
+    inner: R,
+
+    /// Whether a keyframe has been read yet
+    synced: bool,
+
+    /// Timestamp of the last frame read
+    last_ts: u64,
+
+    /// Snapshot reconstructed from the frames read so far
+    current: Snapshot,
+}
+
+impl<R: Read> StreamReader<R> {
+    /// Create a new stream reader, checking the stream header
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader to read the stream from
+    ///
+    /// # Returns
+    ///
+    /// The stream reader, or an error if the header is missing or invalid
+    pub fn new(mut inner: R) -> io::Result<StreamReader<R>> {
+        let mut magic = [0u8; 4];
+        inner.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a snapshot stream"));
+        }
+        Ok(StreamReader {
+            inner,
+            synced: false,
+            last_ts: 0,
+            current: Snapshot::default(),
+        })
+    }
+
+    /// Read the next frame of the stream
+    ///
+    /// # Returns
+    ///
+    /// The timestamp and snapshot of the next frame, `None` at the end of the stream
+    pub fn next_frame(&mut self) -> io::Result<Option<(u64, &Snapshot)>> {
+        let mut tag = [0u8];
+        if self.inner.read(&mut tag)? == 0 {
+            return Ok(None);
+        }
+        let timestamp = self
+            .last_ts
+            .checked_add(read_varint(&mut self.inner)?)
+            .ok_or_else(|| invalid_data("timestamp overflow"))?;
+        match tag[0] {
+            KEYFRAME => {
+                self.current.bids = read_levels(&mut self.inner)?;
+                self.current.asks = read_levels(&mut self.inner)?;
+                self.synced = true;
+            }
+            DELTA if self.synced => {
+                for side in [Side::Bid, Side::Ask] {
+                    for (price, qty) in read_levels(&mut self.inner)? {
+                        self.current.apply(&LevelUpdate { side, price, qty });
+                    }
+                }
+            }
+            DELTA => return Err(invalid_data("delta frame before first keyframe")),
+            _ => return Err(invalid_data("unknown frame tag")),
+        }
+        self.last_ts = timestamp;
+        Ok(Some((timestamp, &self.current)))
+    }
+}
+
+impl<R: Read> Iterator for StreamReader<R> {
+    type Item = io::Result<(u64, Snapshot)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame()
+            .map(|frame| frame.map(|(ts, snapshot)| (ts, snapshot.clone())))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderBook;
+
+    #[test]
+    fn test_stream_roundtrip() {
+        let mut book = OrderBook::new();
+        let mut frames = Vec::new();
+        let mut ids = Vec::new();
+        for i in 0..20 {
+            ids.push(book.add(1, Side::Bid, 100 - i % 5, 10 + i).unwrap());
+            ids.push(book.add(1, Side::Ask, 101 + i % 7, 5 + i).unwrap());
+            if i % 3 == 0 {
+                book.cancel(ids[i as usize]);
+            }
+            frames.push((1_000 * i, book.snapshot()));
+        }
+
+        let mut writer = StreamWriter::new(Vec::new(), 8).unwrap();
+        for (ts, snapshot) in &frames {
+            writer.write(*ts, snapshot).unwrap();
+        }
+        let bytes = writer.into_inner();
+
+        let read: Vec<_> = StreamReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, frames);
+    }
+
+    #[test]
+    fn test_stream_rejects_garbage() {
+        assert!(StreamReader::new(&b"nope"[..]).is_err());
+        let mut reader = StreamReader::new(&b"OBS2\x01\x00\x00\x00"[..]).unwrap();
+        assert!(reader.next_frame().is_err());
+
+        let mut max = Vec::new();
+        write_varint(&mut max, u64::MAX).unwrap();
+        assert_eq!(read_varint(&mut max.as_slice()).unwrap(), u64::MAX);
+        *max.last_mut().unwrap() = 0x02;
+        assert!(read_varint(&mut max.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_stream_extreme_prices() {
+        let frames = [
+            (0, vec![(1 << 62, 5), (1, 5)], vec![(u64::MAX, 1)]),
+            (1, vec![(u64::MAX - 1, 5), (1, 7)], vec![(u64::MAX, 2)]),
+        ];
+        let frames: Vec<_> = (frames.into_iter())
+            .map(|(ts, bids, asks)| (ts, Snapshot { bids, asks }))
+            .collect();
+        let mut writer = StreamWriter::new(Vec::new(), 8).unwrap();
+        for (ts, snapshot) in &frames {
+            writer.write(*ts, snapshot).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let read: Vec<_> = StreamReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, frames);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{AuditEntry, AuditRecord, OrderId, OrderQty, OwnerId, Side, TradeId};
+use std::collections::{HashMap, VecDeque};
+
+/// Thresholds of the surveillance patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurveillanceConfig {
+    /// Longest time an order may rest before being canceled to count towards layering
+    pub flicker_lifetime: u64,
+
+    /// Window in which the counted cancels of an owner on one side add up
+    pub layering_window: u64,
+
+    /// Number of counted cancels within the window that raises a layering alert
+    pub layering_orders: usize,
+
+    /// Start and end times of the closing window, `None` to not check the close
+    pub close_window: Option<(u64, u64)>,
+
+    /// Share of the volume of the closing window, in percent, above which an owner
+    /// taking liquidity raises a marking-the-close alert
+    pub close_share: u64,
+}
+
+impl Default for SurveillanceConfig {
+    fn default() -> SurveillanceConfig {
+        SurveillanceConfig {
+            flicker_lifetime: 1_000_000,
+            layering_window: 1_000_000_000,
+            layering_orders: 5,
+            close_window: None,
+            close_share: 50,
+        }
+    }
+}
+
+/// Pattern flagged by the surveillance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alert {
+    /// Two accounts of the same owner group traded with each other
+    SelfMatch {
+        /// Time of the trade
+        time: u64,
+
+        /// Identifier of the trade
+        trade: TradeId,
+
+        /// Owner group of both accounts
+        group: u64,
+
+        /// Owner of the resting order
+        maker_owner: OwnerId,
+
+        /// Owner of the incoming order
+        taker_owner: OwnerId,
+    },
+
+    /// An owner repeatedly added and quickly canceled orders on one side
+    Layering {
+        /// Time of the cancel completing the pattern
+        time: u64,
+
+        /// Owner of the orders
+        owner: OwnerId,
+
+        /// Side of the orders
+        side: Side,
+
+        /// Number of quickly canceled orders within the window
+        orders: usize,
+    },
+
+    /// An owner took most of the liquidity traded in the closing window
+    MarkingTheClose {
+        /// End time of the closing window
+        time: u64,
+
+        /// Owner taking liquidity
+        owner: OwnerId,
+
+        /// Quantity the owner took in the window
+        qty: OrderQty,
+
+        /// Share of the volume of the window, in percent
+        share: u64,
+    },
+}
+
+/// Order known to the surveillance
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    /// Owner of the order
+    owner: OwnerId,
+
+    /// Side of the order
+    side: Side,
+
+    /// Time the order started resting, `None` while it was still matching
+    rested: Option<u64>,
+
+    /// Whether the order traded
+    filled: bool,
+
+    /// Quantity of the order left to trade
+    remaining: OrderQty,
+}
+
+/// Surveillance of the order and trade streams of an order book for wash trading and
+/// manipulation patterns
+///
+/// The surveillance consumes the audit trail, which carries both the orders and the
+/// trades of the order book with their times.
+#[derive(Debug, Clone, Default)]
+pub struct Surveillance {
+    /// Thresholds of the patterns
+    config: SurveillanceConfig,
+
+    /// Owner group of each account, accounts default to a group of their own
+    groups: HashMap<OwnerId, u64>,
+
+    /// Orders that may still rest
+    orders: HashMap<OrderId, Tracked>,
+
+    /// Times of the counted cancels of each owner and side, oldest first
+    cancels: HashMap<(OwnerId, Side), VecDeque<u64>>,
+
+    /// Quantity taken by each owner in the closing window
+    close_taken: HashMap<OwnerId, OrderQty>,
+
+    /// Volume traded in the closing window
+    close_volume: OrderQty,
+
+    /// Whether the closing window was checked
+    close_checked: bool,
+}
+
+impl Surveillance {
+    /// Create a new surveillance
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The thresholds of the patterns
+    pub fn new(config: SurveillanceConfig) -> Surveillance {
+        Surveillance {
+            config,
+            ..Surveillance::default()
+        }
+    }
+
+    /// Assign an account to an owner group
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The account
+    /// * `group` - The owner group, shared by accounts with the same beneficial owner
+    pub fn set_group(&mut self, owner: OwnerId, group: u64) {
+        self.groups.insert(owner, group);
+    }
+
+    /// Get the owner group of an account
+    fn group(&self, owner: OwnerId) -> u64 {
+        self.groups.get(&owner).copied().unwrap_or(owner)
+    }
+
+    /// Consume the next entry of the audit trail
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry to consume
+    ///
+    /// # Returns
+    ///
+    /// The alerts raised by the entry
+    pub fn on_entry(&mut self, entry: &AuditEntry) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let time = entry.time;
+        if let Some((_, end)) = self.config.close_window {
+            if time >= end {
+                alerts.extend(self.check_close());
+            }
+        }
+        match entry.record {
+            AuditRecord::Add {
+                id,
+                owner,
+                side,
+                qty,
+                ..
+            } => {
+                let tracked = Tracked {
+                    owner,
+                    side,
+                    rested: Some(time),
+                    filled: false,
+                    remaining: qty,
+                };
+                self.orders.insert(id, tracked);
+            }
+            AuditRecord::Execute {
+                id,
+                owner,
+                side,
+                qty,
+                ..
+            } => {
+                let tracked = Tracked {
+                    owner,
+                    side,
+                    rested: None,
+                    filled: false,
+                    remaining: qty,
+                };
+                self.orders.insert(id, tracked);
+            }
+            AuditRecord::Rested { id, qty } => {
+                if let Some(order) = self.orders.get_mut(&id) {
+                    order.rested = Some(time);
+                    order.remaining = qty;
+                }
+            }
+            AuditRecord::Fill(execution) => {
+                for id in [execution.maker, execution.taker] {
+                    if let Some(order) = self.orders.get_mut(&id) {
+                        order.filled = true;
+                        order.remaining = order.remaining.saturating_sub(execution.qty);
+                        if order.remaining == 0 {
+                            self.orders.remove(&id);
+                        }
+                    }
+                }
+                let group = self.group(execution.maker_owner);
+                if group == self.group(execution.taker_owner) {
+                    alerts.push(Alert::SelfMatch {
+                        time,
+                        trade: execution.trade,
+                        group,
+                        maker_owner: execution.maker_owner,
+                        taker_owner: execution.taker_owner,
+                    });
+                }
+                match self.config.close_window {
+                    Some((start, end)) if (start..end).contains(&time) => {
+                        *self.close_taken.entry(execution.taker_owner).or_default() +=
+                            execution.qty;
+                        self.close_volume += execution.qty;
+                    }
+                    _ => {}
+                }
+            }
+            AuditRecord::Canceled { id, .. } => {
+                if let Some(order) = self.orders.remove(&id) {
+                    alerts.extend(self.on_cancel(time, order));
+                }
+            }
+            AuditRecord::Transferred { id, owner } => {
+                if let Some(order) = self.orders.get_mut(&id) {
+                    order.owner = owner;
+                }
+            }
+            AuditRecord::Reduced { id, qty } | AuditRecord::Amended { id, qty, .. } => {
+                if let Some(order) = self.orders.get_mut(&id) {
+                    order.remaining = qty;
+                }
+            }
+            AuditRecord::Quote { .. } | AuditRecord::Bust { .. } => {}
+        }
+        alerts
+    }
+
+    /// Count a cancel towards layering
+    fn on_cancel(&mut self, time: u64, order: Tracked) -> Option<Alert> {
+        let rested = order.rested?;
+        if order.filled || time.saturating_sub(rested) > self.config.flicker_lifetime {
+            return None;
+        }
+        let cancels = self.cancels.entry((order.owner, order.side)).or_default();
+        cancels.push_back(time);
+        while let Some(&oldest) = cancels.front() {
+            if time.saturating_sub(oldest) <= self.config.layering_window {
+                break;
+            }
+            cancels.pop_front();
+        }
+        if cancels.len() < self.config.layering_orders.max(1) {
+            return None;
+        }
+        let orders = cancels.len();
+        cancels.clear();
+        Some(Alert::Layering {
+            time,
+            owner: order.owner,
+            side: order.side,
+            orders,
+        })
+    }
+
+    /// Check the closing window once it ended
+    ///
+    /// This happens on the first entry at or after the end of the window, call it
+    /// directly if no entry follows the close.
+    ///
+    /// # Returns
+    ///
+    /// The marking-the-close alerts, raised once
+    pub fn check_close(&mut self) -> Vec<Alert> {
+        let Some((_, end)) = self.config.close_window else {
+            return Vec::new();
+        };
+        if self.close_checked || self.close_volume == 0 {
+            return Vec::new();
+        }
+        self.close_checked = true;
+        let mut alerts: Vec<_> = (self.close_taken.iter())
+            .map(|(&owner, &qty)| (owner, qty, qty * 100 / self.close_volume))
+            .filter(|&(_, _, share)| share > self.config.close_share)
+            .map(|(owner, qty, share)| Alert::MarkingTheClose {
+                time: end,
+                owner,
+                qty,
+                share,
+            })
+            .collect();
+        alerts.sort_by_key(|alert| match alert {
+            Alert::MarkingTheClose { owner, .. } => *owner,
+            _ => 0,
+        });
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ManualClock, OrderBook};
+
+    #[test]
+    fn test_surveillance() {
+        let clock = ManualClock::new(0);
+        let mut book = OrderBook::builder().clock(clock.clone()).audit().build();
+        let mut surveillance = Surveillance::new(SurveillanceConfig {
+            flicker_lifetime: 10,
+            layering_window: 100,
+            layering_orders: 3,
+            close_window: Some((1_000, 2_000)),
+            close_share: 50,
+        });
+        surveillance.set_group(1, 7);
+        surveillance.set_group(2, 7);
+
+        for _ in 0..3 {
+            let id = book.add(3, Side::Ask, 110, 10).unwrap();
+            clock.advance(5);
+            book.cancel(id);
+        }
+        book.add(1, Side::Ask, 100, 10).unwrap();
+        book.execute(2, Side::Bid, 100, 5);
+        clock.set(1_500);
+        book.execute(4, Side::Bid, 100, 4);
+        book.execute(2, Side::Bid, 100, 1);
+        clock.set(2_000);
+        book.add(5, Side::Bid, 90, 1).unwrap();
+
+        let alerts: Vec<_> = (book.audit().unwrap().entries().iter())
+            .flat_map(|entry| surveillance.on_entry(entry))
+            .collect();
+        assert_eq!(alerts.len(), 4);
+        assert!(matches!(
+            alerts[0],
+            Alert::Layering {
+                owner: 3,
+                side: Side::Ask,
+                orders: 3,
+                ..
+            }
+        ));
+        assert!(matches!(alerts[1], Alert::SelfMatch { group: 7, .. }));
+        assert!(matches!(alerts[2], Alert::SelfMatch { time: 1_500, .. }));
+        assert_eq!(
+            alerts[3],
+            Alert::MarkingTheClose {
+                time: 2_000,
+                owner: 4,
+                qty: 4,
+                share: 80,
+            }
+        );
+        assert!(surveillance.check_close().is_empty());
+        assert_eq!(surveillance.orders.len(), 1);
+    }
+}
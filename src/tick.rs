@@ -0,0 +1,112 @@
+use crate::{Execution, OrderBook, OrderQty, Price, TradeId};
+
+/// Direction of the price of a trade relative to the trade before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TickDirection {
+    /// Traded above the previous trade
+    Up,
+
+    /// Traded below the previous trade
+    Down,
+
+    /// Traded at the price of the previous trade, or first trade
+    #[default]
+    Zero,
+}
+
+impl TickDirection {
+    /// Get the direction of a trade price relative to the last trade
+    ///
+    /// # Arguments
+    ///
+    /// * `last` - The last trade, if any
+    /// * `price` - The price of the new trade
+    pub(crate) fn of(last: Option<LastTrade>, price: Price) -> TickDirection {
+        match last {
+            Some(last) if price > last.price => TickDirection::Up,
+            Some(last) if price < last.price => TickDirection::Down,
+            _ => TickDirection::Zero,
+        }
+    }
+}
+
+/// Last trade of an order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastTrade {
+    /// Identifier of the trade
+    pub trade: TradeId,
+
+    /// Price of the trade
+    pub price: Price,
+
+    /// Quantity of the trade
+    pub qty: OrderQty,
+
+    /// Direction of the price relative to the trade before it
+    pub tick: TickDirection,
+}
+
+impl LastTrade {
+    /// Get the last trade of an execution
+    pub(crate) fn of(execution: &Execution) -> LastTrade {
+        LastTrade {
+            trade: execution.trade,
+            price: execution.price,
+            qty: execution.qty,
+            tick: execution.tick,
+        }
+    }
+}
+
+impl OrderBook {
+    /// Get the last trade that was not busted
+    ///
+    /// # Returns
+    ///
+    /// The last trade, `None` if nothing traded yet
+    pub fn last_trade(&self) -> Option<LastTrade> {
+        self.last_trade
+    }
+
+    /// Get the total quantity traded, excluding busted trades
+    pub fn traded_volume(&self) -> OrderQty {
+        self.volume
+    }
+
+    /// Get the direction of the price of the last trade
+    ///
+    /// # Returns
+    ///
+    /// The tick direction, zero if nothing traded yet
+    pub fn tick_direction(&self) -> TickDirection {
+        self.last_trade.map(|t| t.tick).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_tick_direction() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Ask, 100, 5).unwrap();
+        book.add(1, Side::Ask, 101, 5).unwrap();
+        book.add(1, Side::Bid, 99, 5).unwrap();
+        assert_eq!(book.last_trade(), None);
+
+        book.execute(2, Side::Bid, 101, 7);
+        let last = book.last_trade().unwrap();
+        assert_eq!(
+            (last.price, last.qty, last.tick),
+            (101, 2, TickDirection::Up)
+        );
+        book.execute(2, Side::Bid, 101, 1);
+        assert_eq!(book.tick_direction(), TickDirection::Zero);
+        let result = book.execute(2, Side::Ask, 99, 1);
+        assert_eq!(book.tick_direction(), TickDirection::Down);
+        assert_eq!(book.bbo().last, book.last_trade());
+        assert_eq!(result.status, crate::OrderStatus::Filled);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::audit::AuditRecord;
+use crate::{OrderBook, OrderId, OwnerId, Side};
+
+impl OrderBook {
+    /// Transfer a resting order to another owner, keeping its place in the queue
+    ///
+    /// This supports give-up and allocation workflows. The order counts as an order
+    /// of its new owner from then on, for self-trade prevention and mass cancels alike,
+    /// and leaves the quote of its previous owner if it was part of one. The drop-copy
+    /// sink is told of the ownership change.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The unique identifier of the order
+    /// * `owner` - The new owner of the order
+    ///
+    /// # Returns
+    ///
+    /// The previous owner of the order, `None` if the order is not resting in the order
+    /// book
+    pub fn transfer(&mut self, id: OrderId, owner: OwnerId) -> Option<OwnerId> {
+        let (side, idx) = *self.order_loc.get(&id)?;
+        let book = match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        };
+        let order = book.price_levels[idx].iter_mut().find(|o| o.id == id)?;
+        let previous = order.owner;
+        if previous == owner {
+            return Some(previous);
+        }
+        order.owner = owner;
+
+        if let Some(ids) = self.owner_orders.get_mut(&previous) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.owner_orders.remove(&previous);
+            }
+        }
+        self.owner_orders.entry(owner).or_default().insert(id);
+        if let Some(quote) = self.quotes.get_mut(&previous) {
+            for leg in [&mut quote.bid, &mut quote.ask] {
+                if *leg == Some(id) {
+                    *leg = None;
+                }
+            }
+        }
+
+        self.record(AuditRecord::Transferred { id, owner });
+        if let Some(sink) = self.drop_copy.as_mut() {
+            sink.on_transfer(id, previous, owner);
+        }
+        Some(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Execution, ExecutionSink, StpPolicy};
+    use std::sync::mpsc::{channel, Sender};
+
+    /// Forwards ownership changes over a channel
+    #[derive(Debug)]
+    struct Transfers(Sender<(OrderId, OwnerId, OwnerId)>);
+
+    impl ExecutionSink for Transfers {
+        fn on_execution(&mut self, _execution: &Execution) {}
+
+        fn on_transfer(&mut self, id: OrderId, from: OwnerId, to: OwnerId) {
+            let _ = self.0.send((id, from, to));
+        }
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut book = OrderBook::builder().stp(StpPolicy::CancelResting).build();
+        let (tx, rx) = channel();
+        book.set_drop_copy(Transfers(tx));
+        let order = book.add(1, Side::Ask, 100, 10).unwrap();
+        book.add(3, Side::Ask, 100, 10).unwrap();
+
+        assert_eq!(book.transfer(order, 2), Some(1));
+        assert_eq!(rx.try_recv(), Ok((order, 1, 2)));
+        assert_eq!(book.queue_position(order), Some((0, 0)));
+        assert_eq!(book.cancel_all(1), 0);
+
+        let result = book.execute(1, Side::Bid, 100, 5);
+        assert_eq!(result.orders, [(100, 5)]);
+        assert_eq!(book.order(order).unwrap().qty, 5);
+        book.execute(2, Side::Bid, 100, 5);
+        assert!(book.order(order).is_none());
+        assert_eq!(book.get_total_qty(Side::Ask, 100), 5);
+        assert_eq!(book.transfer(order, 1), None);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{HalfBook, LevelStats, OrderBook, OrderQty, Price};
+
+/// Read-only view of a price level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelView {
+    /// Rank of the level, zero for the best level
+    pub rank: usize,
+
+    /// Price of the level
+    pub price: Price,
+
+    /// Total quantity resting at the level
+    pub qty: OrderQty,
+
+    /// Number of orders resting at the level
+    pub orders: usize,
+}
+
+impl HalfBook {
+    /// Get the position in prices of the level of a rank
+    fn rank_pos(&self, rank: usize) -> Option<usize> {
+        (rank < self.prices.len()).then(|| self.prices.len() - 1 - rank)
+    }
+
+    /// Get a view of the level at a position in prices
+    fn view(&self, pos: usize) -> LevelView {
+        LevelView {
+            rank: self.prices.len() - 1 - pos,
+            price: self.prices[pos],
+            qty: self.qtys[pos],
+            orders: self.price_levels[self.queues[pos]].len(),
+        }
+    }
+
+    /// Get a view of the level of a rank
+    fn level(&self, rank: usize) -> Option<LevelView> {
+        self.rank_pos(rank).map(|pos| self.view(pos))
+    }
+
+    /// Get a view of the level at a price
+    fn level_at(&self, price: Price) -> Option<LevelView> {
+        self.position(price).ok().map(|pos| self.view(pos))
+    }
+
+    /// Iterate over views of the levels, best first
+    fn views(&self) -> impl Iterator<Item = LevelView> + '_ {
+        (0..self.prices.len()).rev().map(|pos| self.view(pos))
+    }
+}
+
+/// Read-only view of the bid side of an order book, levels ranked from the highest
+/// price
+#[derive(Debug, Clone, Copy)]
+pub struct BidBookView<'a> {
+    /// Bid side of the order book
+    half: &'a HalfBook,
+}
+
+/// Read-only view of the ask side of an order book, levels ranked from the lowest
+/// price
+#[derive(Debug, Clone, Copy)]
+pub struct AskBookView<'a> {
+    /// Ask side of the order book
+    half: &'a HalfBook,
+}
+
+impl<'a> BidBookView<'a> {
+    /// Get the number of non-empty levels
+    pub fn len(&self) -> usize {
+        self.half.prices.len()
+    }
+
+    /// Check whether no bid rests in the order book
+    pub fn is_empty(&self) -> bool {
+        self.half.prices.is_empty()
+    }
+
+    /// Get the best bid level
+    pub fn best(&self) -> Option<LevelView> {
+        self.half.level(0)
+    }
+
+    /// Get a level by rank
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the level, zero for the highest bid
+    ///
+    /// # Returns
+    ///
+    /// The view of the level, `None` if there are not that many levels
+    pub fn level(&self, rank: usize) -> Option<LevelView> {
+        self.half.level(rank)
+    }
+
+    /// Get a level by price
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the level
+    ///
+    /// # Returns
+    ///
+    /// The view of the level, `None` if no bid rests at the price
+    pub fn get(&self, price: Price) -> Option<LevelView> {
+        self.half.level_at(price)
+    }
+
+    /// Get the statistics of the orders of a level by rank
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the level, zero for the highest bid
+    pub fn stats(&self, rank: usize) -> Option<LevelStats> {
+        self.half.rank_pos(rank).map(|pos| self.half.stats(pos))
+    }
+
+    /// Iterate over the levels, highest price first
+    pub fn iter(&self) -> impl Iterator<Item = LevelView> + 'a {
+        self.half.views()
+    }
+}
+
+impl<'a> AskBookView<'a> {
+    /// Get the number of non-empty levels
+    pub fn len(&self) -> usize {
+        self.half.prices.len()
+    }
+
+    /// Check whether no ask rests in the order book
+    pub fn is_empty(&self) -> bool {
+        self.half.prices.is_empty()
+    }
+
+    /// Get the best ask level
+    pub fn best(&self) -> Option<LevelView> {
+        self.half.level(0)
+    }
+
+    /// Get a level by rank
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the level, zero for the lowest ask
+    ///
+    /// # Returns
+    ///
+    /// The view of the level, `None` if there are not that many levels
+    pub fn level(&self, rank: usize) -> Option<LevelView> {
+        self.half.level(rank)
+    }
+
+    /// Get a level by price
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the level
+    ///
+    /// # Returns
+    ///
+    /// The view of the level, `None` if no ask rests at the price
+    pub fn get(&self, price: Price) -> Option<LevelView> {
+        self.half.level_at(price)
+    }
+
+    /// Get the statistics of the orders of a level by rank
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the level, zero for the lowest ask
+    pub fn stats(&self, rank: usize) -> Option<LevelStats> {
+        self.half.rank_pos(rank).map(|pos| self.half.stats(pos))
+    }
+
+    /// Iterate over the levels, lowest price first
+    pub fn iter(&self) -> impl Iterator<Item = LevelView> + 'a {
+        self.half.views()
+    }
+}
+
+impl OrderBook {
+    /// Get a read-only view of the bids
+    pub fn bid_view(&self) -> BidBookView<'_> {
+        BidBookView { half: &self.bids }
+    }
+
+    /// Get a read-only view of the asks
+    pub fn ask_view(&self) -> AskBookView<'_> {
+        AskBookView { half: &self.asks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_book_views() {
+        let mut book = OrderBook::new();
+        book.add(1, Side::Bid, 99, 10).unwrap();
+        book.add(1, Side::Bid, 100, 5).unwrap();
+        book.add(2, Side::Bid, 100, 7).unwrap();
+        book.add(1, Side::Ask, 102, 3).unwrap();
+
+        let bids = book.bid_view();
+        assert_eq!(bids.len(), 2);
+        let best = bids.best().unwrap();
+        assert_eq!(
+            (best.rank, best.price, best.qty, best.orders),
+            (0, 100, 12, 2)
+        );
+        assert_eq!(bids.level(1), bids.get(99));
+        assert_eq!(bids.get(99).unwrap().rank, 1);
+        assert!(bids.level(2).is_none() && bids.get(101).is_none());
+        assert_eq!(bids.stats(0).unwrap().max_size, 7);
+        let prices: Vec<_> = bids.iter().map(|l| l.price).collect();
+        assert_eq!(prices, vec![100, 99]);
+
+        let asks = book.ask_view();
+        assert_eq!(asks.best().map(|l| l.price), Some(102));
+        assert!(!asks.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{AmendResult, Command, CommandResult, OrderBook, OwnerId};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::time::Instant;
+
+thread_local! {
+    /// Number of allocations made by the current thread
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// System allocator counting the allocations made by each thread
+///
+/// Install it as the global allocator of a binary for the watchdog to report
+/// allocation counts, which are zero otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Get the number of allocations made by the current thread through the counting
+/// allocator
+pub fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Diagnostics of a command that exceeded the latency budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowCommand {
+    /// The command
+    pub command: Command,
+
+    /// Time taken to apply the command, in nanoseconds
+    pub elapsed: u64,
+
+    /// Number of price levels the command touched
+    pub levels: usize,
+
+    /// Number of allocations made while applying the command
+    pub allocations: usize,
+}
+
+/// Watchdog measuring each applied command against a latency budget
+///
+/// Commands are timed with the monotonic clock of the system, whatever the clock of
+/// the order book, and the slow ones are reported with enough context to find the
+/// inputs that trigger them.
+#[derive(Debug, Clone, Default)]
+pub struct Watchdog {
+    /// Latency budget of a command, in nanoseconds
+    budget: u64,
+
+    /// Number of commands measured
+    commands: u64,
+
+    /// Number of commands that exceeded the budget
+    slow: u64,
+}
+
+/// Count the distinct price levels holding the resting orders of an owner
+fn owner_levels(book: &OrderBook, owner: OwnerId) -> usize {
+    let Some(ids) = book.owner_orders.get(&owner) else {
+        return 0;
+    };
+    let levels: HashSet<_> = (ids.iter())
+        .filter_map(|id| book.order_loc.get(id))
+        .collect();
+    levels.len()
+}
+
+/// Count the price levels a command will touch should it succeed, for the commands
+/// whose result does not tell
+fn levels_before(book: &OrderBook, command: &Command) -> usize {
+    match *command {
+        Command::CancelAll { owner } => owner_levels(book, owner),
+        Command::Amend { id, price, .. } => match book.order(id) {
+            Some(order) if order.price != price => 2,
+            _ => 1,
+        },
+        Command::Open => match book.indicative() {
+            Some(indicative) => {
+                let bids = book.bids.prices.iter().filter(|&&p| p >= indicative.price);
+                let asks = book.asks.prices.iter().filter(|&&p| p <= indicative.price);
+                bids.count() + asks.count()
+            }
+            None => 0,
+        },
+        _ => 0,
+    }
+}
+
+/// Count the price levels touched by a command from its result
+///
+/// A cancel of all the orders of an owner, an amend or an auction touches the levels
+/// counted before it applied, and a quote the levels its sides rest at. Stop orders and
+/// entering pre-open touch no level by themselves.
+fn levels_touched(result: &CommandResult, before: usize) -> usize {
+    match result {
+        CommandResult::Added(_) => 1,
+        CommandResult::Rejected(_) => 0,
+        CommandResult::Executed(fill) => {
+            let swept: HashSet<_> = fill.orders.iter().map(|&(price, _)| price).collect();
+            swept.len() + usize::from(fill.id.is_some())
+        }
+        CommandResult::Canceled(_) => 1,
+        CommandResult::CanceledAll(_) => before,
+        CommandResult::Amended(AmendResult::Amended(_)) => before,
+        CommandResult::Amended(_) => 0,
+        CommandResult::Reduced(left) => usize::from(left.is_some()),
+        CommandResult::Transferred(previous) => usize::from(previous.is_some()),
+        CommandResult::Quoted(quote) => {
+            usize::from(quote.bid.is_some()) + usize::from(quote.ask.is_some())
+        }
+        CommandResult::Busted(busted) => usize::from(busted.is_ok()),
+        CommandResult::Opened(executions) => match executions.is_empty() {
+            true => 0,
+            false => before,
+        },
+        CommandResult::StopAdded(_) | CommandResult::StopCanceled(_) | CommandResult::PreOpened => {
+            0
+        }
+    }
+}
+
+impl Watchdog {
+    /// Create a new watchdog
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The latency budget of a command, in nanoseconds
+    pub fn new(budget: u64) -> Watchdog {
+        Watchdog {
+            budget,
+            ..Watchdog::default()
+        }
+    }
+
+    /// Get the number of commands measured and the number that exceeded the budget
+    pub fn counts(&self) -> (u64, u64) {
+        (self.commands, self.slow)
+    }
+
+    /// Apply a command to an order book, measuring its latency
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book to apply the command to
+    /// * `command` - The command to apply
+    ///
+    /// # Returns
+    ///
+    /// The result of the command, and its diagnostics if it exceeded the budget
+    pub fn apply(
+        &mut self,
+        book: &mut OrderBook,
+        command: &Command,
+    ) -> (CommandResult, Option<SlowCommand>) {
+        let before = levels_before(book, command);
+        let allocated = allocations();
+        let start = Instant::now();
+        let result = command.apply(book);
+        let elapsed = start.elapsed().as_nanos() as u64;
+        let allocations = allocations() - allocated;
+        self.commands += 1;
+        if elapsed <= self.budget {
+            return (result, None);
+        }
+        self.slow += 1;
+        let slow = SlowCommand {
+            command: *command,
+            elapsed,
+            levels: levels_touched(&result, before),
+            allocations,
+        };
+        (result, Some(slow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn test_watchdog() {
+        let mut book = OrderBook::new();
+        for price in 100..105 {
+            book.add(1, Side::Ask, price, 10).unwrap();
+        }
+        let sweep = Command::Execute {
+            owner: 2,
+            side: Side::Bid,
+            price: 102,
+            qty: 35,
+            tag: 0,
+        };
+        let add = Command::Add {
+            owner: 1,
+            side: Side::Ask,
+            price: 105,
+            qty: 10,
+            tag: 0,
+        };
+        let mut watchdog = Watchdog::new(u64::MAX);
+        assert_eq!(watchdog.apply(&mut book, &add).1, None);
+
+        let mut watchdog = Watchdog::new(0);
+        let (_, slow) = watchdog.apply(&mut book, &sweep);
+        let slow = slow.unwrap();
+        assert_eq!(slow.command, sweep);
+        assert_eq!(slow.levels, 4);
+        assert!(slow.elapsed > 0);
+        assert!(slow.allocations > 0);
+        assert_eq!(watchdog.counts(), (1, 1));
+
+        book.add(1, Side::Ask, 105, 10).unwrap();
+        let (_, slow) = watchdog.apply(&mut book, &Command::CancelAll { owner: 1 });
+        assert_eq!(slow.unwrap().levels, 3);
+    }
+}